@@ -1,7 +1,11 @@
 use sqlx::{PgPool, Error as SqlxError};
 
+// แต่ละไฟล์ห่อ API ไว้ใน module ชื่อเดียวกัน (เช่น `pool::pool::create_pool`)
+#[allow(clippy::module_inception)]
 pub mod pool;
+#[allow(clippy::module_inception)]
 pub mod config;
+#[allow(clippy::module_inception)]
 pub mod health;
 pub mod init;
 pub mod api;
@@ -1,7 +1,27 @@
-use axum::http::StatusCode;
+use axum::http::{header, HeaderValue, StatusCode};
 use axum::response::{IntoResponse, Response};
-use axum::Json;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sqlx::error::ErrorKind;
 use thiserror::Error;
+use uuid::Uuid;
+
+use crate::middlewares::request_id;
+
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// One violated rule, addressed by the JSON path of the offending field.
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        FieldError { field: field.into(), message: message.into() }
+    }
+}
 
 #[derive(Debug, Error)]
 #[allow(dead_code)]
@@ -12,27 +32,169 @@ pub enum AppError {
     NotFound,
     #[error("Invalid input: {0}")]
     ValidationError(String),
+    #[error("Unknown category ids: {0:?}")]
+    UnknownCategories(Vec<i32>),
+    #[error("Unknown product ids: {0:?}")]
+    UnknownProducts(Vec<Uuid>),
+    #[error("Validation failed: {} field error(s)", .0.len())]
+    InvalidFields(Vec<FieldError>),
+    #[error("Unauthorized")]
+    Unauthorized,
+    #[error("Forbidden")]
+    Forbidden,
+    #[error("Conflict: {0}")]
+    Conflict(String),
+    #[error("Precondition failed: {0}")]
+    PreconditionFailed(String),
+    #[error("Rate limited")]
+    RateLimited { retry_after_secs: Option<u64> },
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
 }
 
-impl IntoResponse for AppError {
+/*
+Error body ตาม RFC 9457 (application/problem+json)
+ - `code`: ค่าคงที่ที่ client ใช้แยกประเภท error ได้ (เช่น `not_found`, `conflict`)
+ - `details`: รายการ error ราย field (ถ้ามี)
+ - `request_id`: ตรงกับ header `x-request-id` ของ response
+*/
+#[derive(Debug, Clone, Serialize)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    pub code: String,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<FieldError>,
+    pub request_id: Option<String>,
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+impl ProblemDetails {
+    pub fn new(status: StatusCode, code: &str, detail: impl Into<String>) -> Self {
+        ProblemDetails {
+            problem_type: format!("urn:snapmymeal:error:{}", code),
+            title: status.canonical_reason().unwrap_or("Error").to_string(),
+            status: status.as_u16(),
+            code: code.to_string(),
+            detail: detail.into(),
+            details: Vec::new(),
+            request_id: request_id::current(),
+            extensions: Map::new(),
+        }
+    }
+
+    pub fn with_details(mut self, details: Vec<FieldError>) -> Self {
+        self.details = details;
+        self
+    }
+
+    pub fn with_extension(mut self, key: &str, value: Value) -> Self {
+        self.extensions.insert(key.to_string(), value);
+        self
+    }
+}
+
+impl IntoResponse for ProblemDetails {
     fn into_response(self) -> Response {
-        let (status, message) = match self {
-            AppError::DatabaseError(_) => {
-                // ไม่ expose database error details ให้ client
-                (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error".to_string())
-            },
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let body = serde_json::to_vec(&self).unwrap_or_default();
+        (status, [(header::CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON))], body).into_response()
+    }
+}
+
+// แปลง sqlx error เป็น problem โดยไม่ expose รายละเอียดของ database ให้ client
+fn database_problem(error: &sqlx::Error) -> ProblemDetails {
+    match error {
+        sqlx::Error::Database(db_err) => match db_err.kind() {
+            ErrorKind::UniqueViolation => ProblemDetails::new(
+                StatusCode::CONFLICT, "conflict", "A resource with the same unique value already exists"),
+            // ลบแถวที่ยังถูกอ้างอิงอยู่คือ conflict ของสถานะ; repository แปลงเป็น `AppError::Conflict` เอง
+            ErrorKind::ForeignKeyViolation => ProblemDetails::new(
+                StatusCode::UNPROCESSABLE_ENTITY, "reference_violation", "The request references a resource that does not exist"),
+            ErrorKind::CheckViolation | ErrorKind::NotNullViolation => ProblemDetails::new(
+                StatusCode::UNPROCESSABLE_ENTITY, "constraint_violation", "The request violates a data constraint"),
+            _ => internal_problem(),
+        },
+        sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed => ProblemDetails::new(
+            StatusCode::SERVICE_UNAVAILABLE, "service_unavailable", "The database is temporarily unavailable"),
+        _ => internal_problem(),
+    }
+}
+
+fn internal_problem() -> ProblemDetails {
+    ProblemDetails::new(StatusCode::INTERNAL_SERVER_ERROR, "internal_error", "Internal server error")
+}
+
+impl AppError {
+    pub fn to_problem(&self) -> ProblemDetails {
+        match self {
+            AppError::DatabaseError(e) => database_problem(e),
             AppError::NotFound => {
-                (StatusCode::NOT_FOUND, "Resource not found".to_string())
+                ProblemDetails::new(StatusCode::NOT_FOUND, "not_found", "Resource not found")
             },
             AppError::ValidationError(msg) => {
-                (StatusCode::BAD_REQUEST, msg)
+                ProblemDetails::new(StatusCode::BAD_REQUEST, "invalid_input", msg.clone())
+            },
+            AppError::UnknownCategories(ids) => {
+                let details = ids.iter()
+                    .map(|id| FieldError::new("categories_ids", format!("category {} does not exist", id)))
+                    .collect();
+                ProblemDetails::new(StatusCode::UNPROCESSABLE_ENTITY, "unknown_categories", "One or more categories do not exist")
+                    .with_details(details)
+                    .with_extension("invalid_category_ids", serde_json::json!(ids))
+            },
+            AppError::UnknownProducts(ids) => {
+                let details = ids.iter()
+                    .map(|id| FieldError::new("product_ids", format!("product {} does not exist", id)))
+                    .collect();
+                ProblemDetails::new(StatusCode::NOT_FOUND, "not_found", "One or more products do not exist")
+                    .with_details(details)
+                    .with_extension("missing_product_ids", serde_json::json!(ids))
+            },
+            AppError::InvalidFields(details) => {
+                ProblemDetails::new(StatusCode::UNPROCESSABLE_ENTITY, "validation_failed", "Validation failed")
+                    .with_details(details.clone())
+            },
+            AppError::Unauthorized => {
+                ProblemDetails::new(StatusCode::UNAUTHORIZED, "unauthorized", "Authentication is required")
+            },
+            AppError::Forbidden => {
+                ProblemDetails::new(StatusCode::FORBIDDEN, "forbidden", "You are not allowed to perform this action")
+            },
+            AppError::Conflict(msg) => {
+                ProblemDetails::new(StatusCode::CONFLICT, "conflict", msg.clone())
             },
-        };
-        
-        let body = Json(serde_json::json!({
-            "error": message
-        }));
-        
-        (status, body).into_response()
+            AppError::PreconditionFailed(msg) => {
+                ProblemDetails::new(StatusCode::PRECONDITION_FAILED, "precondition_failed", msg.clone())
+            },
+            AppError::RateLimited { retry_after_secs } => {
+                let problem = ProblemDetails::new(StatusCode::TOO_MANY_REQUESTS, "rate_limited", "Too many requests");
+                match retry_after_secs {
+                    Some(secs) => problem.with_extension("retry_after_secs", serde_json::json!(secs)),
+                    None => problem,
+                }
+            },
+            AppError::ServiceUnavailable(msg) => {
+                ProblemDetails::new(StatusCode::SERVICE_UNAVAILABLE, "service_unavailable", msg.clone())
+            },
+        }
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        if let AppError::DatabaseError(e) = &self {
+            tracing::error!("Database error ({:?}): {:?}", request_id::current(), e);
+        }
+
+        let mut response = self.to_problem().into_response();
+        if let AppError::RateLimited { retry_after_secs: Some(secs) } = self {
+            response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(secs));
+        }
+        response
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,34 @@
+use axum::{extract::State, http::StatusCode, Json};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use crate::{
+    errors::AppError,
+    models::ingredients::{Additive, AdditiveForm},
+    repositories::{additive_repositories::AdditiveRepository, product_repositories::ProductRepository},
+    services::nova::{AdditiveService, AdditiveServiceTrait},
+};
+
+
+fn create_additive_service(pool: Arc<PgPool>) -> AdditiveService {
+    let repo = Arc::new(AdditiveRepository::new(pool.clone()));
+    let products = Arc::new(ProductRepository::new(pool));
+    AdditiveService::new(repo, products)
+}
+
+pub async fn get_additive_list(
+    State(pool): State<Arc<PgPool>>,
+) -> Result<(StatusCode, Json<Vec<Additive>>), AppError> {
+    let service = create_additive_service(pool);
+    let additives = service.list_additives().await?;
+    Ok((StatusCode::OK, Json(additives)))
+}
+
+pub async fn add_additive(
+    State(pool): State<Arc<PgPool>>,
+    Json(payload): Json<AdditiveForm>,
+) -> Result<(StatusCode, Json<Additive>), AppError> {
+    let service = create_additive_service(pool);
+    let additive = service.add_additive(payload).await?;
+    Ok((StatusCode::CREATED, Json(additive)))
+}
@@ -0,0 +1,27 @@
+use axum::{extract::{Path, Query, State}, http::StatusCode, Json};
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    errors::AppError,
+    models::alternatives::{AlternativesQuery, ProductAlternatives},
+    repositories::product_repositories::ProductRepository,
+    services::alternatives::{AlternativesService, AlternativesServiceTrait},
+};
+
+
+fn create_alternatives_service(pool: Arc<PgPool>) -> AlternativesService {
+    let products = Arc::new(ProductRepository::new(pool));
+    AlternativesService::new(products)
+}
+
+pub async fn get_product_alternatives(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<AlternativesQuery>,
+) -> Result<(StatusCode, Json<ProductAlternatives>), AppError> {
+    let service = create_alternatives_service(pool);
+    let alternatives = service.alternatives_for_product(id, query).await?.ok_or(AppError::NotFound)?;
+    Ok((StatusCode::OK, Json(alternatives)))
+}
@@ -0,0 +1,61 @@
+use axum::{extract::{Path, State}, http::StatusCode, Json};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use crate::{
+    errors::AppError,
+    models::categories::{Categories, CategoriesForm, CategoriesWithProductsResponse},
+    repositories::category_repositories::CategoryRepository,
+    services::category_service::{self, CategoryServiceTrait}
+};
+
+
+fn create_category_service(pool: Arc<PgPool>) -> category_service::CategoryService {
+    let repo = Arc::new(CategoryRepository::new(pool));
+    category_service::CategoryService::new(repo)
+}
+
+pub async fn get_category_list(
+    State(pool): State<Arc<PgPool>>,
+) -> Result<(StatusCode, Json<Vec<Categories>>), AppError> {
+    let service = create_category_service(pool);
+    let categories = service.list_categories().await?;
+    Ok((StatusCode::OK, Json(categories)))
+}
+
+pub async fn add_category(
+    State(pool): State<Arc<PgPool>>,
+    Json(payload): Json<CategoriesForm>
+) -> Result<(StatusCode, Json<Categories>), AppError> {
+    let service = create_category_service(pool);
+    let new_category = service.add_category(payload).await?;
+    Ok((StatusCode::CREATED, Json(new_category)))
+}
+
+pub async fn get_category_with_products(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<i32>,
+) -> Result<(StatusCode, Json<CategoriesWithProductsResponse>), AppError> {
+    let service = create_category_service(pool);
+    let response = service.get_category_with_products(id).await?.ok_or(AppError::NotFound)?;
+    Ok((StatusCode::OK, Json(response)))
+}
+
+pub async fn rename_category_with_id(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<i32>,
+    Json(payload): Json<CategoriesForm>
+) -> Result<(StatusCode, Json<Categories>), AppError> {
+    let service = create_category_service(pool);
+    let category = service.rename_category_from_id(id, payload).await?.ok_or(AppError::NotFound)?;
+    Ok((StatusCode::OK, Json(category)))
+}
+
+pub async fn delete_category_with_id(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    let service = create_category_service(pool);
+    service.delete_category_from_id(id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
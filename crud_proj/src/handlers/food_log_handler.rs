@@ -0,0 +1,80 @@
+use axum::{extract::{Path, Query, State}, http::StatusCode, Json};
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    errors::AppError,
+    models::food_log::{DailySummary, FoodLogDateQuery, FoodLogEntryForm, FoodLogEntryResponse},
+    repositories::{
+        food_log_repositories::FoodLogRepository, product_repositories::ProductRepository,
+        user_profile_repositories::UserProfileRepository,
+    },
+    services::food_log::{FoodLogService, FoodLogServiceTrait},
+};
+
+
+fn create_food_log_service(pool: Arc<PgPool>) -> FoodLogService {
+    let entries = Arc::new(FoodLogRepository::new(pool.clone()));
+    let profiles = Arc::new(UserProfileRepository::new(pool.clone()));
+    let products = Arc::new(ProductRepository::new(pool));
+    FoodLogService::new(entries, profiles, products)
+}
+
+pub async fn get_food_log(
+    State(pool): State<Arc<PgPool>>,
+    Path(profile_id): Path<Uuid>,
+    Query(query): Query<FoodLogDateQuery>,
+) -> Result<(StatusCode, Json<Vec<FoodLogEntryResponse>>), AppError> {
+    let service = create_food_log_service(pool);
+    let entries = service.list_food_log(profile_id, query).await?.ok_or(AppError::NotFound)?;
+    Ok((StatusCode::OK, Json(entries)))
+}
+
+pub async fn add_food_log_entry(
+    State(pool): State<Arc<PgPool>>,
+    Path(profile_id): Path<Uuid>,
+    Json(payload): Json<FoodLogEntryForm>,
+) -> Result<(StatusCode, Json<FoodLogEntryResponse>), AppError> {
+    let service = create_food_log_service(pool);
+    let entry = service.add_food_log_entry(profile_id, payload).await?.ok_or(AppError::NotFound)?;
+    Ok((StatusCode::CREATED, Json(entry)))
+}
+
+pub async fn get_food_log_entry_from_id(
+    State(pool): State<Arc<PgPool>>,
+    Path((profile_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<(StatusCode, Json<FoodLogEntryResponse>), AppError> {
+    let service = create_food_log_service(pool);
+    let entry = service.get_food_log_entry_from_id(profile_id, id).await?.ok_or(AppError::NotFound)?;
+    Ok((StatusCode::OK, Json(entry)))
+}
+
+pub async fn update_food_log_entry_with_id(
+    State(pool): State<Arc<PgPool>>,
+    Path((profile_id, id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<FoodLogEntryForm>,
+) -> Result<(StatusCode, Json<FoodLogEntryResponse>), AppError> {
+    let service = create_food_log_service(pool);
+    let entry = service.update_food_log_entry_from_id(profile_id, id, payload).await?.ok_or(AppError::NotFound)?;
+    Ok((StatusCode::OK, Json(entry)))
+}
+
+pub async fn delete_food_log_entry_with_id(
+    State(pool): State<Arc<PgPool>>,
+    Path((profile_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, AppError> {
+    let service = create_food_log_service(pool);
+    service.delete_food_log_entry_from_id(profile_id, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn get_daily_summary(
+    State(pool): State<Arc<PgPool>>,
+    Path(profile_id): Path<Uuid>,
+    Query(query): Query<FoodLogDateQuery>,
+) -> Result<(StatusCode, Json<DailySummary>), AppError> {
+    let service = create_food_log_service(pool);
+    let summary = service.daily_summary(profile_id, query).await?.ok_or(AppError::NotFound)?;
+    Ok((StatusCode::OK, Json(summary)))
+}
@@ -0,0 +1,36 @@
+use axum::{extract::{Path, Query, State}, http::StatusCode, Json};
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    errors::AppError,
+    models::healthier_choice::{CriteriaVersionQuery, HealthierChoiceReport, HealthierChoiceResult},
+    repositories::product_repositories::ProductRepository,
+    services::healthier_choice::{HealthierChoiceService, HealthierChoiceServiceTrait},
+};
+
+
+fn create_healthier_choice_service(pool: Arc<PgPool>) -> HealthierChoiceService {
+    let repo = Arc::new(ProductRepository::new(pool));
+    HealthierChoiceService::new(repo)
+}
+
+pub async fn get_product_healthier_choice(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<CriteriaVersionQuery>,
+) -> Result<(StatusCode, Json<HealthierChoiceResult>), AppError> {
+    let service = create_healthier_choice_service(pool);
+    let result = service.evaluate_product(id, query.version).await?.ok_or(AppError::NotFound)?;
+    Ok((StatusCode::OK, Json(result)))
+}
+
+pub async fn get_healthier_choice_mismatches(
+    State(pool): State<Arc<PgPool>>,
+    Query(query): Query<CriteriaVersionQuery>,
+) -> Result<(StatusCode, Json<HealthierChoiceReport>), AppError> {
+    let service = create_healthier_choice_service(pool);
+    let report = service.mismatch_report(query.version).await?;
+    Ok((StatusCode::OK, Json(report)))
+}
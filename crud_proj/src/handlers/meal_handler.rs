@@ -0,0 +1,62 @@
+use axum::{extract::{Path, State}, http::StatusCode, Json};
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    errors::AppError,
+    models::meals::{MealForm, MealResponse},
+    repositories::meal_repositories::MealRepository,
+    services::meal_service::{MealService, MealServiceTrait},
+};
+
+
+fn create_meal_service(pool: Arc<PgPool>) -> MealService {
+    let repo = Arc::new(MealRepository::new(pool));
+    MealService::new(repo)
+}
+
+pub async fn get_meal_list(
+    State(pool): State<Arc<PgPool>>,
+) -> Result<(StatusCode, Json<Vec<MealResponse>>), AppError> {
+    let service = create_meal_service(pool);
+    let meals = service.list_meals().await?;
+    Ok((StatusCode::OK, Json(meals)))
+}
+
+pub async fn add_meal(
+    State(pool): State<Arc<PgPool>>,
+    Json(payload): Json<MealForm>,
+) -> Result<(StatusCode, Json<MealResponse>), AppError> {
+    let service = create_meal_service(pool);
+    let meal = service.add_meal(payload).await?;
+    Ok((StatusCode::CREATED, Json(meal)))
+}
+
+pub async fn get_meal_from_id(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+) -> Result<(StatusCode, Json<MealResponse>), AppError> {
+    let service = create_meal_service(pool);
+    let meal = service.get_meal_from_id(id).await?.ok_or(AppError::NotFound)?;
+    Ok((StatusCode::OK, Json(meal)))
+}
+
+pub async fn update_meal_with_id(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<MealForm>,
+) -> Result<(StatusCode, Json<MealResponse>), AppError> {
+    let service = create_meal_service(pool);
+    let meal = service.update_meal_from_id(id, payload).await?.ok_or(AppError::NotFound)?;
+    Ok((StatusCode::OK, Json(meal)))
+}
+
+pub async fn delete_meal_with_id(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    let service = create_meal_service(pool);
+    service.delete_meal_from_id(id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,26 @@
+use axum::{extract::State, http::StatusCode, Json};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use crate::{
+    errors::AppError,
+    models::meal_plan::{MealPlan, MealPlanRequest},
+    repositories::{product_repositories::ProductRepository, user_profile_repositories::UserProfileRepository},
+    services::meal_plan::{MealPlanService, MealPlanServiceTrait},
+};
+
+
+fn create_meal_plan_service(pool: Arc<PgPool>) -> MealPlanService {
+    let products = Arc::new(ProductRepository::new(pool.clone()));
+    let profiles = Arc::new(UserProfileRepository::new(pool));
+    MealPlanService::new(products, profiles)
+}
+
+pub async fn optimize_meal_plan(
+    State(pool): State<Arc<PgPool>>,
+    Json(payload): Json<MealPlanRequest>,
+) -> Result<(StatusCode, Json<MealPlan>), AppError> {
+    let service = create_meal_plan_service(pool);
+    let plan = service.optimize_meal_plan(payload).await?;
+    Ok((StatusCode::OK, Json(plan)))
+}
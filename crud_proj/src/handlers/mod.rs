@@ -1 +1,11 @@
-pub mod product_handler;
\ No newline at end of file
+pub mod product_handler;
+pub mod category_handler;
+pub mod healthier_choice_handler;
+pub mod additive_handler;
+pub mod user_profile_handler;
+pub mod meal_plan_handler;
+pub mod value_metrics_handler;
+pub mod food_log_handler;
+pub mod meal_handler;
+pub mod product_comparison_handler;
+pub mod alternatives_handler;
\ No newline at end of file
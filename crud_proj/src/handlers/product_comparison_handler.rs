@@ -0,0 +1,25 @@
+use axum::{extract::State, http::StatusCode, Json};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use crate::{
+    errors::AppError,
+    models::product_comparison::{ProductComparison, ProductComparisonRequest},
+    repositories::product_repositories::ProductRepository,
+    services::product_comparison::{ProductComparisonService, ProductComparisonServiceTrait},
+};
+
+
+fn create_product_comparison_service(pool: Arc<PgPool>) -> ProductComparisonService {
+    let products = Arc::new(ProductRepository::new(pool));
+    ProductComparisonService::new(products)
+}
+
+pub async fn compare_products(
+    State(pool): State<Arc<PgPool>>,
+    Json(payload): Json<ProductComparisonRequest>,
+) -> Result<(StatusCode, Json<ProductComparison>), AppError> {
+    let service = create_product_comparison_service(pool);
+    let comparison = service.compare_products(payload).await?;
+    Ok((StatusCode::OK, Json(comparison)))
+}
@@ -1,33 +1,30 @@
-use axum::{extract::{Path, Query, State}, http::StatusCode, Json};
+use axum::{extract::{OriginalUri, Path, Query, State}, http::StatusCode, Json};
 use uuid::Uuid;
-use crate::{models::{pagination::TemplateResponse, products::{ProductForm, ProductResponse}}};
+use crate::{models::{front_of_pack::FrontOfPackQuery, nutrition::DailyValueQuery, pagination::TemplateResponse, product_filter::ProductFilter, products::{DerivedScoresReport, ProductForm, ProductPatch, ProductResponse}}};
 use sqlx::PgPool;
 use std::sync::Arc;
 
 use crate::{
-    errors::AppError, models::{pagination::Pagination}, repositories::product_repositories::ProductRepository, services::product_service::{self, ProductServiceTrait}
+    errors::AppError, models::{pagination::Pagination}, repositories::{additive_repositories::AdditiveRepository, product_repositories::ProductRepository}, services::product_service::{self, ProductServiceTrait}
 };
 
 
 fn create_product_service(pool: Arc<PgPool>) -> product_service::ProductService {
-    let repo = Arc::new(ProductRepository::new(pool));
-    product_service::ProductService::new(repo)
+    let repo = Arc::new(ProductRepository::new(pool.clone()));
+    let additives = Arc::new(AdditiveRepository::new(pool));
+    product_service::ProductService::new(repo, additives)
 }
 
 pub async fn get_product_list(
     State(pool): State<Arc<PgPool>>,
-    Query(pagination): Query<Pagination>
+    OriginalUri(uri): OriginalUri,
+    Query(pagination): Query<Pagination>,
+    Query(filter): Query<ProductFilter>,
+    Query(daily_values): Query<DailyValueQuery>,
 ) -> Result<(StatusCode, Json<TemplateResponse<ProductResponse>>), AppError> {
     let service = create_product_service(pool);
-    let pagination_clone = pagination.clone();
-    let products: Vec<ProductResponse> = service.list_products(pagination).await?;
-    let total = products.len();
-    let response = TemplateResponse::<ProductResponse> {
-        items: products.clone(),
-        total,
-        limit: pagination_clone.limit.unwrap_or(0),
-        offset: pagination_clone.offset.unwrap_or(0),
-    };
+    let page = service.list_products(pagination.clone(), filter, daily_values).await?;
+    let response = TemplateResponse::from_page(page, &pagination, &uri);
     Ok((StatusCode::OK, Json(response)))
 }
 
@@ -43,10 +40,22 @@ pub async fn add_product(
 pub async fn get_product_from_id(
     State(pool): State<Arc<PgPool>>,
     Path(id): Path<Uuid>,
+    Query(daily_values): Query<DailyValueQuery>,
+    Query(front_of_pack): Query<FrontOfPackQuery>,
 ) -> Result<(StatusCode, Json<ProductResponse>), AppError> {
-    let repo = Arc::new(ProductRepository::new(pool.clone()));
-    let service = product_service::ProductService::new(repo);
-    let product = service.get_product_from_id(id).await?.ok_or_else(|| AppError::NotFound)?;
+    let service = create_product_service(pool);
+    let product = service.get_product_from_id(id, daily_values, front_of_pack).await?.ok_or_else(|| AppError::NotFound)?;
+    Ok((StatusCode::OK, Json(product)))
+}
+
+pub async fn get_product_from_barcode(
+    State(pool): State<Arc<PgPool>>,
+    Path(code): Path<String>,
+    Query(daily_values): Query<DailyValueQuery>,
+    Query(front_of_pack): Query<FrontOfPackQuery>,
+) -> Result<(StatusCode, Json<ProductResponse>), AppError> {
+    let service = create_product_service(pool);
+    let product = service.get_product_from_barcode(&code, daily_values, front_of_pack).await?.ok_or(AppError::NotFound)?;
     Ok((StatusCode::OK, Json(product)))
 }
 
@@ -60,6 +69,16 @@ pub async fn update_product_with_id(
     Ok((StatusCode::OK, Json(product)))
 }
 
+pub async fn patch_product_with_id(
+    State(pool): State<Arc<PgPool>>, 
+    Path(id): Path<Uuid>, 
+    Json(patch): Json<ProductPatch>
+) -> Result<(StatusCode, Json<ProductResponse>), AppError> {
+    let service = create_product_service(pool);
+    let product = service.patch_product_from_id(id, patch).await?.ok_or(AppError::NotFound)?;
+    Ok((StatusCode::OK, Json(product)))
+}
+
 pub async fn delete_product_with_id(
     State(pool): State<Arc<PgPool>>,
     Path(id): Path<Uuid>,
@@ -67,4 +86,11 @@ pub async fn delete_product_with_id(
     let service = create_product_service(pool);
     service.delete_product_from_id(id).await?;
     Ok(StatusCode::NO_CONTENT)
-}
\ No newline at end of file
+}
+pub async fn recompute_derived_scores(
+    State(pool): State<Arc<PgPool>>,
+) -> Result<(StatusCode, Json<DerivedScoresReport>), AppError> {
+    let service = create_product_service(pool);
+    let report = service.recompute_derived_scores().await?;
+    Ok((StatusCode::OK, Json(report)))
+}
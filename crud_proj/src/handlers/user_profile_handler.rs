@@ -0,0 +1,62 @@
+use axum::{extract::{Path, State}, http::StatusCode, Json};
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    errors::AppError,
+    models::user_profiles::{UserProfileForm, UserProfileResponse},
+    repositories::user_profile_repositories::UserProfileRepository,
+    services::user_profile_service::{UserProfileService, UserProfileServiceTrait},
+};
+
+
+fn create_user_profile_service(pool: Arc<PgPool>) -> UserProfileService {
+    let repo = Arc::new(UserProfileRepository::new(pool));
+    UserProfileService::new(repo)
+}
+
+pub async fn get_user_profile_list(
+    State(pool): State<Arc<PgPool>>,
+) -> Result<(StatusCode, Json<Vec<UserProfileResponse>>), AppError> {
+    let service = create_user_profile_service(pool);
+    let profiles = service.list_user_profiles().await?;
+    Ok((StatusCode::OK, Json(profiles)))
+}
+
+pub async fn add_user_profile(
+    State(pool): State<Arc<PgPool>>,
+    Json(payload): Json<UserProfileForm>,
+) -> Result<(StatusCode, Json<UserProfileResponse>), AppError> {
+    let service = create_user_profile_service(pool);
+    let profile = service.add_user_profile(payload).await?;
+    Ok((StatusCode::CREATED, Json(profile)))
+}
+
+pub async fn get_user_profile_from_id(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+) -> Result<(StatusCode, Json<UserProfileResponse>), AppError> {
+    let service = create_user_profile_service(pool);
+    let profile = service.get_user_profile_from_id(id).await?.ok_or(AppError::NotFound)?;
+    Ok((StatusCode::OK, Json(profile)))
+}
+
+pub async fn update_user_profile_with_id(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UserProfileForm>,
+) -> Result<(StatusCode, Json<UserProfileResponse>), AppError> {
+    let service = create_user_profile_service(pool);
+    let profile = service.update_user_profile_from_id(id, payload).await?.ok_or(AppError::NotFound)?;
+    Ok((StatusCode::OK, Json(profile)))
+}
+
+pub async fn delete_user_profile_with_id(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    let service = create_user_profile_service(pool);
+    service.delete_user_profile_from_id(id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,27 @@
+use axum::{extract::{Path, Query, State}, http::StatusCode, Json};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use crate::{
+    errors::AppError,
+    models::value_metrics::{BestValueQuery, BestValueRanking},
+    repositories::{category_repositories::CategoryRepository, product_repositories::ProductRepository},
+    services::value_metrics::{BestValueService, BestValueServiceTrait},
+};
+
+
+fn create_best_value_service(pool: Arc<PgPool>) -> BestValueService {
+    let products = Arc::new(ProductRepository::new(pool.clone()));
+    let categories = Arc::new(CategoryRepository::new(pool));
+    BestValueService::new(products, categories)
+}
+
+pub async fn get_category_best_value(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<i32>,
+    Query(query): Query<BestValueQuery>,
+) -> Result<(StatusCode, Json<BestValueRanking>), AppError> {
+    let service = create_best_value_service(pool);
+    let ranking = service.best_value_in_category(id, query).await?.ok_or(AppError::NotFound)?;
+    Ok((StatusCode::OK, Json(ranking)))
+}
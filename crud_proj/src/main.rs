@@ -5,9 +5,11 @@ mod errors;
 mod services;
 mod repositories;
 mod boostdb;
+mod middlewares;
 
 use std::sync::Arc;
 use boostdb::Database;
+use models::off_import::ImportOptions;
 use tracing::{info};
 use env_logger::Env;
 
@@ -23,7 +25,28 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let env = Env::default().filter_or("RUST_LOG", default_level);
     env_logger::init_from_env(env);
 
-    
+    // `crud_proj import-off <file>` นำเข้าไฟล์ export ของ Open Food Facts แทนการเปิด server
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("import-off") {
+        let options = match ImportOptions::from_args(&args[1..]) {
+            Ok(options) => options,
+            Err(e) => {
+                eprintln!("{}\n{}", e, ImportOptions::USAGE);
+                std::process::exit(2);
+            }
+        };
+        let database = initialize_database().await?;
+        let report = services::off_import::run(Arc::new(database.pool().clone()), &options).await?;
+        println!(
+            "{} rows: {} created, {} updated, {} not sold in Thailand, {} skipped, {} invalid{} (report: {})",
+            report.rows, report.created, report.updated, report.not_in_thailand,
+            report.skipped.len(), report.invalid.len(),
+            if report.dry_run { " [dry run]" } else { "" },
+            options.report_path,
+        );
+        return Ok(());
+    }
+
     let database = initialize_database().await?;
     let db_pool = Arc::new(database.pool().clone());
 
@@ -0,0 +1 @@
+pub mod request_id;
\ No newline at end of file
@@ -0,0 +1,94 @@
+use axum::{
+    body::to_bytes,
+    extract::Request,
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use uuid::Uuid;
+
+use crate::errors::{ProblemDetails, PROBLEM_JSON};
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+// ไม่อ่าน body ของ error ที่ใหญ่ผิดปกติ (rejection ของ axum เป็นข้อความสั้น ๆ)
+const MAX_PLAIN_ERROR_BODY: usize = 16 * 1024;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Request ID of the request being handled by the current task, if any.
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+// รับ x-request-id จาก client (เช่น gateway) ถ้าเป็นค่าที่ปลอดภัย ไม่เช่นนั้นสร้างใหม่
+fn incoming_request_id(request: &Request) -> Option<String> {
+    let value = request.headers().get(REQUEST_ID_HEADER)?.to_str().ok()?;
+    let valid = !value.is_empty()
+        && value.len() <= 128
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    valid.then(|| value.to_string())
+}
+
+/// Assigns every request an ID, echoes it in the `x-request-id` header and makes it
+/// available to error bodies. Error responses that did not come from `AppError` (axum
+/// extractor rejections, unknown routes) are rewritten as problem+json too.
+pub async fn request_id_middleware(request: Request, next: Next) -> Response {
+    let request_id = incoming_request_id(&request).unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    let mut response = REQUEST_ID
+        .scope(request_id.clone(), async move {
+            let response = next.run(request).await;
+            into_problem_response(response).await
+        })
+        .await;
+
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+async fn into_problem_response(response: Response) -> Response {
+    let status = response.status();
+    let is_problem = response.headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|content_type| content_type.as_bytes().starts_with(PROBLEM_JSON.as_bytes()));
+    if !(status.is_client_error() || status.is_server_error()) || is_problem {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let text = to_bytes(body, MAX_PLAIN_ERROR_BODY)
+        .await
+        .map(|bytes| String::from_utf8_lossy(&bytes).trim().to_string())
+        .unwrap_or_default();
+    let detail = if text.is_empty() {
+        status.canonical_reason().unwrap_or("Error").to_string()
+    } else {
+        text
+    };
+
+    let mut problem_response = ProblemDetails::new(status, status_code_name(status), detail).into_response();
+    for (name, value) in parts.headers.iter() {
+        if name != header::CONTENT_TYPE && name != header::CONTENT_LENGTH {
+            problem_response.headers_mut().insert(name.clone(), value.clone());
+        }
+    }
+    problem_response
+}
+
+fn status_code_name(status: StatusCode) -> &'static str {
+    match status {
+        StatusCode::BAD_REQUEST => "bad_request",
+        StatusCode::NOT_FOUND => "not_found",
+        StatusCode::METHOD_NOT_ALLOWED => "method_not_allowed",
+        StatusCode::PAYLOAD_TOO_LARGE => "payload_too_large",
+        StatusCode::UNSUPPORTED_MEDIA_TYPE => "unsupported_media_type",
+        StatusCode::UNPROCESSABLE_ENTITY => "invalid_request_body",
+        status if status.is_server_error() => "internal_error",
+        _ => "error",
+    }
+}
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use super::nutrition::NutrientBasis;
+use super::products::ProductResponse;
+/*
+ทางเลือกที่ดีกว่าของ product (`GET /api/v1/products/{id}/alternatives`)
+ - ผู้สมัคร: product ที่อยู่ใน category เดียวกันอย่างน้อยหนึ่ง category
+ - ต้องลดน้ำตาล, โซเดียม หรือไขมันอิ่มตัวอย่างน้อยหนึ่งตัวไม่ต่ำกว่า `MIN_REDUCTION_PERCENT`
+   และไม่สูงกว่าของเดิมในอีกสองตัว
+ - เทียบต่อ 100 g/ml เมื่อ product เดิมมี serving size (ผู้สมัครต้องมีด้วย) ไม่เช่นนั้นเทียบต่อ serving
+ - ราคาไม่เกินราคาเดิม + `price_margin` % (ไม่กรองราคาเมื่อ product เดิมไม่มีราคา)
+ - เรียงให้ product ที่ไม่ใช่ UPF หรือได้ Healthier Choice มาก่อน แล้วตามผลรวม % ที่ลดได้ และราคา
+*/
+
+pub const MIN_REDUCTION_PERCENT: f32 = 10.0;
+pub const DEFAULT_PRICE_MARGIN_PERCENT: f32 = 20.0;
+pub const MAX_PRICE_MARGIN_PERCENT: f32 = 200.0;
+pub const DEFAULT_ALTERNATIVES_LIMIT: usize = 5;
+pub const MAX_ALTERNATIVES_LIMIT: usize = 20;
+
+/// Query of `GET /api/v1/products/{id}/alternatives`.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct AlternativesQuery {
+    /// How much more than the original an alternative may cost, in percent.
+    pub price_margin: Option<f32>,
+    pub limit: Option<usize>,
+}
+
+impl AlternativesQuery {
+    pub fn validate(&self) -> Result<(), AppError> {
+        if let Some(margin) = self.price_margin
+            && !(margin.is_finite() && (0.0..=MAX_PRICE_MARGIN_PERCENT).contains(&margin))
+        {
+            return Err(AppError::ValidationError(format!(
+                "price_margin must be between 0 and {}", MAX_PRICE_MARGIN_PERCENT
+            )));
+        }
+        if let Some(limit) = self.limit
+            && !(1..=MAX_ALTERNATIVES_LIMIT).contains(&limit)
+        {
+            return Err(AppError::ValidationError(format!(
+                "limit must be between 1 and {}", MAX_ALTERNATIVES_LIMIT
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn price_margin(&self) -> f32 {
+        self.price_margin.unwrap_or(DEFAULT_PRICE_MARGIN_PERCENT)
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_ALTERNATIVES_LIMIT)
+    }
+}
+
+/// What a candidate has to beat, on `basis`; built by the service from the original product.
+#[derive(Debug, Clone)]
+pub struct AlternativeCriteria {
+    pub product_id: Uuid,
+    pub basis: NutrientBasis,
+    pub sugar: f64,
+    pub sodium: f64,
+    pub saturated_fat: f64,
+    /// `None` leaves the price unfiltered.
+    pub max_price: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NutrientChange {
+    pub nutrient: String,
+    pub unit: String,
+    pub original: f32,
+    pub alternative: f32,
+    /// Relative change; negative is lower. `None` when the original is 0.
+    pub percent: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alternative {
+    pub rank: usize,
+    /// Why it was picked, e.g. `-40% sodium, +2 THB`.
+    pub reason: String,
+    pub changes: Vec<NutrientChange>,
+    /// Alternative price minus the original; `None` when either has no price.
+    pub price_difference: Option<f32>,
+    /// Not ultra-processed or Healthier Choice certified.
+    pub preferred: bool,
+    pub product: ProductResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductAlternatives {
+    pub product: ProductResponse,
+    pub basis: NutrientBasis,
+    pub price_margin: f32,
+    pub max_price: Option<f32>,
+    pub items: Vec<Alternative>,
+}
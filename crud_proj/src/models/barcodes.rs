@@ -0,0 +1,95 @@
+use std::collections::BTreeSet;
+/*
+บาร์โค้ด EAN-13 และ UPC-A (GTIN-13/GTIN-12) ของ product
+ - หลักสุดท้ายเป็น check digit: ถ่วงน้ำหนัก 3 และ 1 สลับกันจากหลักขวาสุด (ไม่รวม check digit)
+ - UPC-A เติม 0 ข้างหน้าเป็น EAN-13 ที่มี check digit เดิม จึงเก็บและค้นหาด้วยรูป EAN-13 เสมอ
+*/
+
+const EAN13_LENGTH: usize = 13;
+const UPCA_LENGTH: usize = 12;
+
+/// A barcode with a valid check digit, in its 13-digit EAN-13 form.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Barcode(String);
+
+impl Barcode {
+    /// Accepts EAN-13 or UPC-A digits; surrounding whitespace is ignored.
+    pub fn parse(code: &str) -> Result<Self, String> {
+        let code = code.trim();
+        if !code.chars().all(|c| c.is_ascii_digit()) || !matches!(code.len(), EAN13_LENGTH | UPCA_LENGTH) {
+            return Err(format!(
+                "must be {} digits (EAN-13) or {} digits (UPC-A)", EAN13_LENGTH, UPCA_LENGTH
+            ));
+        }
+
+        let ean13 = format!("{:0>13}", code);
+        let digits: Vec<u32> = ean13.chars().filter_map(|c| c.to_digit(10)).collect();
+        let expected = check_digit(&digits[..EAN13_LENGTH - 1]);
+        if digits[EAN13_LENGTH - 1] != expected {
+            return Err(format!("has an invalid check digit (expected {})", expected));
+        }
+        Ok(Barcode(ean13))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+fn check_digit(digits: &[u32]) -> u32 {
+    let sum: u32 = digits.iter()
+        .rev()
+        .enumerate()
+        .map(|(index, digit)| if index % 2 == 0 { digit * 3 } else { *digit })
+        .sum();
+    (10 - sum % 10) % 10
+}
+
+/// Distinct EAN-13 forms of the valid codes, sorted; invalid codes are skipped (validate first).
+pub fn normalized_barcodes(codes: &[String]) -> Vec<String> {
+    codes.iter()
+        .filter_map(|code| Barcode::parse(code).ok())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|barcode| barcode.0)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_ean13_with_a_valid_check_digit() {
+        assert_eq!(Barcode::parse("4006381333931").unwrap().as_str(), "4006381333931");
+        assert_eq!(Barcode::parse(" 8850000000010\n").unwrap().as_str(), "8850000000010");
+    }
+
+    #[test]
+    fn rejects_a_wrong_check_digit() {
+        assert_eq!(
+            Barcode::parse("8850000000019").unwrap_err(),
+            "has an invalid check digit (expected 0)"
+        );
+        assert!(Barcode::parse("036000291453").is_err());
+    }
+
+    #[test]
+    fn rejects_other_lengths_and_non_digits() {
+        for code in ["", "40063813339", "40063813339310", "400638133393X", "4006-38133393"] {
+            assert!(Barcode::parse(code).is_err(), "{}", code);
+        }
+    }
+
+    #[test]
+    fn upc_a_becomes_ean13_with_a_leading_zero() {
+        assert_eq!(Barcode::parse("036000291452").unwrap().as_str(), "0036000291452");
+        assert_eq!(Barcode::parse("036000291452"), Barcode::parse("0036000291452"));
+    }
+
+    #[test]
+    fn normalizes_to_distinct_sorted_ean13() {
+        let codes = ["8850000000010", "036000291452", "0036000291452", "123"].map(str::to_string);
+        assert_eq!(normalized_barcodes(&codes), vec!["0036000291452", "8850000000010"]);
+    }
+}
@@ -1,21 +1,36 @@
-use uuid::Uuid;
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+
+use super::products::{nullable, Product};
 /*
 Model ของ Categoris ที่ใช้จัดหมวดหมู่ของ Product
+ - `id`: Identity ของ category (`categories.id` เป็น `INT GENERATED ALWAYS AS IDENTITY`)
+ - `name`: ชื่อ category (unique)
+ - `food_group`: กลุ่มอาหารตามเกณฑ์ Healthier Choice (เช่น `beverages`, `instant_noodles`) ใช้เลือกเกณฑ์ที่ประเมิน product
+ - `off_tags`: category tag ของ Open Food Facts (เช่น `en:instant-noodles`) ที่ importer จับคู่เข้ากับ category นี้
 */
 #[derive(Debug, Clone, Serialize, Deserialize, Default, FromRow)]
 pub struct Categories {
-    pub id: Uuid,
+    pub id: i32,
     pub name: String,
+    pub food_group: Option<String>,
+    pub off_tags: Vec<String>,
 }
 
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CategoriesForm {
-    pub id: Option<String>,
     pub name: String,
+    /// Omitted on `PATCH` keeps the current food group; `null` clears it.
+    #[serde(default, deserialize_with = "nullable")]
+    pub food_group: Option<Option<String>>,
+    /// Omitted on `PATCH` keeps the current tags; `null` or `[]` clears them.
+    #[serde(default, deserialize_with = "nullable")]
+    pub off_tags: Option<Option<Vec<String>>>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CategoriesWithProductsResponse {
     pub categories: Categories,
     pub products: Vec<Product>,
-}
\ No newline at end of file
+}
@@ -0,0 +1,229 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+use crate::errors::{AppError, FieldError};
+use super::nutrition::NutrientAmounts;
+use super::products::Product;
+use super::user_profiles::DailyTargets;
+/*
+บันทึกอาหารที่ผู้ใช้กินจริง (`/api/v1/profiles/{id}/food-log`)
+ - `quantity`: จำนวน serving (`servings`) หรือกรัม/มิลลิลิตร (`grams`) ของ product
+   กรัมจะแปลงเป็น serving ด้วย `serving_size_grams` จึงใช้ได้เฉพาะ product ที่มี serving size
+ - ค่าสารอาหารและราคาคำนวณจาก column ของ `products` ทุกครั้งที่อ่าน (แก้ product แล้วบันทึกเดิมเปลี่ยนตาม)
+ - `eaten_at`: เวลาที่กิน (RFC 3339, default = ตอนบันทึก) ส่งกลับในเขตเวลาของ profile
+ - วันของบันทึก (`date`) ตัดตามเขตเวลา `timezone` ของ profile ไม่ใช่ UTC
+*/
+
+// กันค่าที่พิมพ์ผิด (เช่นกรัมใส่ในช่อง serving)
+const MAX_SERVINGS: f32 = 50.0;
+const MAX_GRAMS: f32 = 5000.0;
+
+// ช่วง %ของเป้าหมายพลังงาน/สารอาหารที่ถือว่าตรงเป้า
+pub const ON_TRACK_MIN_PERCENT: f32 = 90.0;
+pub const ON_TRACK_MAX_PERCENT: f32 = 110.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuantityUnit {
+    #[default]
+    Servings,
+    Grams,
+}
+
+impl QuantityUnit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuantityUnit::Servings => "servings",
+            QuantityUnit::Grams => "grams",
+        }
+    }
+
+    /// Servings that `quantity` stands for, or `None` for grams of a product without a serving size.
+    pub fn servings(&self, quantity: f32, serving_size: Option<f32>) -> Option<f32> {
+        match self {
+            QuantityUnit::Servings => Some(quantity),
+            QuantityUnit::Grams => serving_size
+                .filter(|size| size.is_finite() && *size > 0.0)
+                .map(|size| quantity / size),
+        }
+    }
+
+    /// Largest quantity accepted in this unit.
+    pub fn max_quantity(&self) -> f32 {
+        match self {
+            QuantityUnit::Servings => MAX_SERVINGS,
+            QuantityUnit::Grams => MAX_GRAMS,
+        }
+    }
+}
+
+impl TryFrom<String> for QuantityUnit {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "servings" => Ok(QuantityUnit::Servings),
+            "grams" => Ok(QuantityUnit::Grams),
+            other => Err(format!("unknown quantity unit {:?}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MealSlot {
+    Breakfast,
+    Lunch,
+    Dinner,
+    Snack,
+}
+
+impl MealSlot {
+    pub const ALL: [MealSlot; 4] = [MealSlot::Breakfast, MealSlot::Lunch, MealSlot::Dinner, MealSlot::Snack];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MealSlot::Breakfast => "breakfast",
+            MealSlot::Lunch => "lunch",
+            MealSlot::Dinner => "dinner",
+            MealSlot::Snack => "snack",
+        }
+    }
+}
+
+impl TryFrom<String> for MealSlot {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        MealSlot::ALL.into_iter()
+            .find(|slot| slot.as_str() == value)
+            .ok_or_else(|| format!("unknown meal slot {:?}", value))
+    }
+}
+
+/// A stored entry with the product row it refers to.
+#[derive(Debug, Clone, FromRow)]
+pub struct FoodLogEntry {
+    pub id: Uuid,
+    pub profile_id: Uuid,
+    pub product_id: Uuid,
+    pub quantity: f32,
+    #[sqlx(try_from = "String")]
+    pub quantity_unit: QuantityUnit,
+    #[sqlx(try_from = "String")]
+    pub meal_slot: MealSlot,
+    pub eaten_at: DateTime<Utc>,
+    #[sqlx(json)]
+    pub product: Product,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FoodLogEntryForm {
+    pub product_id: Uuid,
+    pub quantity: f32,
+    #[serde(default)]
+    pub quantity_unit: QuantityUnit,
+    pub meal_slot: MealSlot,
+    /// Defaults to the time the entry is written.
+    pub eaten_at: Option<DateTime<Utc>>,
+}
+
+impl FoodLogEntryForm {
+    pub fn validate(&self) -> Result<(), AppError> {
+        let max = self.quantity_unit.max_quantity();
+        if !(self.quantity.is_finite() && self.quantity > 0.0 && self.quantity <= max) {
+            return Err(AppError::InvalidFields(vec![FieldError::new(
+                "quantity",
+                format!("must be greater than 0 and at most {} {}", max, self.quantity_unit.as_str()),
+            )]));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FoodLogEntryResponse {
+    pub id: Uuid,
+    pub profile_id: Uuid,
+    pub product_id: Uuid,
+    pub product_name: String,
+    pub brand: Option<String>,
+    pub quantity: f32,
+    pub quantity_unit: QuantityUnit,
+    pub meal_slot: MealSlot,
+    /// In the profile's time zone.
+    pub eaten_at: DateTime<FixedOffset>,
+    pub date: NaiveDate,
+    /// `None` for grams of a product without a serving size; such entries are left out of totals.
+    pub servings: Option<f32>,
+    pub cost: Option<f32>,
+    pub nutrients: Option<NutrientAmounts>,
+}
+
+/// `date` of the list and summary endpoints; defaults to today in the profile's time zone.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct FoodLogDateQuery {
+    pub date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetKind {
+    /// Aim for about the target (energy, protein, fat, carbs).
+    Goal,
+    /// Stay at or below the target (sugar, sodium).
+    Limit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressStatus {
+    Under,
+    OnTrack,
+    Over,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetProgress {
+    pub nutrient: String,
+    pub unit: String,
+    pub kind: TargetKind,
+    pub intake: f32,
+    pub target: f32,
+    pub percent: f32,
+    pub status: ProgressStatus,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MealSlotSummary {
+    pub meal_slot: MealSlot,
+    pub entries: usize,
+    pub calories: f32,
+    pub spent: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailySpending {
+    pub spent: f32,
+    pub daily_budget: Option<f32>,
+    /// Negative when over budget; `None` without a budget.
+    pub remaining: Option<f32>,
+    pub over_budget: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailySummary {
+    pub profile_id: Uuid,
+    pub date: NaiveDate,
+    pub timezone: String,
+    pub entries: usize,
+    /// Entries that could not be converted to servings and are not in the totals.
+    pub unconverted_entries: usize,
+    pub totals: NutrientAmounts,
+    pub targets: DailyTargets,
+    pub progress: Vec<TargetProgress>,
+    pub by_meal: Vec<MealSlotSummary>,
+    pub spending: DailySpending,
+}
@@ -0,0 +1,224 @@
+use serde::{Deserialize, Serialize};
+
+use crate::errors::AppError;
+use super::nutrition::{NutrientBasis, NutritionFacts, ServingUnit};
+/*
+ฉลากด้านหน้าบรรจุภัณฑ์ (front-of-pack) ที่คำนวณจากค่าสารอาหารของ product
+ - Traffic lights: สีเขียว/เหลือง/แดงของไขมัน, ไขมันอิ่มตัว, น้ำตาล และเกลือ ต่อ 100 g/ml
+   ตามเกณฑ์ของ UK FSA (เครื่องดื่ม = product ที่ `serving_unit` เป็น `ml` ใช้เกณฑ์แยก)
+ - "HIGH IN" warnings: สัญลักษณ์แปดเหลี่ยมตาม threshold profile ที่เลือกด้วย `warning_profile`
+   เมื่อเพิ่ม profile ใหม่ให้เพิ่มใน `WARNING_PROFILES`
+ - เกลือ (g) = โซเดียม (mg) × 2.5 / 1000
+*/
+
+pub const DEFAULT_WARNING_PROFILE: &str = "chile";
+
+// เกลือ 1 g ต่อโซเดียม 400 mg
+const SALT_G_PER_SODIUM_MG: f64 = 0.0025;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LightColour {
+    Green,
+    Amber,
+    Red,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelNutrient {
+    Energy,
+    Fat,
+    SaturatedFat,
+    Sugars,
+    Salt,
+    Sodium,
+}
+
+impl LabelNutrient {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LabelNutrient::Energy => "energy",
+            LabelNutrient::Fat => "fat",
+            LabelNutrient::SaturatedFat => "saturated_fat",
+            LabelNutrient::Sugars => "sugars",
+            LabelNutrient::Salt => "salt",
+            LabelNutrient::Sodium => "sodium",
+        }
+    }
+
+    pub fn unit(&self) -> &'static str {
+        match self {
+            LabelNutrient::Energy => "kcal",
+            LabelNutrient::Sodium => "mg",
+            _ => "g",
+        }
+    }
+
+    /// Unrounded amount on `basis`, computed like `column_sql` so labels agree with the list filters.
+    pub fn exact_amount(&self, nutrition: &NutritionFacts, basis: NutrientBasis) -> Option<f64> {
+        let amounts = &nutrition.per_serving;
+        let value = |amount: f32| basis.value(amount, nutrition.serving_size);
+        match self {
+            LabelNutrient::Energy => value(amounts.calories),
+            LabelNutrient::Fat => value(amounts.fat),
+            LabelNutrient::SaturatedFat => value(amounts.saturated_fat),
+            LabelNutrient::Sugars => value(amounts.sugar),
+            LabelNutrient::Salt => value(amounts.sodium).map(|sodium| sodium * SALT_G_PER_SODIUM_MG),
+            LabelNutrient::Sodium => value(amounts.sodium),
+        }
+    }
+
+    /// `float8` expression of the amount on `basis`, for list filters.
+    pub fn column_sql(&self, basis: NutrientBasis) -> String {
+        match self {
+            LabelNutrient::Energy => basis.column_sql("calories"),
+            LabelNutrient::Fat => basis.column_sql("fat"),
+            LabelNutrient::SaturatedFat => basis.column_sql("saturated_fat"),
+            LabelNutrient::Sugars => basis.column_sql("sugar"),
+            LabelNutrient::Salt => format!("({} * {})", basis.column_sql("sodium"), SALT_G_PER_SODIUM_MG),
+            LabelNutrient::Sodium => basis.column_sql("sodium"),
+        }
+    }
+}
+
+/// A limit that differs between foods (per 100 g) and beverages (per 100 ml).
+#[derive(Debug, Clone, Copy)]
+pub struct Limit {
+    pub solid: f32,
+    pub liquid: f32,
+}
+
+impl Limit {
+    pub fn for_unit(&self, unit: ServingUnit) -> f32 {
+        match unit {
+            ServingUnit::G => self.solid,
+            ServingUnit::Ml => self.liquid,
+        }
+    }
+}
+
+/// Green up to and including `green_max`, red above `red_above`, amber in between (per 100 g/ml).
+#[derive(Debug, Clone, Copy)]
+pub struct LightBands {
+    pub nutrient: LabelNutrient,
+    pub green_max: Limit,
+    pub red_above: Limit,
+}
+
+pub static TRAFFIC_LIGHT_BANDS: [LightBands; 4] = [
+    LightBands {
+        nutrient: LabelNutrient::Fat,
+        green_max: Limit { solid: 3.0, liquid: 1.5 },
+        red_above: Limit { solid: 17.5, liquid: 8.75 },
+    },
+    LightBands {
+        nutrient: LabelNutrient::SaturatedFat,
+        green_max: Limit { solid: 1.5, liquid: 0.75 },
+        red_above: Limit { solid: 5.0, liquid: 2.5 },
+    },
+    LightBands {
+        nutrient: LabelNutrient::Sugars,
+        green_max: Limit { solid: 5.0, liquid: 2.5 },
+        red_above: Limit { solid: 22.5, liquid: 11.25 },
+    },
+    LightBands {
+        nutrient: LabelNutrient::Salt,
+        green_max: Limit { solid: 0.3, liquid: 0.3 },
+        red_above: Limit { solid: 1.5, liquid: 0.75 },
+    },
+];
+
+/// "HIGH IN" when the amount on `basis` is above the limit.
+#[derive(Debug, Clone, Copy)]
+pub struct WarningThreshold {
+    pub nutrient: LabelNutrient,
+    pub limit: Limit,
+    pub basis: NutrientBasis,
+}
+
+#[derive(Debug)]
+pub struct WarningProfile {
+    pub name: &'static str,
+    pub thresholds: &'static [WarningThreshold],
+}
+
+impl WarningProfile {
+    pub fn requires_serving_size(&self) -> bool {
+        self.thresholds.iter().any(|threshold| threshold.basis.requires_serving_size())
+    }
+}
+
+const fn per_100(nutrient: LabelNutrient, solid: f32, liquid: f32) -> WarningThreshold {
+    WarningThreshold { nutrient, limit: Limit { solid, liquid }, basis: NutrientBasis::Per100 }
+}
+
+const fn per_serving(nutrient: LabelNutrient, max: f32) -> WarningThreshold {
+    WarningThreshold { nutrient, limit: Limit { solid: max, liquid: max }, basis: NutrientBasis::Serving }
+}
+
+pub static WARNING_PROFILES: &[WarningProfile] = &[
+    // Chile, Ley 20.606 (ระยะสุดท้าย): ต่อ 100 g / 100 ml
+    WarningProfile {
+        name: "chile",
+        thresholds: &[
+            per_100(LabelNutrient::Energy, 275.0, 70.0),
+            per_100(LabelNutrient::Sugars, 10.0, 5.0),
+            per_100(LabelNutrient::SaturatedFat, 4.0, 3.0),
+            per_100(LabelNutrient::Sodium, 400.0, 100.0),
+        ],
+    },
+    // Canada (2022): 15% ของ Daily Value ต่อหนึ่งหน่วยบริโภค
+    WarningProfile {
+        name: "canada",
+        thresholds: &[
+            per_serving(LabelNutrient::Sugars, 15.0),
+            per_serving(LabelNutrient::SaturatedFat, 3.0),
+            per_serving(LabelNutrient::Sodium, 345.0),
+        ],
+    },
+];
+
+pub fn warning_profile(name: Option<&str>) -> Result<&'static WarningProfile, AppError> {
+    let name = name.map(str::trim).filter(|name| !name.is_empty()).unwrap_or(DEFAULT_WARNING_PROFILE);
+    WARNING_PROFILES.iter()
+        .find(|profile| profile.name == name)
+        .ok_or_else(|| {
+            let known: Vec<&str> = WARNING_PROFILES.iter().map(|profile| profile.name).collect();
+            AppError::ValidationError(format!(
+                "Unknown warning_profile {:?}; known: {}", name, known.join(", ")
+            ))
+        })
+}
+
+/// Opt-in query option of the product endpoints.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct FrontOfPackQuery {
+    pub warning_profile: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TrafficLight {
+    pub nutrient: String,
+    pub colour: LightColour,
+    pub per_100: f32,
+    pub unit: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HighInWarning {
+    pub nutrient: String,
+    pub amount: f32,
+    pub limit: f32,
+    pub unit: String,
+    pub basis: NutrientBasis,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FrontOfPack {
+    /// `None` when the product has no serving size to normalize to 100 g/ml.
+    pub traffic_lights: Option<Vec<TrafficLight>>,
+    pub warning_profile: String,
+    pub high_in: Vec<HighInWarning>,
+    /// Nutrients of the profile that could not be checked (per-100 limits without a serving size).
+    pub unevaluated: Vec<String>,
+}
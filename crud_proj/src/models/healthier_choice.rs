@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+use super::{nutrition::NutrientBasis, products::ProductResponse};
+/*
+ผลการประเมินสัญลักษณ์ทางเลือกสุขภาพ (Healthier Choice) ที่คำนวณจากค่าสารอาหาร
+ - `pass`: ผ่านทุกเกณฑ์ของทุกกลุ่มอาหารของ product
+ - `fail`: มีอย่างน้อยหนึ่งเกณฑ์ที่เกิน (`failed`)
+ - `insufficient_data`: ไม่มีเกณฑ์ที่เกิน แต่บางเกณฑ์ประเมินไม่ได้ (เช่นเกณฑ์ต่อ 100 g แต่ไม่มี serving size)
+ - `not_applicable`: category ของ product ไม่มีกลุ่มอาหารที่มีเกณฑ์ใน version นี้
+*/
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthierChoiceStatus {
+    Pass,
+    Fail,
+    InsufficientData,
+    NotApplicable,
+}
+
+/// One threshold of the criteria and the product's value it was checked against.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThresholdCheck {
+    pub food_group: String,
+    pub nutrient: String,
+    pub max: f32,
+    pub unit: String,
+    pub basis: NutrientBasis,
+    /// `None` when the value cannot be derived on `basis`.
+    pub actual: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthierChoiceResult {
+    pub product_id: Uuid,
+    pub product_name: String,
+    pub criteria_version: String,
+    pub food_groups: Vec<String>,
+    pub status: HealthierChoiceStatus,
+    /// Computed eligibility (`status == pass`).
+    pub eligible: bool,
+    /// The hand-maintained `products.is_healthier` flag.
+    pub stored_is_healthier: bool,
+    pub failed: Vec<ThresholdCheck>,
+    pub unevaluated: Vec<ThresholdCheck>,
+}
+
+/// Products whose stored `is_healthier` flag disagrees with a `pass`/`fail` result, and the
+/// products the criteria cannot decide (`insufficient_data`/`not_applicable`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthierChoiceReport {
+    pub criteria_version: String,
+    pub checked: usize,
+    pub mismatch_count: usize,
+    pub mismatches: Vec<HealthierChoiceResult>,
+    pub unverifiable_count: usize,
+    pub unverifiable: Vec<HealthierChoiceResult>,
+}
+
+/// A product row plus the food groups of its categories.
+#[derive(Debug, Clone, FromRow)]
+pub struct ProductWithFoodGroups {
+    #[sqlx(flatten)]
+    pub product: ProductResponse,
+    pub food_groups: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct CriteriaVersionQuery {
+    /// Criteria version to evaluate against; defaults to the current one.
+    pub version: Option<String>,
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+/*
+ทะเบียนวัตถุเจือปนอาหารและผลการจัดกลุ่ม NOVA
+ - `Additive`: วัตถุเจือปน/ส่วนผสมทางอุตสาหกรรมที่บ่งชี้การแปรรูป (เช่น emulsifier, sweetener, flavour enhancer)
+ - `NovaClassification`: NOVA group (1–4) ที่คำนวณจากรายการส่วนประกอบ พร้อม ingredient ที่เป็นตัวกำหนด
+*/
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Additive {
+    pub id: i32,
+    pub e_number: Option<String>,
+    pub name: String,
+    pub function: String,
+    pub nova_group: i16,
+    pub aliases: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdditiveForm {
+    pub e_number: Option<String>,
+    pub name: String,
+    pub function: String,
+    /// Defaults to 4 (ultra-processed marker); 3 for additives typical of processed foods.
+    #[serde(default)]
+    pub nova_group: Option<i16>,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+/// An ingredient that decided the product's NOVA group.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NovaMarker {
+    pub ingredient: String,
+    /// Registry additive name, or the processed culinary ingredient that matched.
+    pub marker: String,
+    pub e_number: Option<String>,
+    pub function: String,
+    pub nova_group: i16,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct NovaClassification {
+    /// `None` when the product has no ingredient list.
+    pub group: Option<i16>,
+    pub markers: Vec<NovaMarker>,
+}
@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::errors::{AppError, FieldError};
+use super::nutrition::NutrientAmounts;
+use super::user_profiles::DailyTargets;
+/*
+คำขอและผลลัพธ์ของการจัดอาหารรายวันภายใต้งบประมาณ (`POST /api/v1/meal-plans/optimize`)
+ - หนึ่งหน่วยของ product = หนึ่ง serving ที่ราคา `price` (ค่าสารอาหารใน `products` เป็นค่าต่อ serving)
+ - `objective`: `min_cost` = ราคาต่ำสุดที่ผ่านทุกเงื่อนไข, `max_health` = คะแนนสุขภาพรวมสูงสุดภายในงบ
+ - `profile_id`: ใช้เป้าหมายต่อวันของ user profile เป็นค่า default ของเงื่อนไขที่ไม่ได้ส่งมา
+ - `exclude`: product, category หรืออาหารแปรรูปสูง (UPF) ที่ไม่ต้องการ
+*/
+
+pub const DEFAULT_MAX_SERVINGS_PER_PRODUCT: i32 = 3;
+pub const MAX_SERVINGS_PER_PRODUCT: i32 = 10;
+/// Health points of a serving are this minus its Nutri-Score points (at least 0).
+pub const HEALTH_POINTS_CEILING: f32 = 20.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanObjective {
+    #[default]
+    MinCost,
+    MaxHealth,
+}
+
+impl PlanObjective {
+    /// ORDER BY over the `products p` alias ranking the products most useful to this objective first,
+    /// so capping the candidate list keeps those.
+    pub fn candidate_order_sql(&self) -> String {
+        match self {
+            PlanObjective::MinCost => "p.price, p.id".to_string(),
+            // คะแนนสุขภาพต่อบาท; product ที่ไม่มี Nutri-Score ได้ 0 คะแนน
+            PlanObjective::MaxHealth => format!(
+                "GREATEST({ceiling} - COALESCE(p.nutri_score_points, {ceiling}), 0)::float8 / p.price::float8 DESC, p.price, p.id",
+                ceiling = HEALTH_POINTS_CEILING,
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlanExclusions {
+    #[serde(default)]
+    pub product_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub category_ids: Vec<i32>,
+    /// Leaves out products flagged `is_upf` or classified NOVA group 4.
+    #[serde(default)]
+    pub upf: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MealPlanRequest {
+    /// Daily budget in THB.
+    pub budget: f32,
+    #[serde(default)]
+    pub objective: PlanObjective,
+    pub profile_id: Option<Uuid>,
+    pub min_calories: Option<f32>,
+    pub max_calories: Option<f32>,
+    pub min_protein: Option<f32>,
+    pub max_sodium: Option<f32>,
+    pub max_sugar: Option<f32>,
+    pub max_saturated_fat: Option<f32>,
+    #[serde(default)]
+    pub exclude: PlanExclusions,
+    pub max_servings_per_product: Option<i32>,
+}
+
+impl MealPlanRequest {
+    pub fn validate(&self) -> Result<(), AppError> {
+        let mut violations = Vec::new();
+        if !(self.budget.is_finite() && self.budget > 0.0) {
+            violations.push(FieldError::new("budget", "must be greater than 0"));
+        }
+        let limits = [
+            ("min_calories", self.min_calories),
+            ("max_calories", self.max_calories),
+            ("min_protein", self.min_protein),
+            ("max_sodium", self.max_sodium),
+            ("max_sugar", self.max_sugar),
+            ("max_saturated_fat", self.max_saturated_fat),
+        ];
+        for (field, value) in limits {
+            if let Some(value) = value
+                && !(value.is_finite() && value >= 0.0)
+            {
+                violations.push(FieldError::new(field, "must not be negative"));
+            }
+        }
+        if let (Some(min), Some(max)) = (self.min_calories, self.max_calories)
+            && min > max
+        {
+            violations.push(FieldError::new("min_calories", "must not be greater than max_calories"));
+        }
+        if let Some(max_servings) = self.max_servings_per_product
+            && !(1..=MAX_SERVINGS_PER_PRODUCT).contains(&max_servings)
+        {
+            violations.push(FieldError::new(
+                "max_servings_per_product",
+                format!("must be between 1 and {}", MAX_SERVINGS_PER_PRODUCT),
+            ));
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::InvalidFields(violations))
+        }
+    }
+
+    pub fn max_servings(&self) -> i32 {
+        self.max_servings_per_product.unwrap_or(DEFAULT_MAX_SERVINGS_PER_PRODUCT)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MealPlanItem {
+    pub product_id: Uuid,
+    pub name: String,
+    pub brand: Option<String>,
+    pub servings: i32,
+    pub unit_price: f32,
+    pub subtotal: f32,
+    pub nutri_score_grade: Option<String>,
+}
+
+/// A constraint of the plan with the planned total it was checked against.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanConstraint {
+    /// e.g. `budget`, `min_calories`, `max_sodium`.
+    pub name: String,
+    pub limit: f32,
+    pub value: f32,
+    pub unit: String,
+    /// The LP relaxation's optimum sits on this limit (no slack): loosening it is what would improve the plan.
+    pub binding: bool,
+}
+
+/// How far one constraint would have to move, on its own, for a plan to exist.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanRelaxation {
+    pub name: String,
+    pub limit: f32,
+    /// `None` when relaxing this constraint alone is not enough.
+    pub suggested_limit: Option<f32>,
+    pub unit: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MealPlan {
+    pub feasible: bool,
+    pub objective: PlanObjective,
+    pub budget: f32,
+    /// Targets of `profile_id` the request's limits defaulted to.
+    pub profile_targets: Option<DailyTargets>,
+    pub candidates: usize,
+    /// The catalog had more eligible products than the optimizer takes; only the best ranked were considered.
+    pub candidates_truncated: bool,
+    /// Candidates left out because another candidate was at least as good on every count.
+    pub dominated_candidates: usize,
+    pub items: Vec<MealPlanItem>,
+    pub total_cost: f32,
+    /// Sum of the per-serving health points of `max_health` (20 minus Nutri-Score points).
+    pub health_points: f32,
+    pub totals: NutrientAmounts,
+    pub constraints: Vec<PlanConstraint>,
+    /// Only when infeasible: which constraint to relax and to what.
+    pub relax: Vec<PlanRelaxation>,
+}
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+use crate::errors::{AppError, FieldError};
+use super::food_log::QuantityUnit;
+use super::nutrition::NutrientAmounts;
+use super::products::Product;
+/*
+มื้ออาหารที่ประกอบจาก product หลายชิ้น (`/api/v1/meals`)
+ - `items`: product และปริมาณ (serving หรือกรัม เหมือนบันทึกอาหาร) ตามลำดับที่ส่งมา
+ - ค่าสารอาหาร, ราคารวม และสัดส่วน (`share`) ของแต่ละ item คำนวณจาก `products` ทุกครั้งที่อ่าน
+   แก้ค่าสารอาหารหรือราคาของ product แล้วมื้อที่มี product นั้นเปลี่ยนตาม
+ - PUT แทนที่ชื่อและ items ทั้งหมด
+*/
+
+const MAX_NAME_LENGTH: usize = 255;
+pub const MAX_MEAL_ITEMS: usize = 20;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Meal {
+    pub id: Uuid,
+    pub name: String,
+}
+
+/// A stored item with the product row it refers to.
+#[derive(Debug, Clone, FromRow)]
+pub struct MealItem {
+    pub meal_id: Uuid,
+    pub position: i32,
+    pub product_id: Uuid,
+    pub quantity: f32,
+    #[sqlx(try_from = "String")]
+    pub quantity_unit: QuantityUnit,
+    #[sqlx(json)]
+    pub product: Product,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MealItemForm {
+    pub product_id: Uuid,
+    pub quantity: f32,
+    #[serde(default)]
+    pub quantity_unit: QuantityUnit,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MealForm {
+    pub name: String,
+    pub items: Vec<MealItemForm>,
+}
+
+impl MealForm {
+    pub fn validate(&self) -> Result<(), AppError> {
+        let mut violations = Vec::new();
+        let name = self.name.trim();
+        if name.is_empty() {
+            violations.push(FieldError::new("name", "must not be empty"));
+        } else if name.chars().count() > MAX_NAME_LENGTH {
+            violations.push(FieldError::new("name", format!("must be at most {} characters", MAX_NAME_LENGTH)));
+        }
+        if self.items.is_empty() || self.items.len() > MAX_MEAL_ITEMS {
+            violations.push(FieldError::new("items", format!("must have between 1 and {} items", MAX_MEAL_ITEMS)));
+        }
+        for (index, item) in self.items.iter().enumerate() {
+            let max = item.quantity_unit.max_quantity();
+            if !(item.quantity.is_finite() && item.quantity > 0.0 && item.quantity <= max) {
+                violations.push(FieldError::new(
+                    format!("items[{}].quantity", index),
+                    format!("must be greater than 0 and at most {} {}", max, item.quantity_unit.as_str()),
+                ));
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::InvalidFields(violations))
+        }
+    }
+}
+
+/// Percent of the meal total that one item contributes.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct MealItemShare {
+    pub price: f32,
+    pub calories: f32,
+    pub protein: f32,
+    pub fat: f32,
+    pub saturated_fat: f32,
+    pub carbs: f32,
+    pub sugar: f32,
+    pub sodium: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MealItemResponse {
+    pub position: i32,
+    pub product_id: Uuid,
+    pub product_name: String,
+    pub brand: Option<String>,
+    pub quantity: f32,
+    pub quantity_unit: QuantityUnit,
+    /// `None` for grams of a product without a serving size; such items are left out of the totals.
+    pub servings: Option<f32>,
+    pub price: Option<f32>,
+    pub nutrients: Option<NutrientAmounts>,
+    pub share: Option<MealItemShare>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MealResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub items: Vec<MealItemResponse>,
+    pub total_price: f32,
+    pub totals: NutrientAmounts,
+    /// Items that could not be converted to servings and are not in the totals.
+    pub unconverted_items: usize,
+}
@@ -1,2 +1,21 @@
 pub mod products;
-pub mod pagination;
\ No newline at end of file
+pub mod pagination;
+pub mod categories;
+pub mod product_filter;
+pub mod product_sort;
+pub mod product_validation;
+pub mod nutrition;
+pub mod healthier_choice;
+pub mod ingredients;
+pub mod nutri_score;
+pub mod front_of_pack;
+pub mod user_profiles;
+pub mod meal_plan;
+
+pub mod value_metrics;
+pub mod food_log;
+pub mod meals;
+pub mod product_comparison;
+pub mod alternatives;
+pub mod barcodes;
+pub mod off_import;
\ No newline at end of file
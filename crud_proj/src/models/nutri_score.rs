@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+/*
+Nutri-Score (A–E) ของ product พร้อมคะแนนแยกรายองค์ประกอบ
+ - คะแนนลบ (N): พลังงาน, น้ำตาล, ไขมันอิ่มตัว, โซเดียม ต่อ 100 g/ml
+ - คะแนนบวก (P): ผัก/ผลไม้, ใยอาหาร, โปรตีน
+ - `missing`: ข้อมูลที่ไม่ทราบและถูกนับเป็น 0 คะแนน (เช่น `fibre`)
+*/
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct NegativePoints {
+    pub energy: i16,
+    pub sugars: i16,
+    pub saturated_fat: i16,
+    pub sodium: i16,
+    pub total: i16,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct PositivePoints {
+    pub fruit_veg: i16,
+    pub fibre: i16,
+    pub protein: i16,
+    pub total: i16,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct NutriScore {
+    pub grade: String,
+    /// Final score (`N - P`); lower is better.
+    pub points: i16,
+    /// Scored with the beverage table (products served in `ml`).
+    pub beverage: bool,
+    pub negative: NegativePoints,
+    pub positive: PositivePoints,
+    /// `false` when protein was left out because `N >= 11` and fruit/veg points are low.
+    pub protein_counted: bool,
+    pub missing: Vec<String>,
+}
@@ -0,0 +1,209 @@
+use serde::{Deserialize, Serialize};
+/*
+การ normalize ค่าสารอาหาร
+ - ค่าใน `products` เป็นค่าต่อ 1 serving (ตามฉลาก) และ `serving_size_grams` คือขนาด serving
+   ในหน่วย `serving_unit` (g หรือ ml)
+ - `per_100` = ค่าต่อ 100 g / 100 ml คำนวณจาก `serving_size_grams`
+ - product ที่ไม่มี serving size จะถูกระบุว่า `normalizable: false` และไม่มี `per_100`
+   (ไม่เดาว่าเป็น 100 g)
+ - `%DV` (ร้อยละของ Thai RDI) ส่งเฉพาะเมื่อ client ขอผ่าน `dv=true` หรือ `energy_kcal=...`
+*/
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ServingUnit {
+    #[default]
+    G,
+    Ml,
+}
+
+impl ServingUnit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ServingUnit::G => "g",
+            ServingUnit::Ml => "ml",
+        }
+    }
+}
+
+// ใช้กับ `#[sqlx(try_from = "String")]` ของ column `serving_unit`
+impl TryFrom<String> for ServingUnit {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "g" => Ok(ServingUnit::G),
+            "ml" => Ok(ServingUnit::Ml),
+            other => Err(format!("unknown serving unit {:?}", other)),
+        }
+    }
+}
+
+/// Basis that nutrient filters (`min_sugar`, ...) and sorts (`sort=sugar`) compare on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum NutrientBasis {
+    #[default]
+    #[serde(rename = "serving")]
+    Serving,
+    #[serde(rename = "per_100")]
+    Per100,
+}
+
+impl NutrientBasis {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NutrientBasis::Serving => "serving",
+            NutrientBasis::Per100 => "per_100",
+        }
+    }
+
+    /// Non-NULL `float8` expression for a whitelisted nutrient column of `products p`.
+    /// `Per100` divides by the serving size, so the query must also exclude products
+    /// without one (see `NutrientBasis::requires_serving_size`).
+    pub fn column_sql(&self, column: &str) -> String {
+        match self {
+            NutrientBasis::Serving => format!("p.{}::float8", column),
+            NutrientBasis::Per100 => format!("(p.{}::float8 * 100 / p.serving_size_grams::float8)", column),
+        }
+    }
+
+    pub fn requires_serving_size(&self) -> bool {
+        matches!(self, NutrientBasis::Per100)
+    }
+
+    /// Unrounded per-serving `value` on this basis, computed like `column_sql`; `None` without a serving size.
+    pub fn value(&self, value: f32, serving_size: Option<f32>) -> Option<f64> {
+        match self {
+            NutrientBasis::Serving => Some(f64::from(value)),
+            NutrientBasis::Per100 => serving_size
+                .filter(|size| size.is_finite() && *size > 0.0)
+                .map(|size| f64::from(value) * 100.0 / f64::from(size)),
+        }
+    }
+}
+
+pub fn round_to(value: f32, decimals: i32) -> f32 {
+    let factor = 10f32.powi(decimals);
+    (value * factor).round() / factor
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct NutrientAmounts {
+    pub calories: f32,
+    pub fat: f32,
+    pub sugar: f32,
+    pub sodium: f32,
+    pub protein: f32,
+    pub carbs: f32,
+    pub saturated_fat: f32,
+    pub cholesterol: f32,
+    pub vitamin_c: Option<f32>,
+    pub calcium: Option<f32>,
+    pub vitamin_b1: Option<f32>,
+    pub vitamin_a: Option<f32>,
+    pub fibre: Option<f32>,
+}
+
+impl NutrientAmounts {
+    /// Zero of every nutrient, optional ones included, as the start of a sum: an empty day or meal
+    /// is known to contain nothing.
+    pub fn zero() -> Self {
+        NutrientAmounts {
+            vitamin_c: Some(0.0),
+            calcium: Some(0.0),
+            vitamin_b1: Some(0.0),
+            vitamin_a: Some(0.0),
+            fibre: Some(0.0),
+            ..NutrientAmounts::default()
+        }
+    }
+
+    pub fn scaled(&self, factor: f32) -> Self {
+        // ปัดทศนิยม 2 ตำแหน่งเพื่อไม่ให้ส่ง noise ของ f32 ออกไป
+        let scale = |value: f32| (value * factor * 100.0).round() / 100.0;
+        NutrientAmounts {
+            calories: scale(self.calories),
+            fat: scale(self.fat),
+            sugar: scale(self.sugar),
+            sodium: scale(self.sodium),
+            protein: scale(self.protein),
+            carbs: scale(self.carbs),
+            saturated_fat: scale(self.saturated_fat),
+            cholesterol: scale(self.cholesterol),
+            vitamin_c: self.vitamin_c.map(scale),
+            calcium: self.calcium.map(scale),
+            vitamin_b1: self.vitamin_b1.map(scale),
+            vitamin_a: self.vitamin_a.map(scale),
+            fibre: self.fibre.map(scale),
+        }
+    }
+
+    /// Sum of both amounts; an optional nutrient is only known when it is known on both sides.
+    pub fn plus(&self, other: &NutrientAmounts) -> Self {
+        let optional = |a: Option<f32>, b: Option<f32>| a.zip(b).map(|(a, b)| a + b);
+        NutrientAmounts {
+            calories: self.calories + other.calories,
+            fat: self.fat + other.fat,
+            sugar: self.sugar + other.sugar,
+            sodium: self.sodium + other.sodium,
+            protein: self.protein + other.protein,
+            carbs: self.carbs + other.carbs,
+            saturated_fat: self.saturated_fat + other.saturated_fat,
+            cholesterol: self.cholesterol + other.cholesterol,
+            vitamin_c: optional(self.vitamin_c, other.vitamin_c),
+            calcium: optional(self.calcium, other.calcium),
+            vitamin_b1: optional(self.vitamin_b1, other.vitamin_b1),
+            vitamin_a: optional(self.vitamin_a, other.vitamin_a),
+            fibre: optional(self.fibre, other.fibre),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct NutritionFacts {
+    pub serving_size: Option<f32>,
+    pub serving_unit: ServingUnit,
+    /// `false` when the serving size is unknown, so `per_100` cannot be derived.
+    pub normalizable: bool,
+    pub per_serving: NutrientAmounts,
+    pub per_100: Option<NutrientAmounts>,
+}
+
+impl NutritionFacts {
+    pub fn new(per_serving: NutrientAmounts, serving_size: Option<f32>, serving_unit: ServingUnit) -> Self {
+        let per_100 = serving_size
+            .filter(|size| size.is_finite() && *size > 0.0)
+            .map(|size| per_serving.scaled(100.0 / size));
+        NutritionFacts {
+            serving_size,
+            serving_unit,
+            normalizable: per_100.is_some(),
+            per_serving,
+            per_100,
+        }
+    }
+}
+
+/// Opt-in `%DV` query options of the product endpoints.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct DailyValueQuery {
+    pub dv: Option<bool>,
+    /// Personal energy target; implies `dv=true`. Defaults to the 2,000 kcal Thai RDI basis.
+    pub energy_kcal: Option<f32>,
+}
+
+impl DailyValueQuery {
+    pub fn requested(&self) -> bool {
+        self.dv.unwrap_or(false) || self.energy_kcal.is_some()
+    }
+}
+
+/// Percent of the reference daily intake per nutrient; `calories` is percent of `energy_kcal`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct DailyValues {
+    pub energy_kcal: f32,
+    /// Reference intake (grams / milligrams / micrograms) each percentage is taken of.
+    pub reference: NutrientAmounts,
+    pub per_serving: NutrientAmounts,
+    pub per_100: Option<NutrientAmounts>,
+}
@@ -0,0 +1,156 @@
+use std::collections::{BTreeMap, HashMap};
+use serde::Serialize;
+
+use crate::errors::FieldError;
+/*
+นำเข้า product จากไฟล์ export ของ Open Food Facts (OFF) ที่ดาวน์โหลดไว้ (ไม่เรียก OFF API)
+ - รับ JSONL (หนึ่ง product ต่อบรรทัด) หรือ CSV/TSV (`en.openfoodfacts.org.products.csv` คั่นด้วย tab)
+ - นำเข้าเฉพาะ product ที่ขายในประเทศไทย (`countries_tags` มี `en:thailand`)
+ - `nutriments` ของ OFF เป็นค่าต่อ 100 g/ml หน่วยกรัม (ยกเว้นพลังงาน) แปลงเป็นค่าต่อ serving ของเรา
+   (ใช้ `serving_quantity` ถ้ามี ไม่เช่นนั้น serving = 100 g/ml และแถวนั้นอยู่ใน `warnings` ของรายงาน),
+   kJ → kcal, เกลือ → โซเดียม (× 400 mg/g)
+ - ใช้ barcode (`code`) เป็นตัวระบุ: barcode ใหม่สร้าง product, barcode ที่มีอยู่แล้วแก้เฉพาะ field ที่ OFF มี
+   (ราคา, `is_healthier` และบาร์โค้ดอื่นของ product คงเดิม)
+ - `--dry-run` ตรวจและจับคู่ทุกแถวโดยไม่เขียน database แล้วเขียนรายงานเหมือนกัน
+*/
+
+pub const DEFAULT_REPORT_PATH: &str = "off-import-report.json";
+pub const THAILAND_TAG: &str = "en:thailand";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    Jsonl,
+    Csv,
+}
+
+impl ImportFormat {
+    pub fn from_path(path: &str) -> Option<Self> {
+        let path = path.to_lowercase();
+        if path.ends_with(".jsonl") || path.ends_with(".json") || path.ends_with(".ndjson") {
+            Some(ImportFormat::Jsonl)
+        } else if path.ends_with(".csv") || path.ends_with(".tsv") {
+            Some(ImportFormat::Csv)
+        } else {
+            None
+        }
+    }
+}
+
+/// Arguments of `crud_proj import-off <file> [--dry-run] [--report <path>]`.
+#[derive(Debug, Clone)]
+pub struct ImportOptions {
+    pub path: String,
+    pub format: ImportFormat,
+    pub dry_run: bool,
+    pub report_path: String,
+}
+
+impl ImportOptions {
+    pub const USAGE: &str = "usage: crud_proj import-off <file.jsonl|file.csv> [--dry-run] [--report <path>]";
+
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut path = None;
+        let mut dry_run = false;
+        let mut report_path = DEFAULT_REPORT_PATH.to_string();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--dry-run" => dry_run = true,
+                "--report" => report_path = args.next().ok_or("--report needs a path")?.clone(),
+                flag if flag.starts_with("--") => return Err(format!("unknown option {}", flag)),
+                file if path.is_none() => path = Some(file.to_string()),
+                extra => return Err(format!("unexpected argument {}", extra)),
+            }
+        }
+        let path = path.ok_or("missing the export file")?;
+        let format = ImportFormat::from_path(&path)
+            .ok_or_else(|| format!("{} is not a .jsonl or .csv file", path))?;
+        Ok(ImportOptions { path, format, dry_run, report_path })
+    }
+}
+
+/// The fields of one OFF product the importer reads, from either export format.
+#[derive(Debug, Clone, Default)]
+pub struct OffProduct {
+    pub code: String,
+    pub product_name: Option<String>,
+    pub brands: Option<String>,
+    pub image_url: Option<String>,
+    pub countries_tags: Vec<String>,
+    pub categories_tags: Vec<String>,
+    pub ingredients_text: Option<String>,
+    pub serving_quantity: Option<f64>,
+    pub serving_unit: Option<String>,
+    pub quantity: Option<String>,
+    pub nova_group: Option<i16>,
+    /// `<nutrient>_100g` values keyed by OFF nutrient name (e.g. `saturated-fat`).
+    pub nutriments: HashMap<String, f64>,
+}
+
+impl OffProduct {
+    pub fn sold_in_thailand(&self) -> bool {
+        self.countries_tags.iter().any(|tag| tag == THAILAND_TAG)
+    }
+
+    pub fn per_100(&self, nutrient: &str) -> Option<f64> {
+        self.nutriments.get(nutrient).copied().filter(|value| value.is_finite())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportAction {
+    Created,
+    Updated,
+}
+
+/// A row that was not imported or needs a check; `line` counts from 1 (the CSV header is line 1).
+#[derive(Debug, Clone, Serialize)]
+pub struct RowIssue {
+    pub line: usize,
+    pub code: Option<String>,
+    pub reason: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<FieldError>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportReport {
+    pub source: String,
+    pub format: ImportFormat,
+    pub dry_run: bool,
+    pub rows: usize,
+    /// Rows of products not sold in Thailand; counted but not listed.
+    pub not_in_thailand: usize,
+    /// New products (would be created in a dry run).
+    pub created: usize,
+    /// Existing products matched by barcode (would be updated in a dry run).
+    pub updated: usize,
+    /// Rows left out on purpose, e.g. a barcode already seen earlier in the file.
+    pub skipped: Vec<RowIssue>,
+    /// Rows that could not be read or mapped, or failed product validation.
+    pub invalid: Vec<RowIssue>,
+    /// Imported rows whose values need a check, e.g. stored with a 100 g/ml serving because OFF had none.
+    pub warnings: Vec<RowIssue>,
+    /// OFF category tags without a matching category and how many imported rows had them.
+    pub unmapped_categories: BTreeMap<String, usize>,
+}
+
+impl ImportReport {
+    pub fn new(options: &ImportOptions) -> Self {
+        ImportReport {
+            source: options.path.clone(),
+            format: options.format,
+            dry_run: options.dry_run,
+            rows: 0,
+            not_in_thailand: 0,
+            created: 0,
+            updated: 0,
+            skipped: Vec::new(),
+            invalid: Vec::new(),
+            warnings: Vec::new(),
+            unmapped_categories: BTreeMap::new(),
+        }
+    }
+}
@@ -1,17 +1,230 @@
+use axum::http::Uri;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use serde::{Serialize, Deserialize};
+use uuid::Uuid;
 
+use crate::errors::AppError;
+use super::nutrition::NutrientBasis;
+use super::product_sort::{ProductSort, SortValue};
+
+// จำนวน item ต่อหน้าเมื่อ client ไม่ส่ง `limit` และเพดานสูงสุดที่ server ยอมให้
+pub const DEFAULT_PAGE_SIZE: i64 = 10;
+pub const MAX_PAGE_SIZE: i64 = 100;
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Pagination {
     pub limit: Option<i32>,
     pub offset: Option<i32>,
     pub search: Option<String>,
+    /// Opaque keyset cursor from a previous page's `next_cursor`; mutually exclusive with `offset`.
+    pub cursor: Option<String>,
+    /// Comma separated sort fields, `-` prefix for descending (see `ProductSort`).
+    pub sort: Option<String>,
+    /// `serving` (default) or `per_100`: the basis nutrient filters and sorts compare on.
+    /// `per_100` only lists products that have a serving size.
+    pub basis: Option<NutrientBasis>,
+}
+
+impl Pagination {
+    pub fn validate(&self) -> Result<(), AppError> {
+        if matches!(self.limit, Some(limit) if limit < 1) {
+            return Err(AppError::ValidationError("limit must be greater than 0".to_string()));
+        }
+        if matches!(self.offset, Some(offset) if offset < 0) {
+            return Err(AppError::ValidationError("offset must not be negative".to_string()));
+        }
+        if self.cursor.is_some() && self.offset.unwrap_or(0) > 0 {
+            return Err(AppError::ValidationError("cursor cannot be combined with offset".to_string()));
+        }
+        let sort = self.sort()?;
+        if let Some(cursor) = self.cursor()? {
+            cursor.check_sort(&sort)?;
+        }
+        Ok(())
+    }
+
+    pub fn cursor(&self) -> Result<Option<ProductCursor>, AppError> {
+        self.cursor.as_deref().map(ProductCursor::decode).transpose()
+    }
+
+    pub fn sort(&self) -> Result<ProductSort, AppError> {
+        ProductSort::parse(self.sort.as_deref(), self.search_term().is_some(), self.basis())
+    }
+
+    pub fn basis(&self) -> NutrientBasis {
+        self.basis.unwrap_or_default()
+    }
+
+    pub fn search_term(&self) -> Option<SearchTerm> {
+        let query = self.search.as_deref().map(str::trim).filter(|search| !search.is_empty())?;
+        Some(SearchTerm::new(query))
+    }
+
+    /// Effective page size: defaults to `DEFAULT_PAGE_SIZE` and is capped at `MAX_PAGE_SIZE`.
+    pub fn limit(&self) -> i64 {
+        self.limit
+            .map(|limit| (limit as i64).clamp(1, MAX_PAGE_SIZE))
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+    }
+
+    pub fn offset(&self) -> i64 {
+        self.offset.map(|offset| offset.max(0) as i64).unwrap_or(0)
+    }
+}
+
+/// Free-text search input: the trimmed query for trigram similarity and an escaped
+/// `%...%` pattern for substring matches.
+#[derive(Debug, Clone)]
+pub struct SearchTerm {
+    pub query: String,
+    pub pattern: String,
+}
+
+impl SearchTerm {
+    pub fn new(query: &str) -> Self {
+        let escaped = query
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        SearchTerm {
+            query: query.to_string(),
+            pattern: format!("%{}%", escaped),
+        }
+    }
+}
+
+/// Keyset position of the last row on a page: the value of every sort key plus `id` as
+/// tiebreaker, bound to the sort signature it was issued for.
+///
+/// Number keys are compared with `=` in the next query, so they must decode to the exact
+/// same `f64` (serde_json's `float_roundtrip` feature).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductCursor {
+    pub sort: String,
+    pub values: Vec<SortValue>,
+    pub id: Uuid,
+}
+
+impl ProductCursor {
+    pub fn check_sort(&self, sort: &ProductSort) -> Result<(), AppError> {
+        let matches_keys = sort.keys().len() == self.values.len()
+            && sort.keys().iter().zip(&self.values).all(|(key, value)| {
+                key.field.is_text() == matches!(value, SortValue::Text(_))
+            });
+        if self.sort != sort.signature() || !matches_keys {
+            return Err(AppError::ValidationError("cursor does not match the requested sort".to_string()));
+        }
+        Ok(())
+    }
+
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).unwrap_or_default();
+        URL_SAFE_NO_PAD.encode(json)
+    }
+
+    pub fn decode(cursor: &str) -> Result<Self, AppError> {
+        URL_SAFE_NO_PAD.decode(cursor)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .ok_or_else(|| AppError::ValidationError("cursor is invalid".to_string()))
+    }
+}
+
+/// One page of rows plus the number of rows matching the query across all pages.
+#[derive(Debug, Clone)]
+pub struct PageResult<T> {
+    pub items: Vec<T>,
+    pub total: i64,
+    pub has_more: bool,
+    pub next_cursor: Option<String>,
 }
 
 #[derive(Serialize, Default)]
 pub struct TemplateResponse<T> {
     pub items: Vec<T>,
-    pub total: usize,
-    pub limit: i32,
-    pub offset: i32,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub has_more: bool,
+    pub next: Option<String>,
+    pub prev: Option<String>,
+    pub next_cursor: Option<String>,
+}
+
+impl<T> TemplateResponse<T> {
+    /// Builds the response envelope, deriving navigation links from the request URI so
+    /// that every other query parameter (search, filters, ...) is carried over.
+    /// In cursor mode `next` follows `next_cursor` and there is no `prev` link, since
+    /// keyset pagination only walks forward.
+    pub fn from_page(page: PageResult<T>, pagination: &Pagination, uri: &Uri) -> Self {
+        let limit = pagination.limit();
+        let offset = pagination.offset();
+        let has_more = page.has_more;
+
+        let (next, prev) = if pagination.cursor.is_some() {
+            let next = page.next_cursor.as_ref()
+                .map(|cursor| page_link(uri, &[("limit", limit.to_string()), ("cursor", cursor.clone())]));
+            (next, None)
+        } else {
+            let next = has_more.then(|| page_link(uri, &[("limit", limit.to_string()), ("offset", (offset + limit).to_string())]));
+            let prev = (offset > 0).then(|| page_link(uri, &[("limit", limit.to_string()), ("offset", (offset - limit).max(0).to_string())]));
+            (next, prev)
+        };
+
+        TemplateResponse {
+            items: page.items,
+            total: page.total,
+            limit,
+            offset,
+            has_more,
+            next,
+            prev,
+            next_cursor: page.next_cursor,
+        }
+    }
+}
+
+// คง query parameter อื่น ๆ ไว้ แล้วแทนที่เฉพาะ parameter ที่ใช้เลื่อนหน้า
+fn page_link(uri: &Uri, overrides: &[(&str, String)]) -> String {
+    let mut params: Vec<String> = uri.query()
+        .unwrap_or("")
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .filter(|pair| {
+            let key = pair.split('=').next().unwrap_or("");
+            !matches!(key, "limit" | "offset" | "cursor")
+        })
+        .map(str::to_string)
+        .collect();
+    params.extend(overrides.iter().map(|(key, value)| format!("{}={}", key, value)));
+
+    format!("{}?{}", uri.path(), params.join("&"))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_keeps_computed_sort_values_bit_for_bit() {
+        // ค่าต่อบาทแบบนี้ parser ปกติของ serde_json อ่านกลับมาคลาด 1 ULP
+        let ratio = (0.1 + 0.37) / (11.0 * 1.7);
+        let values: Vec<f64> = (1..200).map(|price| ratio * 100.0 / price as f64).chain([ratio, -1e9, 0.0]).collect();
+        let cursor = ProductCursor {
+            sort: "-protein_per_baht,name".to_string(),
+            values: values.iter().copied().map(SortValue::Number).chain([SortValue::Text("Mama".to_string())]).collect(),
+            id: Uuid::nil(),
+        };
+
+        let decoded = ProductCursor::decode(&cursor.encode()).unwrap();
+        assert_eq!(decoded.values.len(), cursor.values.len());
+        for (value, expected) in decoded.values.iter().zip(&values) {
+            match value {
+                SortValue::Number(number) => assert_eq!(number.to_bits(), expected.to_bits()),
+                SortValue::Text(text) => panic!("number decoded as text {:?}", text),
+            }
+        }
+        assert_eq!(decoded.values.last(), Some(&SortValue::Text("Mama".to_string())));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::errors::{AppError, FieldError};
+use super::nutrition::{NutrientAmounts, NutrientBasis, ServingUnit};
+/*
+เทียบ product หลายชิ้นแบบข้างกัน (`POST /api/v1/products/compare`)
+ - ค่าสารอาหารทุกชิ้นอยู่บนฐานเดียวกัน: ต่อ 100 g/ml (`per_100`, default) หรือต่อ serving
+   product ที่ไม่มี serving size เทียบต่อ 100 ไม่ได้ ต้องใช้ `basis=serving`
+ - `per_100` ของแต่ละชิ้นอยู่ในหน่วยของชิ้นนั้น (`serving_unit`: g หรือ ml)
+ - ต่อสารอาหาร: ชิ้นที่ดีที่สุด/แย่ที่สุดและผลต่าง ตามทิศทาง `better`
+   (`lower` สำหรับพลังงาน, ไขมัน, น้ำตาล, โซเดียม ฯลฯ และ `higher` สำหรับโปรตีน, ใยอาหาร, วิตามิน, แคลเซียม)
+ - สารอาหารที่ไม่ได้กรอกไม่นับในการเทียบ ส่วนราคาต่อ 100 เทียบเฉพาะชิ้นที่มีราคาและ serving size
+*/
+
+pub const MIN_COMPARED_PRODUCTS: usize = 2;
+pub const MAX_COMPARED_PRODUCTS: usize = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductComparisonRequest {
+    pub product_ids: Vec<Uuid>,
+    /// Defaults to `per_100`.
+    pub basis: Option<NutrientBasis>,
+}
+
+impl ProductComparisonRequest {
+    pub fn basis(&self) -> NutrientBasis {
+        self.basis.unwrap_or(NutrientBasis::Per100)
+    }
+
+    pub fn validate(&self) -> Result<(), AppError> {
+        let count = self.product_ids.len();
+        if !(MIN_COMPARED_PRODUCTS..=MAX_COMPARED_PRODUCTS).contains(&count) {
+            return Err(AppError::InvalidFields(vec![FieldError::new(
+                "product_ids",
+                format!("must have between {} and {} products", MIN_COMPARED_PRODUCTS, MAX_COMPARED_PRODUCTS),
+            )]));
+        }
+        let violations: Vec<FieldError> = self.product_ids.iter()
+            .enumerate()
+            .filter(|(index, id)| self.product_ids[..*index].contains(id))
+            .map(|(index, id)| FieldError::new(format!("product_ids[{}]", index), format!("product {} is listed more than once", id)))
+            .collect();
+        if !violations.is_empty() {
+            return Err(AppError::InvalidFields(violations));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Better {
+    Higher,
+    Lower,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComparedProduct {
+    pub id: Uuid,
+    pub name: String,
+    pub brand: Option<String>,
+    pub serving_size: Option<f32>,
+    pub serving_unit: ServingUnit,
+    pub price: f32,
+    /// Price per 100 g/ml; `None` without a price or a serving size.
+    pub price_per_100: Option<f32>,
+    /// On the basis of the comparison.
+    pub nutrients: NutrientAmounts,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ComparedValue {
+    pub product_id: Uuid,
+    pub value: f32,
+}
+
+/// Best and worst product on one nutrient (or on price); `None` when no product has a value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricComparison {
+    pub metric: String,
+    pub unit: String,
+    pub better: Better,
+    pub best: Option<ComparedValue>,
+    pub worst: Option<ComparedValue>,
+    /// Absolute gap between the best and the worst value.
+    pub difference: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductComparison {
+    pub basis: NutrientBasis,
+    pub products: Vec<ComparedProduct>,
+    pub nutrients: Vec<MetricComparison>,
+    pub price_per_100: MetricComparison,
+}
@@ -0,0 +1,125 @@
+use serde::Deserialize;
+
+use crate::errors::AppError;
+use super::front_of_pack::{warning_profile, WarningProfile};
+/*
+Filter ของ `GET /api/v1/products`
+ - ช่วงค่าสารอาหาร/ราคา: `min_*` / `max_*` (รวมขอบ)
+ - ค่าสารอาหารเทียบตาม `basis` ของ `Pagination` (ต่อ serving หรือต่อ 100 g/ml); ราคาเป็นราคาต่อชิ้นเสมอ
+ - `is_upf`, `is_healthier`: กรองตาม flag
+ - `brand`: ชื่อแบรนด์ (ไม่สนตัวพิมพ์เล็ก/ใหญ่) คั่นหลายค่าด้วย `,`
+ - `categories`: category id คั่นด้วย `,` — product ต้องอยู่ในอย่างน้อยหนึ่ง category
+ - `nova_group`: NOVA group ที่คำนวณจากส่วนประกอบ (1–4) คั่นด้วย `,`
+ - `nutri_score`: เกรด Nutri-Score (A–E, ไม่สนตัวพิมพ์) คั่นด้วย `,` เช่น `A,B`
+ - `no_red_lights`: `true` = ไม่มีไฟแดงเลย, `false` = มีไฟแดงอย่างน้อยหนึ่งดวง (เฉพาะ product ที่มี serving size)
+ - `no_high_in`: `true` = ไม่มี "HIGH IN" warning ตาม `warning_profile`, `false` = มีอย่างน้อยหนึ่ง
+ - `warning_profile`: threshold profile ของ warning ทั้งใน filter และใน response (default `chile`)
+*/
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ProductFilter {
+    pub min_calories: Option<f32>,
+    pub max_calories: Option<f32>,
+    pub min_sugar: Option<f32>,
+    pub max_sugar: Option<f32>,
+    pub min_sodium: Option<f32>,
+    pub max_sodium: Option<f32>,
+    pub min_fat: Option<f32>,
+    pub max_fat: Option<f32>,
+    pub min_protein: Option<f32>,
+    pub max_protein: Option<f32>,
+    pub min_price: Option<f32>,
+    pub max_price: Option<f32>,
+
+    pub is_upf: Option<bool>,
+    pub nova_group: Option<String>,
+    pub nutri_score: Option<String>,
+    pub no_red_lights: Option<bool>,
+    pub no_high_in: Option<bool>,
+    pub warning_profile: Option<String>,
+    pub is_healthier: Option<bool>,
+    pub brand: Option<String>,
+    pub categories: Option<String>,
+}
+
+/// A numeric range filter on a whitelisted `products` column.
+pub struct RangeFilter {
+    pub column: &'static str,
+    /// Nutrient columns follow the requested `NutrientBasis`; others compare as stored.
+    pub nutrient: bool,
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+}
+
+impl ProductFilter {
+    pub fn validate(&self) -> Result<(), AppError> {
+        for range in self.ranges() {
+            if let (Some(min), Some(max)) = (range.min, range.max)
+                && min > max
+            {
+                return Err(AppError::ValidationError(format!(
+                    "min_{} must not be greater than max_{}", range.column, range.column
+                )));
+            }
+        }
+        self.category_ids()?;
+        self.nova_groups()?;
+        self.nutri_score_grades()?;
+        self.warning_profile()?;
+        Ok(())
+    }
+
+    pub fn ranges(&self) -> [RangeFilter; 6] {
+        [
+            RangeFilter { column: "calories", nutrient: true, min: self.min_calories, max: self.max_calories },
+            RangeFilter { column: "sugar", nutrient: true, min: self.min_sugar, max: self.max_sugar },
+            RangeFilter { column: "sodium", nutrient: true, min: self.min_sodium, max: self.max_sodium },
+            RangeFilter { column: "fat", nutrient: true, min: self.min_fat, max: self.max_fat },
+            RangeFilter { column: "protein", nutrient: true, min: self.min_protein, max: self.max_protein },
+            RangeFilter { column: "price", nutrient: false, min: self.min_price, max: self.max_price },
+        ]
+    }
+
+    /// Lower-cased brand names to match exactly.
+    pub fn brands(&self) -> Vec<String> {
+        split_list(self.brand.as_deref())
+            .map(str::to_lowercase)
+            .collect()
+    }
+
+    pub fn category_ids(&self) -> Result<Vec<i32>, AppError> {
+        split_list(self.categories.as_deref())
+            .map(|id| id.parse::<i32>().map_err(|_| {
+                AppError::ValidationError(format!("categories contains an invalid id: {:?}", id))
+            }))
+            .collect()
+    }
+
+    pub fn nova_groups(&self) -> Result<Vec<i16>, AppError> {
+        split_list(self.nova_group.as_deref())
+            .map(|group| match group.parse::<i16>() {
+                Ok(group) if (1..=4).contains(&group) => Ok(group),
+                _ => Err(AppError::ValidationError(format!("nova_group must be between 1 and 4, got {:?}", group))),
+            })
+            .collect()
+    }
+
+    pub fn nutri_score_grades(&self) -> Result<Vec<String>, AppError> {
+        split_list(self.nutri_score.as_deref())
+            .map(|grade| match grade.to_uppercase().as_str() {
+                grade @ ("A" | "B" | "C" | "D" | "E") => Ok(grade.to_string()),
+                _ => Err(AppError::ValidationError(format!("nutri_score must be one of A, B, C, D, E, got {:?}", grade))),
+            })
+            .collect()
+    }
+
+    pub fn warning_profile(&self) -> Result<&'static WarningProfile, AppError> {
+        warning_profile(self.warning_profile.as_deref())
+    }
+}
+
+fn split_list(value: Option<&str>) -> impl Iterator<Item = &str> {
+    value.unwrap_or("")
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
@@ -0,0 +1,196 @@
+use serde::{Deserialize, Serialize};
+
+use crate::errors::AppError;
+use super::nutrition::NutrientBasis;
+/*
+การเรียงลำดับ product list ผ่าน `sort=price,-protein`
+ - ชื่อ field ต้องอยู่ใน whitelist ของ `SortField` เท่านั้น (ไม่มีการต่อ string จาก client ลง SQL)
+ - `-` นำหน้า = เรียงจากมากไปน้อย
+ - `p.id` ต่อท้ายเสมอเป็น tiebreaker เพื่อให้ลำดับคงที่ทั้ง offset และ cursor
+ - `relevance` ใช้ได้เฉพาะเมื่อมี `search` และเป็นค่า default (`-relevance,name`) ในกรณีนั้น
+ - field สารอาหาร (calories, sugar, sodium, protein) เรียงตาม `basis` (ต่อ serving หรือต่อ 100 g/ml)
+ - `protein_per_baht`, `kcal_per_baht`, `nutrient_density_per_baht` เป็นค่าต่อบาท (ดู `models::value_metrics`)
+ - `nutri_score` เรียงตามคะแนน (น้อย = ดี) product ที่ยังไม่มีเกรดอยู่ท้ายสุดเมื่อเรียงจากน้อยไปมาก
+ - `nutrient_density_per_baht` ของ product ที่ยังไม่มีคะแนนอยู่ท้ายสุดเมื่อเรียงจากมากไปน้อย
+*/
+
+// ต่ำกว่า density ต่อบาทที่เป็นไปได้จริง (ค่า sort ต้องไม่เป็น NULL เพื่อให้ keyset cursor เทียบได้)
+const UNRANKED_DENSITY_PER_BAHT: f64 = -1e9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortField {
+    Name,
+    Price,
+    Calories,
+    Sugar,
+    Sodium,
+    Protein,
+    ProteinPerBaht,
+    KcalPerBaht,
+    NutrientDensityPerBaht,
+    NutriScore,
+    Relevance,
+}
+
+impl SortField {
+    pub const ALL: [SortField; 11] = [
+        SortField::Name,
+        SortField::Price,
+        SortField::Calories,
+        SortField::Sugar,
+        SortField::Sodium,
+        SortField::Protein,
+        SortField::ProteinPerBaht,
+        SortField::KcalPerBaht,
+        SortField::NutrientDensityPerBaht,
+        SortField::NutriScore,
+        SortField::Relevance,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortField::Name => "name",
+            SortField::Price => "price",
+            SortField::Calories => "calories",
+            SortField::Sugar => "sugar",
+            SortField::Sodium => "sodium",
+            SortField::Protein => "protein",
+            SortField::ProteinPerBaht => "protein_per_baht",
+            SortField::KcalPerBaht => "kcal_per_baht",
+            SortField::NutrientDensityPerBaht => "nutrient_density_per_baht",
+            SortField::NutriScore => "nutri_score",
+            SortField::Relevance => "relevance",
+        }
+    }
+
+    /// SQL expression over the `products p` alias; never NULL so keyset comparisons hold.
+    pub fn sql(&self, basis: NutrientBasis) -> String {
+        match self {
+            SortField::Name => "p.name".to_string(),
+            SortField::Price => "COALESCE(p.price, 0)::float8".to_string(),
+            SortField::Calories => basis.column_sql("calories"),
+            SortField::Sugar => basis.column_sql("sugar"),
+            SortField::Sodium => basis.column_sql("sodium"),
+            SortField::Protein => basis.column_sql("protein"),
+            // ต่อบาทของทั้งชิ้น ไม่ขึ้นกับ basis
+            SortField::ProteinPerBaht => "COALESCE(p.protein::float8 / NULLIF(p.price, 0)::float8, 0)".to_string(),
+            SortField::KcalPerBaht => "COALESCE(p.calories::float8 / NULLIF(p.price, 0)::float8, 0)".to_string(),
+            // คะแนนติดลบได้ จึงให้ product ที่ยังไม่ได้คำนวณ (NULL หลัง migration 0008) หรือไม่มีราคา
+            // ต่ำกว่าทุกค่าจริง: อยู่ท้ายสุดเมื่อเรียงจากมากไปน้อย
+            SortField::NutrientDensityPerBaht => format!(
+                "COALESCE(p.nutrient_density::float8 / NULLIF(p.price, 0)::float8, {})", UNRANKED_DENSITY_PER_BAHT
+            ),
+            // คะแนนต่ำสุด/สูงสุดที่เป็นไปได้อยู่ราว -15..40 จึงใช้ 100 แทนค่าที่ยังไม่มีเกรด
+            SortField::NutriScore => "COALESCE(p.nutri_score_points, 100)::float8".to_string(),
+            // คำนวณใน LATERAL subquery `r` ของ product list เมื่อมีการค้นหา
+            SortField::Relevance => "r.relevance".to_string(),
+        }
+    }
+
+    pub fn is_text(&self) -> bool {
+        matches!(self, SortField::Name)
+    }
+
+    fn parse(name: &str) -> Option<SortField> {
+        SortField::ALL.into_iter().find(|field| field.as_str() == name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortKey {
+    pub field: SortField,
+    pub descending: bool,
+}
+
+/// Value of one sort key for the last row of a page, stored inside the cursor.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum SortValue {
+    Number(f64),
+    Text(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProductSort {
+    keys: Vec<SortKey>,
+    basis: NutrientBasis,
+}
+
+impl Default for ProductSort {
+    fn default() -> Self {
+        ProductSort {
+            keys: vec![SortKey { field: SortField::Name, descending: false }],
+            basis: NutrientBasis::default(),
+        }
+    }
+}
+
+impl ProductSort {
+    pub fn parse(sort: Option<&str>, searching: bool, basis: NutrientBasis) -> Result<Self, AppError> {
+        let Some(sort) = sort.map(str::trim).filter(|sort| !sort.is_empty()) else {
+            let default = if searching { ProductSort::by_relevance() } else { ProductSort::default() };
+            return Ok(ProductSort { basis, ..default });
+        };
+
+        let mut keys: Vec<SortKey> = Vec::new();
+        for part in sort.split(',').map(str::trim) {
+            let (name, descending) = match part.strip_prefix('-') {
+                Some(name) => (name, true),
+                None => (part, false),
+            };
+            let field = SortField::parse(name).ok_or_else(|| {
+                let allowed: Vec<&str> = SortField::ALL.iter().map(SortField::as_str).collect();
+                AppError::ValidationError(format!(
+                    "Unknown sort field {:?}; allowed: {}", name, allowed.join(", ")
+                ))
+            })?;
+            if field == SortField::Relevance && !searching {
+                return Err(AppError::ValidationError("Sorting by relevance requires search".to_string()));
+            }
+            if keys.iter().any(|key| key.field == field) {
+                return Err(AppError::ValidationError(format!("Duplicate sort field {:?}", name)));
+            }
+            keys.push(SortKey { field, descending });
+        }
+        Ok(ProductSort { keys, basis })
+    }
+
+    fn by_relevance() -> Self {
+        ProductSort {
+            keys: vec![
+                SortKey { field: SortField::Relevance, descending: true },
+                SortKey { field: SortField::Name, descending: false },
+            ],
+            basis: NutrientBasis::default(),
+        }
+    }
+
+    pub fn keys(&self) -> &[SortKey] {
+        &self.keys
+    }
+
+    pub fn sql(&self, key: &SortKey) -> String {
+        key.field.sql(self.basis)
+    }
+
+    /// Canonical form (e.g. `price,-protein` or `sugar@per_100`) used to tie a cursor to
+    /// the sort it was issued for.
+    pub fn signature(&self) -> String {
+        let keys = self.keys.iter()
+            .map(|key| format!("{}{}", if key.descending { "-" } else { "" }, key.field.as_str()))
+            .collect::<Vec<_>>()
+            .join(",");
+        match self.basis {
+            NutrientBasis::Serving => keys,
+            basis => format!("{}@{}", keys, basis.as_str()),
+        }
+    }
+
+    pub fn order_by(&self) -> String {
+        let mut terms: Vec<String> = self.keys.iter()
+            .map(|key| format!("{} {}", self.sql(key), if key.descending { "DESC" } else { "ASC" }))
+            .collect();
+        terms.push("p.id ASC".to_string());
+        terms.join(", ")
+    }
+}
@@ -0,0 +1,186 @@
+use crate::errors::{AppError, FieldError};
+
+use super::barcodes::Barcode;
+use super::products::{ProductForm, ProductPatch, ProductResponse};
+/*
+Domain validation ของ ProductForm
+ - เก็บ error ทุกข้อพร้อม field path แล้วคืนพร้อมกันเป็น 422 (`AppError::InvalidFields`)
+ - ค่าสารอาหาร/ราคาต้องไม่ติดลบ, ไขมันอิ่มตัว <= ไขมัน, น้ำตาล <= คาร์โบไฮเดรต
+ - พลังงานต้องสอดคล้องกับ Atwater factors (4/4/9 kcal ต่อกรัม) ภายในช่วงที่ยอมรับได้
+ - บาร์โค้ดต้องเป็น EAN-13/UPC-A ที่ check digit ถูกต้อง
+*/
+
+// ความยาวสูงสุดของ VARCHAR(255) ใน migration
+const MAX_TEXT_LENGTH: usize = 255;
+
+// ใยอาหาร, sugar alcohol, การปัดเศษบนฉลาก ทำให้ค่าจริงคลาดจาก 4/4/9 ได้พอสมควร
+const ATWATER_ABSOLUTE_TOLERANCE_KCAL: f32 = 15.0;
+const ATWATER_RELATIVE_TOLERANCE: f32 = 0.25;
+
+#[derive(Default)]
+struct Violations(Vec<FieldError>);
+
+impl Violations {
+    fn add(&mut self, field: &str, message: impl Into<String>) {
+        self.0.push(FieldError::new(field, message));
+    }
+
+    fn non_negative(&mut self, field: &str, value: f32) {
+        if !value.is_finite() {
+            self.add(field, "must be a finite number");
+        } else if value < 0.0 {
+            self.add(field, "must not be negative");
+        }
+    }
+
+    fn optional_non_negative(&mut self, field: &str, value: Option<f32>) {
+        if let Some(value) = value {
+            self.non_negative(field, value);
+        }
+    }
+
+    fn max_length(&mut self, field: &str, value: Option<&str>) {
+        if let Some(value) = value
+            && value.chars().count() > MAX_TEXT_LENGTH
+        {
+            self.add(field, format!("must be at most {} characters", MAX_TEXT_LENGTH));
+        }
+    }
+
+    fn into_result(self) -> Result<(), AppError> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::InvalidFields(self.0))
+        }
+    }
+}
+
+/// Energy implied by the macronutrients using Atwater general factors.
+pub fn atwater_kcal(protein: f32, carbs: f32, fat: f32) -> f32 {
+    4.0 * protein + 4.0 * carbs + 9.0 * fat
+}
+
+impl ProductForm {
+    pub fn validate(&self) -> Result<(), AppError> {
+        let mut violations = Violations::default();
+
+        if self.name.trim().is_empty() {
+            violations.add("name", "must not be empty");
+        }
+        violations.max_length("name", Some(&self.name));
+        violations.max_length("brand", self.brand.as_deref());
+        violations.max_length("image_url", self.image_url.as_deref());
+
+        if let Some(serving_size) = self.serving_size_grams
+            && !(serving_size.is_finite() && serving_size > 0.0)
+        {
+            violations.add("serving_size_grams", "must be greater than 0");
+        }
+
+        if self.calories < 0 {
+            violations.add("calories", "must not be negative");
+        }
+        violations.non_negative("fat", self.fat);
+        violations.non_negative("sugar", self.sugar);
+        violations.non_negative("sodium", self.sodium);
+        violations.non_negative("protein", self.protein);
+        violations.non_negative("carbs", self.carbs);
+        violations.non_negative("saturated_fat", self.saturated_fat);
+        violations.non_negative("cholesterol", self.cholesterol);
+        violations.optional_non_negative("vitamin_c", self.vitamin_c);
+        violations.optional_non_negative("calcium", self.calcium);
+        violations.optional_non_negative("vitamin_b1", self.vitamin_b1);
+        violations.optional_non_negative("vitamin_a", self.vitamin_a);
+        violations.optional_non_negative("fibre", self.fibre);
+        if let Some(percent) = self.fruit_veg_percent
+            && !(0.0..=100.0).contains(&percent)
+        {
+            violations.add("fruit_veg_percent", "must be between 0 and 100");
+        }
+        violations.non_negative("price", self.price);
+
+        if self.saturated_fat > self.fat {
+            violations.add("saturated_fat", format!("must not exceed fat ({} g)", self.fat));
+        }
+        if self.sugar > self.carbs {
+            violations.add("sugar", format!("must not exceed carbs ({} g)", self.carbs));
+        }
+
+        if let Some(serving_size) = self.serving_size_grams {
+            let macros = self.fat + self.carbs + self.protein;
+            if serving_size > 0.0 && macros > serving_size {
+                violations.add("serving_size_grams", format!(
+                    "fat + carbs + protein ({} g) exceed the serving size", macros
+                ));
+            }
+        }
+
+        let expected = atwater_kcal(self.protein, self.carbs, self.fat);
+        let calories = self.calories as f32;
+        let tolerance = ATWATER_ABSOLUTE_TOLERANCE_KCAL + ATWATER_RELATIVE_TOLERANCE * calories.max(expected);
+        if self.calories >= 0 && (calories - expected).abs() > tolerance {
+            violations.add("calories", format!(
+                "{} kcal is inconsistent with the macronutrients (~{:.0} kcal from 4/4/9)",
+                self.calories, expected
+            ));
+        }
+
+        for (index, ingredient) in self.ingredients.iter().enumerate() {
+            let field = format!("ingredients[{}]", index);
+            if ingredient.trim().is_empty() {
+                violations.add(&field, "must not be empty");
+            }
+            violations.max_length(&field, Some(ingredient));
+        }
+
+        for (index, code) in self.barcodes.iter().enumerate() {
+            if let Err(message) = Barcode::parse(code) {
+                violations.add(&format!("barcodes[{}]", index), message);
+            }
+        }
+
+        for (index, id) in self.categories_ids.iter().enumerate() {
+            if *id <= 0 {
+                violations.add(&format!("categories_ids[{}]", index), "must be a positive id");
+            }
+        }
+
+        violations.into_result()
+    }
+}
+
+impl ProductPatch {
+    /// The full form that results from applying this patch to the stored product, so a
+    /// partial update is held to the same rules as a full one.
+    pub fn merged_with(&self, current: &ProductResponse) -> ProductForm {
+        ProductForm {
+            id: Some(current.id.to_string()),
+            name: self.name.clone().unwrap_or_else(|| current.name.clone()),
+            brand: self.brand.clone().unwrap_or_else(|| current.brand.clone()),
+            image_url: self.image_url.clone().unwrap_or_else(|| current.image_url.clone()),
+            categories_ids: self.categories_ids.clone().unwrap_or_else(|| current.categories_ids.clone()),
+            serving_size_grams: self.serving_size_grams.unwrap_or(current.serving_size_grams),
+            serving_unit: self.serving_unit.unwrap_or(current.serving_unit),
+            calories: self.calories.unwrap_or(current.calories),
+            fat: self.fat.unwrap_or(current.fat),
+            sugar: self.sugar.unwrap_or(current.sugar),
+            sodium: self.sodium.unwrap_or(current.sodium),
+            protein: self.protein.unwrap_or(current.protein),
+            carbs: self.carbs.unwrap_or(current.carbs),
+            saturated_fat: self.saturated_fat.unwrap_or(current.saturated_fat),
+            cholesterol: self.cholesterol.unwrap_or(current.cholesterol),
+            vitamin_c: self.vitamin_c.unwrap_or(current.vitamin_c),
+            calcium: self.calcium.unwrap_or(current.calcium),
+            vitamin_b1: self.vitamin_b1.unwrap_or(current.vitamin_b1),
+            vitamin_a: self.vitamin_a.unwrap_or(current.vitamin_a),
+            fibre: self.fibre.unwrap_or(current.fibre),
+            fruit_veg_percent: self.fruit_veg_percent.unwrap_or(current.fruit_veg_percent),
+            price: self.price.unwrap_or(current.price),
+            is_upf: self.is_upf.unwrap_or(current.is_upf),
+            is_healthier: self.is_healthier.unwrap_or(current.is_healthier),
+            ingredients: self.ingredients.clone().unwrap_or_else(|| current.ingredients.clone()),
+            barcodes: self.barcodes.clone().unwrap_or_else(|| current.barcodes.clone()),
+        }
+    }
+}
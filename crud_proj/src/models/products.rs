@@ -1,13 +1,27 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use sqlx::prelude::FromRow;
 use uuid::Uuid;
+
+use super::ingredients::{NovaClassification, NovaMarker};
+use super::front_of_pack::FrontOfPack;
+use super::nutri_score::NutriScore;
+use super::nutrition::{DailyValues, NutrientAmounts, NutritionFacts, ServingUnit};
+use super::value_metrics::ValueMetrics;
 /*
 Product Model
  - `categories`: Category to which the product belongs
+ - `categories_ids`: `categories.id` values (`INT`) to link the product to; unknown ids are rejected with 422
  - `brand`: Name of the product owner (`Option<String>`)
  - `image_url`: URL of the product image (`Option<String>`)
- - `serving_size_grams`: Serving size in grams
- - `is_upf`: Whether the product is ultra-processed food
+ - `serving_size_grams`: Serving size in grams (millilitres when `serving_unit` is `ml`)
+ - `serving_unit`: `g` or `ml`; decides whether normalized values are per 100 g or per 100 ml
+ - `is_upf`: Whether the product is ultra-processed food (entered by hand)
+ - `ingredients`: Ingredient list in label order
+ - `nova_group`: NOVA group (1–4) computed from `ingredients`, `nova_markers` are the ingredients that decided it
+ - `fibre`: Dietary fibre in grams per serving (`Option<f32>`)
+ - `fruit_veg_percent`: Share of fruit, vegetables, pulses and nuts in percent (`Option<f32>`)
+ - `nutri_score`: Nutri-Score grade (A–E) and points computed from the nutrients on every write
+ - `value`: protein, kcal and nutrient density per baht of `price` (see `models::value_metrics`)
  - `is_healthier`: Whether the product is certified [Healthier Choice](http://healthierlogo.com/)
  */
 
@@ -19,6 +33,8 @@ pub struct Product {
     pub image_url: Option<String>,
 
     pub serving_size_grams: Option<f32>,
+    #[sqlx(try_from = "String")]
+    pub serving_unit: ServingUnit,
     pub calories: i32,
     pub fat: f32,
     pub sugar: f32,
@@ -32,20 +48,44 @@ pub struct Product {
     pub calcium: Option<f32>,
     pub vitamin_b1: Option<f32>,
     pub vitamin_a: Option<f32>,
+    pub fibre: Option<f32>,
+    pub fruit_veg_percent: Option<f32>,
 
     pub price: f32,
     pub is_upf: bool,
+    pub nova_group: Option<i16>,
+    pub nutri_score_grade: Option<String>,
     pub is_healthier: bool,
 }
 
+/// Values computed from a product form by the service and stored with it on every write.
+#[derive(Debug, Clone, Default)]
+pub struct DerivedScores {
+    pub nova: NovaClassification,
+    /// `None` when the product has no serving size to normalize to 100 g/ml.
+    pub nutri_score: Option<NutriScore>,
+    /// NRF-style score per serving that `nutrient_density_per_baht` ranks by.
+    pub nutrient_density: f32,
+}
+
+/// Result of recomputing the derived scores of every stored product.
+#[derive(Debug, Clone, Serialize)]
+pub struct DerivedScoresReport {
+    pub products: usize,
+    /// Products that got a Nutri-Score (the rest have no serving size).
+    pub nutri_scored: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProductForm {
     pub id: Option<String>,
     pub name: String,
     pub brand: Option<String>,
     pub image_url: Option<String>,
-    pub categories_ids: Vec<String>,
+    pub categories_ids: Vec<i32>,
     pub serving_size_grams: Option<f32>,
+    #[serde(default)]
+    pub serving_unit: ServingUnit,
     pub calories: i32,
     pub fat: f32,
     pub sugar: f32,
@@ -58,9 +98,97 @@ pub struct ProductForm {
     pub calcium: Option<f32>,
     pub vitamin_b1: Option<f32>,
     pub vitamin_a: Option<f32>,
+    #[serde(default)]
+    pub fibre: Option<f32>,
+    #[serde(default)]
+    pub fruit_veg_percent: Option<f32>,
     pub price: f32,
     pub is_upf: bool,
     pub is_healthier: bool,
+    #[serde(default)]
+    pub ingredients: Vec<String>,
+    /// EAN-13 or UPC-A codes, one per pack size.
+    #[serde(default)]
+    pub barcodes: Vec<String>,
+}
+
+/*
+Body ของ PATCH: field ที่ไม่ส่งมาจะไม่ถูกแก้ไข
+ - field ที่ nullable (`brand`, `image_url`, `serving_size_grams`, vitamin/calcium, `fibre`, `fruit_veg_percent`) ส่ง `null` เพื่อเคลียร์ค่า
+ - field ที่บังคับมีค่าห้ามส่ง `null`
+ - `categories_ids`, `ingredients`, `barcodes` ถ้าส่งมาจะแทนที่ของเดิมทั้งหมด
+ */
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProductPatch {
+    #[serde(default, deserialize_with = "non_null")]
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub brand: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub image_url: Option<Option<String>>,
+    #[serde(default, deserialize_with = "non_null")]
+    pub categories_ids: Option<Vec<i32>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub serving_size_grams: Option<Option<f32>>,
+    #[serde(default, deserialize_with = "non_null")]
+    pub serving_unit: Option<ServingUnit>,
+    #[serde(default, deserialize_with = "non_null")]
+    pub calories: Option<i32>,
+    #[serde(default, deserialize_with = "non_null")]
+    pub fat: Option<f32>,
+    #[serde(default, deserialize_with = "non_null")]
+    pub sugar: Option<f32>,
+    #[serde(default, deserialize_with = "non_null")]
+    pub sodium: Option<f32>,
+    #[serde(default, deserialize_with = "non_null")]
+    pub protein: Option<f32>,
+    #[serde(default, deserialize_with = "non_null")]
+    pub carbs: Option<f32>,
+    #[serde(default, deserialize_with = "non_null")]
+    pub saturated_fat: Option<f32>,
+    #[serde(default, deserialize_with = "non_null")]
+    pub cholesterol: Option<f32>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub vitamin_c: Option<Option<f32>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub calcium: Option<Option<f32>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub vitamin_b1: Option<Option<f32>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub vitamin_a: Option<Option<f32>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub fibre: Option<Option<f32>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub fruit_veg_percent: Option<Option<f32>>,
+    #[serde(default, deserialize_with = "non_null")]
+    pub price: Option<f32>,
+    #[serde(default, deserialize_with = "non_null")]
+    pub is_upf: Option<bool>,
+    #[serde(default, deserialize_with = "non_null")]
+    pub is_healthier: Option<bool>,
+    #[serde(default, deserialize_with = "non_null")]
+    pub ingredients: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "non_null")]
+    pub barcodes: Option<Vec<String>>,
+}
+
+// Present field → `Some(value)`; `null` ถูกปฏิเสธเพราะ column ไม่รับ NULL
+fn non_null<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+// Present field → `Some(Some(value))` หรือ `Some(None)` เมื่อส่ง `null`; ไม่ส่งมา → `None` (จาก `default`)
+pub(crate) fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, FromRow)]
@@ -70,8 +198,15 @@ pub struct ProductResponse {
     pub brand: Option<String>,
     pub image_url: Option<String>,
     pub categories: Vec<String>,
+    /// Ids of `categories`, in id order.
+    pub categories_ids: Vec<i32>,
+    pub ingredients: Vec<String>,
+    /// EAN-13 form of every barcode of the product.
+    pub barcodes: Vec<String>,
 
     pub serving_size_grams: Option<f32>,
+    #[sqlx(try_from = "String")]
+    pub serving_unit: ServingUnit,
     pub calories: i32,
     pub fat: f32,
     pub sugar: f32,
@@ -85,8 +220,80 @@ pub struct ProductResponse {
     pub calcium: Option<f32>,
     pub vitamin_b1: Option<f32>,
     pub vitamin_a: Option<f32>,
+    pub fibre: Option<f32>,
+    pub fruit_veg_percent: Option<f32>,
 
     pub price: f32,
     pub is_upf: bool,
+    pub nova_group: Option<i16>,
+    #[sqlx(json)]
+    pub nova_markers: Vec<NovaMarker>,
+    #[sqlx(json(nullable))]
+    pub nutri_score: Option<NutriScore>,
     pub is_healthier: bool,
+
+    /// Search score in `[0, 1]`; only present on list results of a `search` query.
+    #[sqlx(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relevance: Option<f64>,
+
+    /// Per-serving and per-100 g/ml values, filled in by the service (`with_nutrition`).
+    #[sqlx(skip)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nutrition: Option<NutritionFacts>,
+
+    /// `%DV` of the Thai RDI (or a custom energy target); only present when requested.
+    #[sqlx(skip)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily_values: Option<DailyValues>,
+
+    /// Traffic lights and "HIGH IN" warnings, filled in by the service.
+    #[sqlx(skip)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub front_of_pack: Option<FrontOfPack>,
+
+    /// Nutrients per baht, filled in by the service; absent when the product has no price.
+    #[sqlx(skip)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<ValueMetrics>,
+}
+
+// ทุก struct ของ product มี column สารอาหารชุดเดียวกัน; เพิ่มสารอาหารใหม่ที่นี่ที่เดียว
+macro_rules! impl_nutrient_amounts {
+    ($($product:ty),+) => {
+        $(
+            impl $product {
+                pub fn nutrient_amounts(&self) -> NutrientAmounts {
+                    NutrientAmounts {
+                        calories: self.calories as f32,
+                        fat: self.fat,
+                        sugar: self.sugar,
+                        sodium: self.sodium,
+                        protein: self.protein,
+                        carbs: self.carbs,
+                        saturated_fat: self.saturated_fat,
+                        cholesterol: self.cholesterol,
+                        vitamin_c: self.vitamin_c,
+                        calcium: self.calcium,
+                        vitamin_b1: self.vitamin_b1,
+                        vitamin_a: self.vitamin_a,
+                        fibre: self.fibre,
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_nutrient_amounts!(Product, ProductForm, ProductResponse);
+
+impl ProductResponse {
+    pub fn with_nutrition(mut self) -> Self {
+        self.nutrition = Some(NutritionFacts::new(
+            self.nutrient_amounts(),
+            self.serving_size_grams,
+            self.serving_unit,
+        ));
+        self
+    }
 }
@@ -0,0 +1,228 @@
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+use crate::errors::{AppError, FieldError};
+/*
+User profile ของผู้บริโภค ใช้คำนวณพลังงานและสารอาหารที่เหมาะกับแต่ละคน
+ - `age`, `sex`, `height_cm`, `weight_kg`: ใช้คำนวณ BMR (Mifflin-St Jeor) สำหรับผู้ใหญ่อายุ 18 ปีขึ้นไป
+ - `activity_level`: ตัวคูณ BMR → TDEE (`sedentary` 1.2 ถึง `very_active` 1.9)
+ - `goal`: `lose` / `maintain` / `gain` ปรับพลังงานเป้าหมายจาก TDEE
+ - `timezone`: เขตเวลา IANA ที่ใช้ตัดขอบวันของบันทึกอาหาร (default `Asia/Bangkok`)
+ - `daily_budget`: งบค่าอาหารต่อวัน (THB) ที่สรุปรายวันเทียบการใช้จ่าย (`None` = ไม่ได้ตั้ง)
+ - `targets`: เป้าหมายต่อวันที่ service คำนวณทุกครั้งที่อ่าน (ไม่เก็บใน DB)
+*/
+
+const MAX_NAME_LENGTH: usize = 255;
+pub const DEFAULT_TIMEZONE: &str = "Asia/Bangkok";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Sex {
+    Male,
+    Female,
+}
+
+impl Sex {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Sex::Male => "male",
+            Sex::Female => "female",
+        }
+    }
+}
+
+impl TryFrom<String> for Sex {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "male" => Ok(Sex::Male),
+            "female" => Ok(Sex::Female),
+            other => Err(format!("unknown sex {:?}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityLevel {
+    Sedentary,
+    Light,
+    #[default]
+    Moderate,
+    Active,
+    VeryActive,
+}
+
+impl ActivityLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActivityLevel::Sedentary => "sedentary",
+            ActivityLevel::Light => "light",
+            ActivityLevel::Moderate => "moderate",
+            ActivityLevel::Active => "active",
+            ActivityLevel::VeryActive => "very_active",
+        }
+    }
+
+    /// Physical activity factor that BMR is multiplied by to get TDEE.
+    pub fn factor(&self) -> f32 {
+        match self {
+            ActivityLevel::Sedentary => 1.2,
+            ActivityLevel::Light => 1.375,
+            ActivityLevel::Moderate => 1.55,
+            ActivityLevel::Active => 1.725,
+            ActivityLevel::VeryActive => 1.9,
+        }
+    }
+}
+
+impl TryFrom<String> for ActivityLevel {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "sedentary" => Ok(ActivityLevel::Sedentary),
+            "light" => Ok(ActivityLevel::Light),
+            "moderate" => Ok(ActivityLevel::Moderate),
+            "active" => Ok(ActivityLevel::Active),
+            "very_active" => Ok(ActivityLevel::VeryActive),
+            other => Err(format!("unknown activity level {:?}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Goal {
+    Lose,
+    #[default]
+    Maintain,
+    Gain,
+}
+
+impl Goal {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Goal::Lose => "lose",
+            Goal::Maintain => "maintain",
+            Goal::Gain => "gain",
+        }
+    }
+}
+
+impl TryFrom<String> for Goal {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "lose" => Ok(Goal::Lose),
+            "maintain" => Ok(Goal::Maintain),
+            "gain" => Ok(Goal::Gain),
+            other => Err(format!("unknown goal {:?}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UserProfile {
+    pub id: Uuid,
+    pub name: String,
+    pub age: i16,
+    #[sqlx(try_from = "String")]
+    pub sex: Sex,
+    pub height_cm: f32,
+    pub weight_kg: f32,
+    #[sqlx(try_from = "String")]
+    pub activity_level: ActivityLevel,
+    #[sqlx(try_from = "String")]
+    pub goal: Goal,
+    pub timezone: String,
+    pub daily_budget: Option<f32>,
+}
+
+impl UserProfile {
+    /// The stored time zone; names are validated on write, so this only falls back for rows edited by hand.
+    pub fn tz(&self) -> Tz {
+        self.timezone.parse().unwrap_or(chrono_tz::Asia::Bangkok)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserProfileForm {
+    pub name: String,
+    pub age: i16,
+    pub sex: Sex,
+    pub height_cm: f32,
+    pub weight_kg: f32,
+    #[serde(default)]
+    pub activity_level: ActivityLevel,
+    #[serde(default)]
+    pub goal: Goal,
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
+    #[serde(default)]
+    pub daily_budget: Option<f32>,
+}
+
+fn default_timezone() -> String {
+    DEFAULT_TIMEZONE.to_string()
+}
+
+impl UserProfileForm {
+    pub fn validate(&self) -> Result<(), AppError> {
+        let mut violations = Vec::new();
+        let name = self.name.trim();
+        if name.is_empty() {
+            violations.push(FieldError::new("name", "must not be empty"));
+        } else if name.chars().count() > MAX_NAME_LENGTH {
+            violations.push(FieldError::new("name", format!("must be at most {} characters", MAX_NAME_LENGTH)));
+        }
+        // Mifflin-St Jeor ใช้ได้กับผู้ใหญ่ ช่วงส่วนสูง/น้ำหนักกันค่าที่พิมพ์ผิดหน่วย (เช่น เมตรแทนเซนติเมตร)
+        if !(18..=100).contains(&self.age) {
+            violations.push(FieldError::new("age", "must be between 18 and 100"));
+        }
+        if !(100.0..=250.0).contains(&self.height_cm) {
+            violations.push(FieldError::new("height_cm", "must be between 100 and 250"));
+        }
+        if !(30.0..=300.0).contains(&self.weight_kg) {
+            violations.push(FieldError::new("weight_kg", "must be between 30 and 300"));
+        }
+        if self.timezone.trim().parse::<Tz>().is_err() {
+            violations.push(FieldError::new("timezone", "must be an IANA time zone such as Asia/Bangkok"));
+        }
+        if let Some(budget) = self.daily_budget
+            && !(budget.is_finite() && budget >= 0.0)
+        {
+            violations.push(FieldError::new("daily_budget", "must not be negative"));
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::InvalidFields(violations))
+        }
+    }
+}
+
+/// Daily energy and nutrient targets; `sugar_g` and `sodium_mg` are upper limits.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DailyTargets {
+    pub bmr_kcal: f32,
+    pub tdee_kcal: f32,
+    pub energy_kcal: f32,
+    pub protein_g: f32,
+    pub fat_g: f32,
+    pub carbs_g: f32,
+    pub sugar_g: f32,
+    pub sodium_mg: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserProfileResponse {
+    #[serde(flatten)]
+    pub profile: UserProfile,
+    pub targets: DailyTargets,
+}
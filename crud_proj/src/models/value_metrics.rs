@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+
+use crate::errors::AppError;
+use super::categories::Categories;
+use super::product_sort::SortField;
+use super::products::ProductResponse;
+/*
+ความคุ้มค่าของ product ต่อราคา (ต่อบาทของทั้งชิ้น/serving ตาม `price`)
+ - `protein_per_baht`: โปรตีน (g) ต่อบาท
+ - `kcal_per_baht`: พลังงาน (kcal) ต่อบาท
+ - `nutrient_density`: คะแนนแบบ NRF ต่อ serving = ผลรวม %Thai RDI ของโปรตีน, ใยอาหาร, vitamin A/C/B1
+   และแคลเซียม (แต่ละตัวไม่เกิน 100) ลบผลรวม %Thai RDI ของไขมันอิ่มตัว, น้ำตาล และโซเดียม
+ - `nutrient_density_per_baht`: `nutrient_density` ต่อบาท (ติดลบได้)
+ - product ที่ไม่มีราคา (`price` = 0) ไม่มีค่าเหล่านี้
+*/
+
+pub const DEFAULT_BEST_VALUE_LIMIT: i64 = 10;
+pub const MAX_BEST_VALUE_LIMIT: i64 = 50;
+
+/// Amount per baht to rank by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ValueMetric {
+    #[serde(rename = "protein_per_baht")]
+    Protein,
+    #[serde(rename = "kcal_per_baht")]
+    Kcal,
+    #[default]
+    #[serde(rename = "nutrient_density_per_baht")]
+    NutrientDensity,
+}
+
+impl ValueMetric {
+    /// Sort key of the product list that ranks by this metric.
+    pub fn sort_field(&self) -> SortField {
+        match self {
+            ValueMetric::Protein => SortField::ProteinPerBaht,
+            ValueMetric::Kcal => SortField::KcalPerBaht,
+            ValueMetric::NutrientDensity => SortField::NutrientDensityPerBaht,
+        }
+    }
+
+    pub fn value(&self, metrics: &ValueMetrics) -> f32 {
+        match self {
+            ValueMetric::Protein => metrics.protein_per_baht,
+            ValueMetric::Kcal => metrics.kcal_per_baht,
+            ValueMetric::NutrientDensity => metrics.nutrient_density_per_baht,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ValueMetrics {
+    pub protein_per_baht: f32,
+    pub kcal_per_baht: f32,
+    pub nutrient_density: f32,
+    pub nutrient_density_per_baht: f32,
+}
+
+/// Query of `GET /api/v1/categories/{id}/best-value`.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct BestValueQuery {
+    #[serde(default)]
+    pub metric: ValueMetric,
+    /// Leaves out products flagged `is_upf` or classified NOVA group 4.
+    #[serde(default)]
+    pub non_upf: bool,
+    /// Only certified Healthier Choice products.
+    #[serde(default)]
+    pub healthier: bool,
+    pub limit: Option<i64>,
+}
+
+impl BestValueQuery {
+    pub fn validate(&self) -> Result<(), AppError> {
+        if let Some(limit) = self.limit
+            && !(1..=MAX_BEST_VALUE_LIMIT).contains(&limit)
+        {
+            return Err(AppError::ValidationError(format!(
+                "limit must be between 1 and {}", MAX_BEST_VALUE_LIMIT
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(DEFAULT_BEST_VALUE_LIMIT)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BestValueEntry {
+    pub rank: usize,
+    /// Value of the requested metric.
+    pub score: f32,
+    pub product: ProductResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BestValueRanking {
+    pub category: Categories,
+    pub metric: ValueMetric,
+    pub non_upf: bool,
+    pub healthier: bool,
+    pub items: Vec<BestValueEntry>,
+}
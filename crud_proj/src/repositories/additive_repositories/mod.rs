@@ -0,0 +1,89 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::{debug, error, info, warn};
+
+use crate::{
+    errors::AppError,
+    models::ingredients::{Additive, AdditiveForm},
+};
+
+#[async_trait]
+pub trait AdditiveRepositoryTrait: Send + Sync {
+    async fn get_additive_list(&self) -> Result<Vec<Additive>, AppError>;
+    async fn create_additive(&self, additive: AdditiveForm) -> Result<Additive, AppError>;
+}
+
+pub struct AdditiveRepository {
+    pool: Arc<PgPool>,
+}
+
+impl AdditiveRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        AdditiveRepository { pool }
+    }
+}
+
+// E number เก็บเป็นตัวพิมพ์ใหญ่ (E621) แต่ตัวอักษรต่อท้ายเป็นตัวพิมพ์เล็ก (E150d)
+fn normalize_e_number(e_number: &str) -> Option<String> {
+    let compact: String = e_number.chars().filter(|c| !c.is_whitespace() && *c != '-').collect();
+    let number = compact.trim_start_matches(['e', 'E']);
+    if number.is_empty() {
+        return None;
+    }
+    Some(format!("E{}", number.to_lowercase()))
+}
+
+#[async_trait]
+impl AdditiveRepositoryTrait for AdditiveRepository {
+    async fn get_additive_list(&self) -> Result<Vec<Additive>, AppError> {
+        let additives = sqlx::query_as::<_, Additive>(
+            "SELECT id, e_number, name, function, nova_group, aliases FROM additives ORDER BY e_number NULLS LAST, name"
+        )
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| {
+                error!("Error fetching additives: {:?}", e);
+                AppError::DatabaseError(e)
+            })?;
+
+        info!("Successfully fetched {} additives.", additives.len());
+        Ok(additives)
+    }
+
+    async fn create_additive(&self, additive: AdditiveForm) -> Result<Additive, AppError> {
+        let e_number = additive.e_number.as_deref().and_then(normalize_e_number);
+        let aliases: Vec<String> = additive.aliases.iter()
+            .map(|alias| alias.trim().to_lowercase())
+            .filter(|alias| !alias.is_empty())
+            .collect();
+
+        let row = sqlx::query_as::<_, Additive>(
+            r#"
+            INSERT INTO additives (e_number, name, function, nova_group, aliases)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, e_number, name, function, nova_group, aliases
+            "#
+        )
+        .bind(&e_number)
+        .bind(additive.name.trim())
+        .bind(additive.function.trim())
+        .bind(additive.nova_group.unwrap_or(4))
+        .bind(&aliases)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| match &e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                warn!("Additive {:?} / {:?} already exists", additive.name, e_number);
+                AppError::Conflict("An additive with the same name or E number already exists".to_string())
+            }
+            _ => {
+                error!("Error creating additive {:?}: {:?}", additive.name, e);
+                AppError::DatabaseError(e)
+            }
+        })?;
+
+        debug!("✅ Additive created successfully: id={}, name={}", row.id, row.name);
+        Ok(row)
+    }
+}
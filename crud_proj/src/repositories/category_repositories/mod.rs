@@ -0,0 +1,189 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::{debug, error, info, warn};
+
+use crate::{
+    errors::AppError,
+    models::{
+        categories::{Categories, CategoriesForm, CategoriesWithProductsResponse},
+        products::Product,
+    },
+};
+
+#[async_trait]
+pub trait CategoryRepositoryTrait: Send + Sync {
+    async fn get_category_list(&self) -> Result<Vec<Categories>, AppError>;
+    async fn create_category(&self, category: CategoriesForm) -> Result<Categories, AppError>;
+    async fn get_category_by_id(&self, id: i32) -> Result<Categories, AppError>;
+    async fn rename_category_by_id(&self, id: i32, category: CategoriesForm) -> Result<Categories, AppError>;
+    async fn delete_category_by_id(&self, id: i32) -> Result<u64, AppError>;
+    async fn get_category_with_products(&self, id: i32) -> Result<CategoriesWithProductsResponse, AppError>;
+}
+
+pub struct CategoryRepository {
+    pool: Arc<PgPool>,
+}
+
+impl CategoryRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        CategoryRepository { pool }
+    }
+}
+
+// ชื่อ category เป็น UNIQUE ดังนั้นชื่อซ้ำควรแจ้ง client แทนที่จะเป็น 500
+fn map_name_conflict(e: sqlx::Error, name: &str) -> AppError {
+    match &e {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+            warn!("Category name {:?} already exists", name);
+            AppError::Conflict(format!("Category '{}' already exists", name))
+        }
+        _ => {
+            error!("Error writing category {:?}: {:?}", name, e);
+            AppError::DatabaseError(e)
+        }
+    }
+}
+
+fn normalize_name(name: &str) -> Result<String, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::ValidationError("Category name must not be empty".to_string()));
+    }
+    Ok(name.to_string())
+}
+
+#[async_trait]
+impl CategoryRepositoryTrait for CategoryRepository {
+    async fn get_category_list(&self) -> Result<Vec<Categories>, AppError> {
+        let categories = sqlx::query_as::<_, Categories>("SELECT id, name, food_group, off_tags FROM categories ORDER BY name")
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| {
+                error!("Error fetching categories: {:?}", e);
+                AppError::DatabaseError(e)
+            })?;
+
+        info!("Successfully fetched {} categories.", categories.len());
+        Ok(categories)
+    }
+
+    async fn create_category(&self, category: CategoriesForm) -> Result<Categories, AppError> {
+        let name = normalize_name(&category.name)?;
+
+        let row = sqlx::query_as::<_, Categories>(
+            "INSERT INTO categories (name, food_group, off_tags) VALUES ($1, $2, COALESCE($3, ARRAY[]::TEXT[])) RETURNING id, name, food_group, off_tags"
+        )
+        .bind(&name)
+        .bind(category.food_group.flatten())
+        .bind(category.off_tags.flatten())
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| map_name_conflict(e, &name))?;
+
+        debug!("✅ Category created successfully: id={}, name={}", row.id, row.name);
+        Ok(row)
+    }
+
+    async fn get_category_by_id(&self, id: i32) -> Result<Categories, AppError> {
+        let result = sqlx::query_as::<_, Categories>("SELECT id, name, food_group, off_tags FROM categories WHERE id = $1")
+            .bind(id)
+            .fetch_one(&*self.pool)
+            .await;
+
+        match result {
+            Ok(category) => Ok(category),
+            Err(sqlx::Error::RowNotFound) => {
+                warn!("Category with id {} not found", id);
+                Err(AppError::NotFound)
+            }
+            Err(e) => {
+                error!("Error fetching category by id {}: {:?}", id, e);
+                Err(AppError::DatabaseError(e))
+            }
+        }
+    }
+
+    async fn rename_category_by_id(&self, id: i32, category: CategoriesForm) -> Result<Categories, AppError> {
+        let name = normalize_name(&category.name)?;
+
+        let result = sqlx::query_as::<_, Categories>(
+            // field ที่ไม่ได้ส่งมาคงค่าเดิม ส่วน `null` เคลียร์ค่า
+            r#"UPDATE categories SET
+                name = $2,
+                food_group = CASE WHEN $3 THEN $4 ELSE food_group END,
+                off_tags = CASE WHEN $5 THEN COALESCE($6, ARRAY[]::TEXT[]) ELSE off_tags END
+            WHERE id = $1 RETURNING id, name, food_group, off_tags"#
+        )
+        .bind(id)
+        .bind(&name)
+        .bind(category.food_group.is_some())
+        .bind(category.food_group.flatten())
+        .bind(category.off_tags.is_some())
+        .bind(category.off_tags.flatten())
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(|e| map_name_conflict(e, &name))?;
+
+        match result {
+            Some(row) => {
+                info!("Successfully renamed category {} to {:?}", id, row.name);
+                Ok(row)
+            }
+            None => {
+                warn!("Category with id {} not found", id);
+                Err(AppError::NotFound)
+            }
+        }
+    }
+
+    async fn delete_category_by_id(&self, id: i32) -> Result<u64, AppError> {
+        let mut tx = self.pool.begin().await
+            .map_err(AppError::DatabaseError)?;
+
+        // Unlink products first (foreign key constraint)
+        sqlx::query("DELETE FROM product_category WHERE category_id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::DatabaseError)?;
+
+        let result = sqlx::query("DELETE FROM categories WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::DatabaseError)?;
+
+        let affected_rows = result.rows_affected();
+
+        tx.commit().await
+            .map_err(AppError::DatabaseError)?;
+
+        info!("Successfully deleted category with id: {}, affected rows: {}", id, affected_rows);
+        Ok(affected_rows)
+    }
+
+    async fn get_category_with_products(&self, id: i32) -> Result<CategoriesWithProductsResponse, AppError> {
+        let categories = self.get_category_by_id(id).await?;
+
+        let query = r#"
+            SELECT p.*
+            FROM products p
+            INNER JOIN product_category pc ON p.id = pc.product_id
+            WHERE pc.category_id = $1
+            ORDER BY p.name
+        "#;
+
+        let products = sqlx::query_as::<_, Product>(query)
+            .bind(id)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| {
+                error!("Error fetching products of category {}: {:?}", id, e);
+                AppError::DatabaseError(e)
+            })?;
+
+        info!("Successfully fetched {} products for category {}", products.len(), id);
+        Ok(CategoriesWithProductsResponse { categories, products })
+    }
+}
@@ -0,0 +1,158 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    errors::AppError,
+    models::food_log::{FoodLogEntry, FoodLogEntryForm},
+};
+
+#[async_trait]
+pub trait FoodLogRepositoryTrait: Send + Sync {
+    async fn get_food_log_entries(&self, profile_id: Uuid, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<FoodLogEntry>, AppError>;
+    async fn create_food_log_entry(&self, profile_id: Uuid, entry: FoodLogEntryForm) -> Result<FoodLogEntry, AppError>;
+    async fn get_food_log_entry_by_id(&self, profile_id: Uuid, id: Uuid) -> Result<FoodLogEntry, AppError>;
+    async fn update_food_log_entry_by_id(&self, profile_id: Uuid, id: Uuid, entry: FoodLogEntryForm) -> Result<FoodLogEntry, AppError>;
+    async fn delete_food_log_entry_by_id(&self, profile_id: Uuid, id: Uuid) -> Result<u64, AppError>;
+}
+
+pub struct FoodLogRepository {
+    pool: Arc<PgPool>,
+}
+
+impl FoodLogRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        FoodLogRepository { pool }
+    }
+}
+
+// แถวของบันทึกพร้อม product ทั้งแถว (เป็น JSON) ที่ใช้คำนวณค่าสารอาหารและราคา
+const FOOD_LOG_ENTRY_SELECT: &str = r#"
+    SELECT e.id, e.profile_id, e.product_id, e.quantity, e.quantity_unit, e.meal_slot, e.eaten_at,
+        to_jsonb(p) AS product"#;
+
+#[async_trait]
+impl FoodLogRepositoryTrait for FoodLogRepository {
+    async fn get_food_log_entries(&self, profile_id: Uuid, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<FoodLogEntry>, AppError> {
+        let query = format!(
+            "{} FROM food_log_entries e JOIN products p ON p.id = e.product_id \
+             WHERE e.profile_id = $1 AND e.eaten_at >= $2 AND e.eaten_at < $3 ORDER BY e.eaten_at, e.id",
+            FOOD_LOG_ENTRY_SELECT
+        );
+        let entries = sqlx::query_as::<_, FoodLogEntry>(&query)
+            .bind(profile_id)
+            .bind(from)
+            .bind(to)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| {
+                error!("Error fetching food log of profile {}: {:?}", profile_id, e);
+                AppError::DatabaseError(e)
+            })?;
+
+        info!("Successfully fetched {} food log entries of profile {}.", entries.len(), profile_id);
+        Ok(entries)
+    }
+
+    async fn create_food_log_entry(&self, profile_id: Uuid, entry: FoodLogEntryForm) -> Result<FoodLogEntry, AppError> {
+        let query = format!(
+            "WITH e AS (\
+                INSERT INTO food_log_entries (profile_id, product_id, quantity, quantity_unit, meal_slot, eaten_at) \
+                VALUES ($1, $2, $3, $4, $5, COALESCE($6, NOW())) RETURNING *\
+             ) {} FROM e JOIN products p ON p.id = e.product_id",
+            FOOD_LOG_ENTRY_SELECT
+        );
+        let row = sqlx::query_as::<_, FoodLogEntry>(&query)
+            .bind(profile_id)
+            .bind(entry.product_id)
+            .bind(entry.quantity)
+            .bind(entry.quantity_unit.as_str())
+            .bind(entry.meal_slot.as_str())
+            .bind(entry.eaten_at)
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| {
+                error!("Error creating food log entry of profile {}: {:?}", profile_id, e);
+                AppError::DatabaseError(e)
+            })?;
+
+        debug!("✅ Food log entry created successfully: id={}", row.id);
+        Ok(row)
+    }
+
+    async fn get_food_log_entry_by_id(&self, profile_id: Uuid, id: Uuid) -> Result<FoodLogEntry, AppError> {
+        let query = format!(
+            "{} FROM food_log_entries e JOIN products p ON p.id = e.product_id WHERE e.profile_id = $1 AND e.id = $2",
+            FOOD_LOG_ENTRY_SELECT
+        );
+        let result = sqlx::query_as::<_, FoodLogEntry>(&query)
+            .bind(profile_id)
+            .bind(id)
+            .fetch_one(&*self.pool)
+            .await;
+
+        match result {
+            Ok(entry) => Ok(entry),
+            Err(sqlx::Error::RowNotFound) => {
+                warn!("Food log entry {} of profile {} not found", id, profile_id);
+                Err(AppError::NotFound)
+            }
+            Err(e) => {
+                error!("Error fetching food log entry {}: {:?}", id, e);
+                Err(AppError::DatabaseError(e))
+            }
+        }
+    }
+
+    async fn update_food_log_entry_by_id(&self, profile_id: Uuid, id: Uuid, entry: FoodLogEntryForm) -> Result<FoodLogEntry, AppError> {
+        // ไม่ส่ง `eaten_at` มา = คงเวลาเดิม
+        let query = format!(
+            "WITH e AS (\
+                UPDATE food_log_entries SET product_id = $3, quantity = $4, quantity_unit = $5, meal_slot = $6, \
+                eaten_at = COALESCE($7, eaten_at) WHERE profile_id = $1 AND id = $2 RETURNING *\
+             ) {} FROM e JOIN products p ON p.id = e.product_id",
+            FOOD_LOG_ENTRY_SELECT
+        );
+        let result = sqlx::query_as::<_, FoodLogEntry>(&query)
+            .bind(profile_id)
+            .bind(id)
+            .bind(entry.product_id)
+            .bind(entry.quantity)
+            .bind(entry.quantity_unit.as_str())
+            .bind(entry.meal_slot.as_str())
+            .bind(entry.eaten_at)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(|e| {
+                error!("Error updating food log entry {}: {:?}", id, e);
+                AppError::DatabaseError(e)
+            })?;
+
+        match result {
+            Some(row) => {
+                info!("Successfully updated food log entry {}", id);
+                Ok(row)
+            }
+            None => {
+                warn!("Food log entry {} of profile {} not found", id, profile_id);
+                Err(AppError::NotFound)
+            }
+        }
+    }
+
+    async fn delete_food_log_entry_by_id(&self, profile_id: Uuid, id: Uuid) -> Result<u64, AppError> {
+        let result = sqlx::query("DELETE FROM food_log_entries WHERE profile_id = $1 AND id = $2")
+            .bind(profile_id)
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(AppError::DatabaseError)?;
+
+        let affected_rows = result.rows_affected();
+        info!("Successfully deleted food log entry with id: {}, affected rows: {}", id, affected_rows);
+        Ok(affected_rows)
+    }
+}
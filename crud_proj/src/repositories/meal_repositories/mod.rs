@@ -0,0 +1,211 @@
+use async_trait::async_trait;
+use sqlx::{PgConnection, PgPool};
+use std::sync::Arc;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    errors::{AppError, FieldError},
+    models::meals::{Meal, MealForm, MealItem, MealItemForm},
+};
+
+#[async_trait]
+pub trait MealRepositoryTrait: Send + Sync {
+    async fn get_meal_list(&self) -> Result<Vec<(Meal, Vec<MealItem>)>, AppError>;
+    async fn create_meal(&self, meal: MealForm) -> Result<(Meal, Vec<MealItem>), AppError>;
+    async fn get_meal_by_id(&self, id: Uuid) -> Result<(Meal, Vec<MealItem>), AppError>;
+    async fn update_meal_by_id(&self, id: Uuid, meal: MealForm) -> Result<(Meal, Vec<MealItem>), AppError>;
+    async fn delete_meal_by_id(&self, id: Uuid) -> Result<u64, AppError>;
+}
+
+pub struct MealRepository {
+    pool: Arc<PgPool>,
+}
+
+impl MealRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        MealRepository { pool }
+    }
+
+    // items ของหลายมื้อพร้อม product ทั้งแถว (เป็น JSON) เรียงตามมื้อและลำดับ
+    async fn get_meal_items(&self, meal_ids: &[Uuid]) -> Result<Vec<MealItem>, AppError> {
+        sqlx::query_as::<_, MealItem>(
+            "SELECT mi.meal_id, mi.position, mi.product_id, mi.quantity, mi.quantity_unit, to_jsonb(p) AS product \
+             FROM meal_items mi JOIN products p ON p.id = mi.product_id \
+             WHERE mi.meal_id = ANY($1) ORDER BY mi.meal_id, mi.position"
+        )
+            .bind(meal_ids)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| {
+                error!("Error fetching items of meals {:?}: {:?}", meal_ids, e);
+                AppError::DatabaseError(e)
+            })
+    }
+}
+
+// ทุก product ต้องมีอยู่จริง และ item ที่เป็นกรัมต้องมี serving size ให้แปลงเป็น serving
+async fn ensure_meal_products(conn: &mut PgConnection, items: &[MealItemForm]) -> Result<(), AppError> {
+    let ids: Vec<Uuid> = items.iter().map(|item| item.product_id).collect();
+    let found: Vec<(Uuid, Option<f32>)> = sqlx::query_as("SELECT id, serving_size_grams FROM products WHERE id = ANY($1)")
+        .bind(&ids)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| {
+            error!("Error checking meal products {:?}: {:?}", ids, e);
+            AppError::DatabaseError(e)
+        })?;
+
+    let mut violations = Vec::new();
+    for (index, item) in items.iter().enumerate() {
+        match found.iter().find(|(id, _)| *id == item.product_id) {
+            None => violations.push(FieldError::new(format!("items[{}].product_id", index), "unknown product")),
+            Some((_, serving_size)) if item.quantity_unit.servings(item.quantity, *serving_size).is_none() => {
+                violations.push(FieldError::new(
+                    format!("items[{}].quantity_unit", index),
+                    "grams needs a product with a serving size; use servings instead",
+                ));
+            }
+            Some(_) => {}
+        }
+    }
+    if !violations.is_empty() {
+        warn!("Rejected meal items: {:?}", violations);
+        return Err(AppError::InvalidFields(violations));
+    }
+    Ok(())
+}
+
+async fn insert_meal_items(conn: &mut PgConnection, meal_id: Uuid, items: &[MealItemForm]) -> Result<(), AppError> {
+    let insert_query = "INSERT INTO meal_items (meal_id, position, product_id, quantity, quantity_unit) VALUES ($1, $2, $3, $4, $5)";
+    for (index, item) in items.iter().enumerate() {
+        sqlx::query(insert_query)
+            .bind(meal_id)
+            .bind(index as i32 + 1)
+            .bind(item.product_id)
+            .bind(item.quantity)
+            .bind(item.quantity_unit.as_str())
+            .execute(&mut *conn)
+            .await
+            .map_err(AppError::DatabaseError)?;
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl MealRepositoryTrait for MealRepository {
+    async fn get_meal_list(&self) -> Result<Vec<(Meal, Vec<MealItem>)>, AppError> {
+        let meals = sqlx::query_as::<_, Meal>("SELECT id, name FROM meals ORDER BY name, id")
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| {
+                error!("Error fetching meals: {:?}", e);
+                AppError::DatabaseError(e)
+            })?;
+
+        let ids: Vec<Uuid> = meals.iter().map(|meal| meal.id).collect();
+        let mut items = self.get_meal_items(&ids).await?;
+        info!("Successfully fetched {} meals with {} items.", meals.len(), items.len());
+        Ok(meals.into_iter()
+            .map(|meal| {
+                let (own, rest) = items.drain(..).partition(|item| item.meal_id == meal.id);
+                items = rest;
+                (meal, own)
+            })
+            .collect())
+    }
+
+    async fn create_meal(&self, meal: MealForm) -> Result<(Meal, Vec<MealItem>), AppError> {
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            error!("❌ Failed to begin transaction: {:?}", e);
+            AppError::DatabaseError(e)
+        })?;
+
+        ensure_meal_products(&mut tx, &meal.items).await?;
+        let id: Uuid = sqlx::query_scalar("INSERT INTO meals (name) VALUES ($1) RETURNING id")
+            .bind(meal.name.trim())
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| {
+                error!("Error creating meal {:?}: {:?}", meal.name, e);
+                AppError::DatabaseError(e)
+            })?;
+        insert_meal_items(&mut tx, id, &meal.items).await?;
+
+        tx.commit().await.map_err(|e| {
+            error!("❌ Failed to commit transaction: {:?}", e);
+            AppError::DatabaseError(e)
+        })?;
+        debug!("✅ Meal created successfully: id={}", id);
+        self.get_meal_by_id(id).await
+    }
+
+    async fn get_meal_by_id(&self, id: Uuid) -> Result<(Meal, Vec<MealItem>), AppError> {
+        let result = sqlx::query_as::<_, Meal>("SELECT id, name FROM meals WHERE id = $1")
+            .bind(id)
+            .fetch_one(&*self.pool)
+            .await;
+
+        let meal = match result {
+            Ok(meal) => meal,
+            Err(sqlx::Error::RowNotFound) => {
+                warn!("Meal with id {} not found", id);
+                return Err(AppError::NotFound);
+            }
+            Err(e) => {
+                error!("Error fetching meal by id {}: {:?}", id, e);
+                return Err(AppError::DatabaseError(e));
+            }
+        };
+        let items = self.get_meal_items(&[id]).await?;
+        Ok((meal, items))
+    }
+
+    async fn update_meal_by_id(&self, id: Uuid, meal: MealForm) -> Result<(Meal, Vec<MealItem>), AppError> {
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            error!("❌ Failed to begin transaction: {:?}", e);
+            AppError::DatabaseError(e)
+        })?;
+
+        let updated = sqlx::query("UPDATE meals SET name = $2 WHERE id = $1")
+            .bind(id)
+            .bind(meal.name.trim())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                error!("Error updating meal {}: {:?}", id, e);
+                AppError::DatabaseError(e)
+            })?;
+        if updated.rows_affected() == 0 {
+            warn!("Meal with id {} not found", id);
+            return Err(AppError::NotFound);
+        }
+
+        ensure_meal_products(&mut tx, &meal.items).await?;
+        sqlx::query("DELETE FROM meal_items WHERE meal_id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        insert_meal_items(&mut tx, id, &meal.items).await?;
+
+        tx.commit().await.map_err(|e| {
+            error!("❌ Failed to commit transaction: {:?}", e);
+            AppError::DatabaseError(e)
+        })?;
+        info!("Successfully updated meal {}", id);
+        self.get_meal_by_id(id).await
+    }
+
+    async fn delete_meal_by_id(&self, id: Uuid) -> Result<u64, AppError> {
+        let result = sqlx::query("DELETE FROM meals WHERE id = $1")
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(AppError::DatabaseError)?;
+
+        let affected_rows = result.rows_affected();
+        info!("Successfully deleted meal with id: {}, affected rows: {}", id, affected_rows);
+        Ok(affected_rows)
+    }
+}
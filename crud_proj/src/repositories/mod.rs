@@ -1 +1,6 @@
-pub mod product_repositories;
\ No newline at end of file
+pub mod product_repositories;
+pub mod category_repositories;
+pub mod additive_repositories;
+pub mod user_profile_repositories;
+pub mod food_log_repositories;
+pub mod meal_repositories;
\ No newline at end of file
@@ -1,24 +1,49 @@
 use async_trait::async_trait;
-use sqlx::{PgPool};
-use std::sync::Arc;
+use sqlx::{postgres::PgRow, types::Json, FromRow, PgConnection, PgPool, Postgres, QueryBuilder, Row};
+use std::{collections::BTreeSet, sync::Arc};
 use uuid::Uuid;
 use tracing::{debug, error, info, warn};
 
 use crate::{
     errors::AppError,
     models::{
-        pagination::Pagination,
-        products::{Product, ProductForm, ProductResponse}
+        alternatives::{AlternativeCriteria, MIN_REDUCTION_PERCENT},
+        barcodes::normalized_barcodes,
+        front_of_pack::{Limit, WarningProfile, TRAFFIC_LIGHT_BANDS},
+        healthier_choice::ProductWithFoodGroups,
+        ingredients::{NovaClassification, NovaMarker},
+        meal_plan::{PlanExclusions, PlanObjective},
+        nutri_score::NutriScore,
+        nutrition::NutrientBasis,
+        pagination::{PageResult, Pagination, ProductCursor, SearchTerm},
+        product_filter::ProductFilter,
+        product_sort::{ProductSort, SortValue},
+        products::{DerivedScores, Product, ProductForm, ProductPatch, ProductResponse},
+        value_metrics::BestValueQuery,
     }
 };
 
+/// Validates and scores the full form of a patched product; runs while the product row is locked.
+pub type ProductRescorer<'a> = dyn Fn(&ProductForm) -> Result<DerivedScores, AppError> + Send + Sync + 'a;
+
 #[async_trait]
 pub trait ProductRepositoryTrait: Send + Sync {
-    async fn get_product_list(&self, pagination: Pagination) -> Result<Vec<ProductResponse>, AppError>;
-    async fn create_product_with_categories(&self, product: ProductForm) -> Result<ProductResponse, AppError>;
+    async fn get_product_list(&self, pagination: Pagination, filter: ProductFilter) -> Result<PageResult<ProductResponse>, AppError>;
+    async fn create_product_with_categories(&self, product: ProductForm, scores: DerivedScores) -> Result<ProductResponse, AppError>;
     async fn get_product_by_id(&self, id: Uuid) -> Result<ProductResponse, AppError>;
-    async fn update_product_by_id(&self, id: Uuid, product: ProductForm) -> Result<ProductResponse, AppError>;
+    async fn get_product_by_barcode(&self, barcode: &str) -> Result<ProductResponse, AppError>;
+    async fn update_product_by_id(&self, id: Uuid, product: ProductForm, scores: DerivedScores) -> Result<ProductResponse, AppError>;
+    async fn patch_product_by_id(&self, id: Uuid, patch: ProductPatch, rescore: &ProductRescorer<'_>) -> Result<ProductResponse, AppError>;
     async fn delete_product_by_id(&self, id: Uuid) -> Result<u64, AppError>;
+    async fn get_product_with_food_groups(&self, id: Uuid) -> Result<ProductWithFoodGroups, AppError>;
+    async fn get_products_with_food_groups(&self) -> Result<Vec<ProductWithFoodGroups>, AppError>;
+    async fn get_all_product_ingredients(&self) -> Result<Vec<(Uuid, Vec<String>)>, AppError>;
+    async fn save_nova_classifications(&self, classifications: &[(Uuid, NovaClassification)]) -> Result<u64, AppError>;
+    async fn save_derived_scores(&self, scores: &[(Uuid, DerivedScores)]) -> Result<u64, AppError>;
+    /// Up to `MAX_MEAL_PLAN_CANDIDATES` products ranked for `objective`, and whether more were eligible.
+    async fn get_meal_plan_candidates(&self, exclude: &PlanExclusions, objective: PlanObjective) -> Result<(Vec<ProductResponse>, bool), AppError>;
+    async fn get_best_value_products(&self, category_id: i32, query: &BestValueQuery) -> Result<Vec<ProductResponse>, AppError>;
+    async fn get_alternative_candidates(&self, criteria: &AlternativeCriteria) -> Result<Vec<ProductResponse>, AppError>;
 }
 
 pub struct ProductRepository {
@@ -31,83 +56,509 @@ impl ProductRepository {
     }
 }
 
-#[async_trait]
-impl ProductRepositoryTrait for ProductRepository {
-    async fn get_product_list(&self, pagination: Pagination) -> Result<Vec<ProductResponse>, AppError> {
-        let current_limit = pagination.limit.unwrap_or(10) as i64;
-        let current_offset = pagination.offset.unwrap_or(0) as i64;
-
-        let (base_query, search_pattern) = if let Some(search) = &pagination.search {
-            let query = r#"
-                SELECT 
-                    p.*, 
-                    COALESCE(
-                        ARRAY_AGG(c.name) FILTER (WHERE c.name IS NOT NULL), 
-                        ARRAY[]::TEXT[]
-                    ) AS categories
-                FROM products p
-                LEFT JOIN product_category pc ON p.id = pc.product_id
-                LEFT JOIN categories c ON pc.category_id = c.id
-                WHERE (p.name ILIKE $1 OR p.brand ILIKE $1)
-                GROUP BY p.id 
-                ORDER BY p.name 
-                LIMIT $2 OFFSET $3
-            "#;
-            let pattern = format!("%{}%", search.to_lowercase());
-            (query, Some(pattern))
+// SELECT หลักของ product list; relevance/sort key columns, FROM, WHERE/ORDER/LIMIT ต่อท้ายด้วย QueryBuilder
+const PRODUCT_SELECT: &str = r#"
+    SELECT 
+        p.*, 
+        COALESCE(
+            ARRAY_AGG(c.name) FILTER (WHERE c.name IS NOT NULL), 
+            ARRAY[]::TEXT[]
+        ) AS categories,
+        COALESCE(
+            ARRAY_AGG(c.id ORDER BY c.id) FILTER (WHERE c.id IS NOT NULL),
+            ARRAY[]::INT[]
+        ) AS categories_ids,
+        COALESCE(
+            (SELECT ARRAY_AGG(pi.name::TEXT ORDER BY pi.position) FROM product_ingredients pi WHERE pi.product_id = p.id),
+            ARRAY[]::TEXT[]
+        ) AS ingredients,
+        COALESCE(
+            (SELECT ARRAY_AGG(pb.barcode::TEXT ORDER BY pb.barcode) FROM product_barcodes pb WHERE pb.product_id = p.id),
+            ARRAY[]::TEXT[]
+        ) AS barcodes"#;
+
+// จำนวน product สูงสุดที่ส่งให้ optimizer (ตัวแปร integer ของ MILP) เรียงตามเป้าหมายของแผน
+const MAX_MEAL_PLAN_CANDIDATES: i64 = 300;
+
+// จำนวนผู้สมัครสูงสุดของทางเลือกที่ดีกว่า (service จัดอันดับเองอีกรอบ)
+const MAX_ALTERNATIVE_CANDIDATES: i64 = 200;
+
+const PRODUCT_JOINS: &str = r#"
+    LEFT JOIN product_category pc ON p.id = pc.product_id
+    LEFT JOIN categories c ON pc.category_id = c.id
+"#;
+
+// กลุ่มอาหาร (Healthier Choice) ของทุก category ที่ product อยู่
+const FOOD_GROUPS_COLUMN: &str = r#",
+        COALESCE(
+            ARRAY_AGG(DISTINCT c.food_group::TEXT) FILTER (WHERE c.food_group IS NOT NULL),
+            ARRAY[]::TEXT[]
+        ) AS food_groups"#;
+
+// เกณฑ์ word similarity ของ trigram (ค่า default ของ pg_trgm คือ 0.6 ซึ่งเข้มเกินไป
+// สำหรับชื่อแบรนด์ไทยที่พิมพ์มาไม่ครบหรือพิมพ์ผิด)
+const SEARCH_SIMILARITY_THRESHOLD: &str = "0.4";
+
+// คะแนนความเกี่ยวข้องของการค้นหา: ค่าสูงสุดระหว่าง word similarity ของชื่อ, แบรนด์
+// และชื่อ category (ถ่วงน้ำหนักต่ำกว่าเล็กน้อย)
+fn push_relevance_lateral(builder: &mut QueryBuilder<'_, Postgres>, search: &SearchTerm) {
+    builder.push(" CROSS JOIN LATERAL (SELECT GREATEST(word_similarity(");
+    builder.push_bind(search.query.clone());
+    builder.push(", p.name), word_similarity(");
+    builder.push_bind(search.query.clone());
+    builder.push(", COALESCE(p.brand, '')), 0.8 * COALESCE((SELECT MAX(word_similarity(");
+    builder.push_bind(search.query.clone());
+    builder.push(", sc.name)) FROM product_category spc JOIN categories sc ON sc.id = spc.category_id WHERE spc.product_id = p.id), 0))::float8 AS relevance) r");
+}
+
+// Keyset condition สำหรับ sort หลาย field ที่ทิศทางต่างกัน:
+// (k1 > v1) OR (k1 = v1 AND k2 < v2) OR ... OR (k1 = v1 AND ... AND p.id > id)
+fn push_keyset_condition(builder: &mut QueryBuilder<'_, Postgres>, sort: &ProductSort, cursor: &ProductCursor) {
+    builder.push(" AND (");
+    for depth in 0..=sort.keys().len() {
+        if depth > 0 {
+            builder.push(" OR ");
+        }
+        builder.push("(TRUE");
+        for (key, value) in sort.keys().iter().zip(&cursor.values).take(depth) {
+            builder.push(format!(" AND {} = ", sort.sql(key)));
+            push_sort_value(builder, value);
+        }
+        match sort.keys().get(depth).zip(cursor.values.get(depth)) {
+            Some((key, value)) => {
+                let op = if key.descending { "<" } else { ">" };
+                builder.push(format!(" AND {} {} ", sort.sql(key), op));
+                push_sort_value(builder, value);
+            }
+            None => {
+                builder.push(" AND p.id > ");
+                builder.push_bind(cursor.id);
+            }
+        }
+        builder.push(")");
+    }
+    builder.push(")");
+}
+
+fn push_sort_value(builder: &mut QueryBuilder<'_, Postgres>, value: &SortValue) {
+    match value {
+        SortValue::Number(number) => builder.push_bind(*number),
+        SortValue::Text(text) => builder.push_bind(text.clone()),
+    };
+}
+
+fn read_sort_values(row: &PgRow, sort: &ProductSort) -> Result<Vec<SortValue>, sqlx::Error> {
+    sort.keys().iter()
+        .enumerate()
+        .map(|(index, key)| {
+            let column = format!("sort_{}", index);
+            if key.field.is_text() {
+                row.try_get::<String, _>(column.as_str()).map(SortValue::Text)
+            } else {
+                row.try_get::<f64, _>(column.as_str()).map(SortValue::Number)
+            }
+        })
+        .collect()
+}
+
+// threshold ของอาหาร/เครื่องดื่มเลือกตาม `serving_unit` ของแต่ละแถว
+fn push_limit(builder: &mut QueryBuilder<'_, Postgres>, limit: Limit) {
+    builder.push("CASE WHEN p.serving_unit = 'ml' THEN ");
+    builder.push_bind(limit.liquid);
+    builder.push(" ELSE ");
+    builder.push_bind(limit.solid);
+    builder.push(" END");
+}
+
+// TRUE เมื่อมีไฟแดงอย่างน้อยหนึ่งดวง (ต่อ 100 g/ml จึงต้องกรอง serving size ก่อน)
+fn push_any_red_light(builder: &mut QueryBuilder<'_, Postgres>) {
+    builder.push("(");
+    for (index, bands) in TRAFFIC_LIGHT_BANDS.iter().enumerate() {
+        if index > 0 {
+            builder.push(" OR ");
+        }
+        builder.push(format!("{} > ", bands.nutrient.column_sql(NutrientBasis::Per100)));
+        push_limit(builder, bands.red_above);
+    }
+    builder.push(")");
+}
+
+// TRUE เมื่อเกิน threshold ของ profile อย่างน้อยหนึ่งตัว
+fn push_any_high_in(builder: &mut QueryBuilder<'_, Postgres>, profile: &WarningProfile) {
+    builder.push("(");
+    for (index, threshold) in profile.thresholds.iter().enumerate() {
+        if index > 0 {
+            builder.push(" OR ");
+        }
+        builder.push(format!("{} > ", threshold.nutrient.column_sql(threshold.basis)));
+        push_limit(builder, threshold.limit);
+    }
+    builder.push(")");
+}
+
+// WHERE clause ที่ใช้ร่วมกันระหว่าง query นับจำนวนและ query ดึงข้อมูล
+// ทุกเงื่อนไขต่อด้วย " AND ..." หลัง "WHERE TRUE"; ค่าจาก client ผ่าน bind เสมอ
+// ส่วนชื่อ column มาจาก whitelist ใน `ProductFilter::ranges`
+fn push_product_filters(
+    builder: &mut QueryBuilder<'_, Postgres>,
+    pagination: &Pagination,
+    filter: &ProductFilter,
+) -> Result<(), AppError> {
+    builder.push(" WHERE TRUE");
+    if let Some(search) = pagination.search_term() {
+        // ใช้เฉพาะ operator ที่ GIN trigram index รองรับ (ILIKE และ <%)
+        builder.push(" AND (p.name ILIKE ");
+        builder.push_bind(search.pattern.clone());
+        builder.push(" OR p.brand ILIKE ");
+        builder.push_bind(search.pattern.clone());
+        builder.push(" OR ");
+        builder.push_bind(search.query.clone());
+        builder.push(" <% p.name OR ");
+        builder.push_bind(search.query.clone());
+        builder.push(" <% p.brand OR EXISTS (SELECT 1 FROM product_category spc JOIN categories sc ON sc.id = spc.category_id WHERE spc.product_id = p.id AND (sc.name ILIKE ");
+        builder.push_bind(search.pattern.clone());
+        builder.push(" OR ");
+        builder.push_bind(search.query.clone());
+        builder.push(" <% sc.name)))");
+    }
+
+    // ต่อ 100 g/ml เทียบได้เฉพาะ product ที่รู้ serving size (ไม่ถือว่าเป็น 100 g)
+    let basis = pagination.basis();
+    if basis.requires_serving_size() {
+        builder.push(" AND p.serving_size_grams > 0");
+    }
+
+    for range in filter.ranges() {
+        let expression = if range.nutrient {
+            basis.column_sql(range.column)
         } else {
-            let query = r#"
-                SELECT 
-                    p.*, 
-                    COALESCE(
-                        ARRAY_AGG(c.name) FILTER (WHERE c.name IS NOT NULL), 
-                        ARRAY[]::TEXT[]
-                    ) AS categories
-                FROM products p
-                LEFT JOIN product_category pc ON p.id = pc.product_id
-                LEFT JOIN categories c ON pc.category_id = c.id
-                GROUP BY p.id 
-                ORDER BY p.name 
-                LIMIT $1 OFFSET $2
-            "#;
-            (query, None)
+            format!("p.{}", range.column)
         };
+        if let Some(min) = range.min {
+            builder.push(format!(" AND {} >= ", expression));
+            builder.push_bind(min);
+        }
+        if let Some(max) = range.max {
+            builder.push(format!(" AND {} <= ", expression));
+            builder.push_bind(max);
+        }
+    }
+
+    if let Some(is_upf) = filter.is_upf {
+        builder.push(" AND p.is_upf = ");
+        builder.push_bind(is_upf);
+    }
+    let nova_groups = filter.nova_groups()?;
+    if !nova_groups.is_empty() {
+        builder.push(" AND p.nova_group = ANY(");
+        builder.push_bind(nova_groups);
+        builder.push(")");
+    }
 
-        let mut query_builder = sqlx::query_as::<_, ProductResponse>(base_query);
+    let nutri_score_grades = filter.nutri_score_grades()?;
+    if !nutri_score_grades.is_empty() {
+        builder.push(" AND p.nutri_score_grade = ANY(");
+        builder.push_bind(nutri_score_grades);
+        builder.push(")");
+    }
 
-        if let Some(pattern) = search_pattern {
-            query_builder = query_builder.bind(pattern);
+    if let Some(no_red_lights) = filter.no_red_lights {
+        builder.push(" AND p.serving_size_grams > 0 AND ");
+        if no_red_lights {
+            builder.push("NOT ");
         }
+        push_any_red_light(builder);
+    }
+    if let Some(no_high_in) = filter.no_high_in {
+        let profile = filter.warning_profile()?;
+        if profile.requires_serving_size() {
+            builder.push(" AND p.serving_size_grams > 0");
+        }
+        builder.push(" AND ");
+        if no_high_in {
+            builder.push("NOT ");
+        }
+        push_any_high_in(builder, profile);
+    }
+    if let Some(is_healthier) = filter.is_healthier {
+        builder.push(" AND p.is_healthier = ");
+        builder.push_bind(is_healthier);
+    }
 
-        let products_result = query_builder
-            .bind(current_limit)
-            .bind(current_offset)
-            .fetch_all(&*self.pool)
+    let brands = filter.brands();
+    if !brands.is_empty() {
+        builder.push(" AND LOWER(p.brand) = ANY(");
+        builder.push_bind(brands);
+        builder.push(")");
+    }
+
+    let category_ids = filter.category_ids()?;
+    if !category_ids.is_empty() {
+        builder.push(" AND EXISTS (SELECT 1 FROM product_category fpc WHERE fpc.product_id = p.id AND fpc.category_id = ANY(");
+        builder.push_bind(category_ids);
+        builder.push("))");
+    }
+    Ok(())
+}
+
+// คืน category id ที่ไม่ซ้ำกัน (เรียงลำดับ) เพื่อไม่ให้ชน PRIMARY KEY ของ product_category
+fn unique_category_ids(ids: &[i32]) -> Vec<i32> {
+    ids.iter().copied().collect::<BTreeSet<_>>().into_iter().collect()
+}
+
+// ตรวจสอบว่าทุก category ที่อ้างถึงมีอยู่จริง ก่อนเขียนลง product_category
+async fn ensure_categories_exist(conn: &mut PgConnection, ids: &[i32]) -> Result<(), AppError> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+
+    let found: Vec<i32> = sqlx::query_scalar("SELECT id FROM categories WHERE id = ANY($1)")
+        .bind(ids)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| {
+            error!("Error checking categories {:?}: {:?}", ids, e);
+            AppError::DatabaseError(e)
+        })?;
+
+    let missing: Vec<i32> = ids.iter()
+        .copied()
+        .filter(|id| !found.contains(id))
+        .collect();
+
+    if !missing.is_empty() {
+        warn!("Rejected unknown category ids: {:?}", missing);
+        return Err(AppError::UnknownCategories(missing));
+    }
+    Ok(())
+}
+
+async fn replace_product_categories(conn: &mut PgConnection, id: Uuid, categories_ids: &[i32]) -> Result<(), AppError> {
+    sqlx::query("DELETE FROM product_category WHERE product_id = $1")
+        .bind(id)
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::DatabaseError)?;
+
+    let insert_query = "INSERT INTO product_category (product_id, category_id) VALUES ($1, $2)";
+    for category_id in categories_ids {
+        sqlx::query(insert_query)
+            .bind(id)
+            .bind(category_id)
+            .execute(&mut *conn)
+            .await
+            .map_err(AppError::DatabaseError)?;
+    }
+    Ok(())
+}
+
+async fn replace_product_ingredients(conn: &mut PgConnection, id: Uuid, ingredients: &[String]) -> Result<(), AppError> {
+    sqlx::query("DELETE FROM product_ingredients WHERE product_id = $1")
+        .bind(id)
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::DatabaseError)?;
+
+    let insert_query = "INSERT INTO product_ingredients (product_id, position, name) VALUES ($1, $2, $3)";
+    for (position, ingredient) in ingredients.iter().enumerate() {
+        sqlx::query(insert_query)
+            .bind(id)
+            .bind(position as i32)
+            .bind(ingredient.trim())
+            .execute(&mut *conn)
+            .await
+            .map_err(AppError::DatabaseError)?;
+    }
+    Ok(())
+}
+
+// บาร์โค้ดที่เป็นของ product อื่นอยู่แล้วเป็น conflict (ไม่ย้ายบาร์โค้ดข้าม product ให้เงียบ ๆ)
+async fn replace_product_barcodes(conn: &mut PgConnection, id: Uuid, codes: &[String]) -> Result<(), AppError> {
+    let barcodes = normalized_barcodes(codes);
+    let taken: Vec<(String, Uuid)> = sqlx::query_as(
+        "SELECT barcode::TEXT, product_id FROM product_barcodes WHERE barcode = ANY($1) AND product_id <> $2 ORDER BY barcode"
+    )
+        .bind(&barcodes)
+        .bind(id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| {
+            error!("Error checking barcodes {:?}: {:?}", barcodes, e);
+            AppError::DatabaseError(e)
+        })?;
+    if let Some((barcode, product_id)) = taken.first() {
+        warn!("Rejected barcodes of other products: {:?}", taken);
+        return Err(AppError::Conflict(format!(
+            "barcode {} is already assigned to product {}", barcode, product_id
+        )));
+    }
+
+    sqlx::query("DELETE FROM product_barcodes WHERE product_id = $1")
+        .bind(id)
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::DatabaseError)?;
+
+    let insert_query = "INSERT INTO product_barcodes (barcode, product_id) VALUES ($1, $2)";
+    for barcode in &barcodes {
+        sqlx::query(insert_query)
+            .bind(barcode)
+            .bind(id)
+            .execute(&mut *conn)
+            .await
+            .map_err(AppError::DatabaseError)?;
+    }
+    Ok(())
+}
+
+// เขียนค่าที่คำนวณจากฟอร์ม (NOVA, Nutri-Score) ใน transaction เดียวกับการเขียน product
+// หรือของทั้ง catalog ใน UPDATE เดียวตอนคำนวณใหม่
+async fn write_derived_scores<'a>(
+    conn: &mut PgConnection,
+    scores: impl IntoIterator<Item = (Uuid, &'a DerivedScores)>,
+) -> Result<u64, AppError> {
+    let (ids, scores): (Vec<Uuid>, Vec<&DerivedScores>) = scores.into_iter().unzip();
+    let nutri_scores: Vec<Option<&NutriScore>> = scores.iter().map(|scores| scores.nutri_score.as_ref()).collect();
+    let result = sqlx::query(
+        r#"
+        UPDATE products p SET
+            nova_group = b.nova_group,
+            nova_markers = b.nova_markers,
+            nutri_score_grade = b.nutri_score_grade,
+            nutri_score_points = b.nutri_score_points,
+            nutri_score = b.nutri_score,
+            nutrient_density = b.nutrient_density
+        FROM UNNEST($1::UUID[], $2::SMALLINT[], $3::JSONB[], $4::TEXT[], $5::SMALLINT[], $6::JSONB[], $7::REAL[])
+            AS b(id, nova_group, nova_markers, nutri_score_grade, nutri_score_points, nutri_score, nutrient_density)
+        WHERE p.id = b.id
+        "#
+    )
+        .bind(&ids)
+        .bind(scores.iter().map(|scores| scores.nova.group).collect::<Vec<_>>())
+        .bind(scores.iter().map(|scores| Json(scores.nova.markers.as_slice())).collect::<Vec<_>>())
+        .bind(nutri_scores.iter().map(|score| score.map(|score| score.grade.as_str())).collect::<Vec<_>>())
+        .bind(nutri_scores.iter().map(|score| score.map(|score| score.points)).collect::<Vec<_>>())
+        .bind(nutri_scores.iter().map(|score| score.map(Json)).collect::<Vec<_>>())
+        .bind(scores.iter().map(|scores| scores.nutrient_density).collect::<Vec<_>>())
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| {
+            error!("Error saving derived scores of {} products: {:?}", ids.len(), e);
+            AppError::DatabaseError(e)
+        })?;
+    Ok(result.rows_affected())
+}
+
+#[async_trait]
+impl ProductRepositoryTrait for ProductRepository {
+    async fn get_product_list(&self, pagination: Pagination, filter: ProductFilter) -> Result<PageResult<ProductResponse>, AppError> {
+        let current_limit = pagination.limit();
+        let current_offset = pagination.offset();
+        let cursor = pagination.cursor()?;
+        let sort = pagination.sort()?;
+        let search = pagination.search_term();
+
+        // threshold ของ `<%` ตั้งแบบ LOCAL จึงต้องรันทั้งสอง query ใน transaction เดียวกัน
+        let mut tx = self.pool.begin().await
+            .map_err(AppError::DatabaseError)?;
+        if search.is_some() {
+            sqlx::query("SELECT set_config('pg_trgm.word_similarity_threshold', $1, true)")
+                .bind(SEARCH_SIMILARITY_THRESHOLD)
+                .execute(&mut *tx)
+                .await
+                .map_err(AppError::DatabaseError)?;
+        }
+
+        let mut count_builder = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM products p");
+        push_product_filters(&mut count_builder, &pagination, &filter)?;
+
+        let total: i64 = count_builder
+            .build_query_scalar()
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| {
+                error!("Error counting products: {:?}", e);
+                AppError::DatabaseError(e)
+            })?;
+
+        // ดึงเกิน 1 แถวเพื่อรู้ว่ายังมีหน้าถัดไปหรือไม่
+        let mut query_builder = QueryBuilder::<Postgres>::new(PRODUCT_SELECT);
+        if search.is_some() {
+            query_builder.push(", r.relevance");
+        }
+        for (index, key) in sort.keys().iter().enumerate() {
+            query_builder.push(format!(", {} AS sort_{}", sort.sql(key), index));
+        }
+        query_builder.push(" FROM products p");
+        if let Some(search) = &search {
+            push_relevance_lateral(&mut query_builder, search);
+        }
+        query_builder.push(PRODUCT_JOINS);
+        push_product_filters(&mut query_builder, &pagination, &filter)?;
+        if let Some(cursor) = &cursor {
+            push_keyset_condition(&mut query_builder, &sort, cursor);
+        }
+        let group_by = if search.is_some() { "p.id, r.relevance" } else { "p.id" };
+        query_builder.push(format!(" GROUP BY {} ORDER BY {} LIMIT ", group_by, sort.order_by()));
+        query_builder.push_bind(current_limit + 1);
+        if cursor.is_none() {
+            query_builder.push(" OFFSET ");
+            query_builder.push_bind(current_offset);
+        }
+
+        let rows_result = query_builder
+            .build()
+            .fetch_all(&mut *tx)
             .await;
         
-        if let Ok(products) = &products_result {
-            info!("Successfully fetched {} products.", products.len());
+        if let Ok(rows) = &rows_result {
+            info!("Successfully fetched {} of {} products.", rows.len().min(current_limit as usize), total);
         }
-        products_result.map_err(|e| {
+        let mut rows = rows_result.map_err(|e| {
             error!("Error fetching products: {:?}", e);
             AppError::DatabaseError(e)
-        })
+        })?;
+
+        let has_more = rows.len() as i64 > current_limit;
+        rows.truncate(current_limit as usize);
+
+        let next_cursor = match rows.last().filter(|_| has_more) {
+            Some(last) => Some(ProductCursor {
+                sort: sort.signature(),
+                values: read_sort_values(last, &sort)?,
+                id: last.try_get("id")?,
+            }.encode()),
+            None => None,
+        };
+
+        let products = rows.iter()
+            .map(ProductResponse::from_row)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        tx.commit().await
+            .map_err(AppError::DatabaseError)?;
+
+        Ok(PageResult { items: products, total, has_more, next_cursor })
     }
 
-    async fn create_product_with_categories(&self, product: ProductForm) -> Result<ProductResponse, AppError> {
+    async fn create_product_with_categories(&self, product: ProductForm, scores: DerivedScores) -> Result<ProductResponse, AppError> {
         let mut tx = self.pool.begin().await.map_err(|e| {
             error!("❌ Failed to begin transaction: {:?}", e);
             AppError::DatabaseError(e)
         })?;
 
+        let categories_ids = unique_category_ids(&product.categories_ids);
+        ensure_categories_exist(&mut tx, &categories_ids).await?;
+
         let product_row = match sqlx::query_as::<_, Product>(
             r#"
             INSERT INTO products (
                 name, brand, image_url, serving_size_grams, calories, fat, sugar, 
                 sodium, protein, carbs, saturated_fat, cholesterol, vitamin_c, 
-                calcium, vitamin_b1, vitamin_a, price, is_upf, is_healthier
+                calcium, vitamin_b1, vitamin_a, price, is_upf, is_healthier, serving_unit,
+                fibre, fruit_veg_percent
             ) VALUES (
-                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22
             )
             RETURNING *
             "#
@@ -131,6 +582,9 @@ impl ProductRepositoryTrait for ProductRepository {
         .bind(product.price)
         .bind(product.is_upf)
         .bind(product.is_healthier)
+        .bind(product.serving_unit.as_str())
+        .bind(product.fibre)
+        .bind(product.fruit_veg_percent)
         .fetch_one(&mut *tx)
         .await {
             Ok(row) => {
@@ -151,17 +605,15 @@ impl ProductRepositoryTrait for ProductRepository {
         };
 
         // Insert categories with better error handling
-        let mut inserted_categories = Vec::new();
-        
-        if !product.categories_ids.is_empty() {
+        if !categories_ids.is_empty() {
             debug!("📝 Inserting {} categories for product {}", 
-                    product.categories_ids.len(), product_row.id);
+                    categories_ids.len(), product_row.id);
 
-            for (index, category_id) in product.categories_ids.iter().enumerate() {
+            for (index, category_id) in categories_ids.iter().enumerate() {
                 if let Err(e) = sqlx::query(
                     "INSERT INTO product_category (product_id, category_id) VALUES ($1, $2)"
                 )
-                .bind(&product_row.id)
+                .bind(product_row.id)
                 .bind(category_id)
                 .execute(&mut *tx)
                 .await {
@@ -179,12 +631,14 @@ impl ProductRepositoryTrait for ProductRepository {
                     return Err(AppError::DatabaseError(e));
                 } else {
                     debug!("✅ Category {} linked to product {}", category_id, product_row.id);
-                    // 🔧 FIX: Get category name instead of storing UUID
-                    inserted_categories.push(format!("{}", category_id)); // This should be category name
                 }
             }
         }
 
+        replace_product_ingredients(&mut tx, product_row.id, &product.ingredients).await?;
+        replace_product_barcodes(&mut tx, product_row.id, &product.barcodes).await?;
+        write_derived_scores(&mut tx, [(product_row.id, &scores)]).await?;
+
         // Commit transaction
         if let Err(e) = tx.commit().await {
             error!("❌ Failed to commit transaction: {:?}", e);
@@ -198,21 +652,9 @@ impl ProductRepositoryTrait for ProductRepository {
     }
 
     async fn get_product_by_id(&self, id: Uuid) -> Result<ProductResponse, AppError> {
-        let query = r#"
-            SELECT
-                p.*,
-                COALESCE(
-                    ARRAY_AGG(c.name) FILTER (WHERE c.name IS NOT NULL),
-                    ARRAY[]::TEXT[]
-                ) AS categories
-            FROM products p
-            LEFT JOIN product_category pc ON p.id = pc.product_id
-            LEFT JOIN categories c ON pc.category_id = c.id
-            WHERE p.id = $1
-            GROUP BY p.id
-        "#;
+        let query = format!("{} FROM products p {} WHERE p.id = $1 GROUP BY p.id", PRODUCT_SELECT, PRODUCT_JOINS);
 
-        let product_result = sqlx::query_as::<_, ProductResponse>(query)
+        let product_result = sqlx::query_as::<_, ProductResponse>(&query)
             .bind(id)
             .fetch_one(&*self.pool)
             .await;
@@ -233,9 +675,36 @@ impl ProductRepositoryTrait for ProductRepository {
         }
     }
 
-    async fn update_product_by_id(&self, id: Uuid, product: ProductForm) -> Result<ProductResponse, AppError> {
+    async fn get_product_by_barcode(&self, barcode: &str) -> Result<ProductResponse, AppError> {
+        let query = format!(
+            "{} FROM products p {} WHERE p.id = (SELECT pb.product_id FROM product_barcodes pb WHERE pb.barcode = $1) GROUP BY p.id",
+            PRODUCT_SELECT, PRODUCT_JOINS
+        );
+
+        let product_result = sqlx::query_as::<_, ProductResponse>(&query)
+            .bind(barcode)
+            .fetch_one(&*self.pool)
+            .await;
+
+        match product_result {
+            Ok(product) => {
+                info!("Successfully fetched product with barcode: {}", barcode);
+                Ok(product)
+            }
+            Err(sqlx::Error::RowNotFound) => {
+                warn!("Product with barcode {} not found", barcode);
+                Err(AppError::NotFound)
+            }
+            Err(e) => {
+                error!("Error fetching product by barcode {}: {:?}", barcode, e);
+                Err(AppError::DatabaseError(e))
+            }
+        }
+    }
+
+    async fn update_product_by_id(&self, id: Uuid, product: ProductForm, scores: DerivedScores) -> Result<ProductResponse, AppError> {
         let mut tx = self.pool.begin().await
-            .map_err(|e| AppError::DatabaseError(e))?;
+            .map_err(AppError::DatabaseError)?;
 
         // 🔧 FIX: Check if product exists first
         let exists_query = "SELECT EXISTS(SELECT 1 FROM products WHERE id = $1)";
@@ -243,13 +712,16 @@ impl ProductRepositoryTrait for ProductRepository {
             .bind(id)
             .fetch_one(&mut *tx)
             .await
-            .map_err(|e| AppError::DatabaseError(e))?;
+            .map_err(AppError::DatabaseError)?;
 
         if !exists {
             let _ = tx.rollback().await;
             return Err(AppError::NotFound);
         }
 
+        let categories_ids = unique_category_ids(&product.categories_ids);
+        ensure_categories_exist(&mut tx, &categories_ids).await?;
+
         // Update product - 🔧 FIX: Use proper UPDATE with all fields
         let product_query = r#"
             UPDATE products 
@@ -272,7 +744,10 @@ impl ProductRepositoryTrait for ProductRepository {
                 vitamin_a = $17,
                 price = $18,
                 is_upf = $19,
-                is_healthier = $20
+                is_healthier = $20,
+                serving_unit = $21,
+                fibre = $22,
+                fruit_veg_percent = $23
             WHERE id = $1
         "#;
 
@@ -297,72 +772,363 @@ impl ProductRepositoryTrait for ProductRepository {
             .bind(product.price)
             .bind(product.is_upf)
             .bind(product.is_healthier)
+            .bind(product.serving_unit.as_str())
+            .bind(product.fibre)
+            .bind(product.fruit_veg_percent)
             .execute(&mut *tx)
             .await
-            .map_err(|e| AppError::DatabaseError(e))?;
+            .map_err(AppError::DatabaseError)?;
 
         if update_result.rows_affected() == 0 {
             let _ = tx.rollback().await;
             return Err(AppError::NotFound);
         }
 
-        // Update categories
-        if !product.categories_ids.is_empty() {
-            // Delete existing categories
-            let delete_query = "DELETE FROM product_category WHERE product_id = $1";
-            sqlx::query(delete_query)
-                .bind(id)
+        // Full replacement: the given list becomes the product's categories (empty clears them)
+        replace_product_categories(&mut tx, id, &categories_ids).await?;
+        replace_product_ingredients(&mut tx, id, &product.ingredients).await?;
+        replace_product_barcodes(&mut tx, id, &product.barcodes).await?;
+        write_derived_scores(&mut tx, [(id, &scores)]).await?;
+
+        // Commit transaction
+        tx.commit().await
+            .map_err(AppError::DatabaseError)?;
+
+        // Fetch updated product
+        self.get_product_by_id(id).await
+    }
+
+    async fn patch_product_by_id(&self, id: Uuid, patch: ProductPatch, rescore: &ProductRescorer<'_>) -> Result<ProductResponse, AppError> {
+        let mut tx = self.pool.begin().await
+            .map_err(AppError::DatabaseError)?;
+
+        // Lock the row so concurrent patches of other fields don't interleave
+        let locked: Option<Uuid> = sqlx::query_scalar("SELECT id FROM products WHERE id = $1 FOR UPDATE")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(AppError::DatabaseError)?;
+
+        if locked.is_none() {
+            let _ = tx.rollback().await;
+            return Err(AppError::NotFound);
+        }
+
+        // Scores come from the locked row merged with the patch, so they match what gets stored
+        let query = format!("{} FROM products p {} WHERE p.id = $1 GROUP BY p.id", PRODUCT_SELECT, PRODUCT_JOINS);
+        let current = sqlx::query_as::<_, ProductResponse>(&query)
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        let scores = match rescore(&patch.merged_with(&current)) {
+            Ok(scores) => scores,
+            Err(e) => {
+                let _ = tx.rollback().await;
+                return Err(e);
+            }
+        };
+
+        let categories_ids = patch.categories_ids.as_deref().map(unique_category_ids);
+        if let Some(categories_ids) = &categories_ids {
+            ensure_categories_exist(&mut tx, categories_ids).await?;
+        }
+
+        // SET เฉพาะ field ที่ client ส่งมา; `Some(None)` ของ field ที่เป็น optional คือการเคลียร์ค่า
+        let mut builder = QueryBuilder::<Postgres>::new("UPDATE products SET ");
+        let mut assignments = builder.separated(", ");
+        let mut changed = 0;
+
+        macro_rules! set_column {
+            ($column:literal, $value:expr) => {
+                if let Some(value) = $value {
+                    assignments.push(concat!($column, " = "));
+                    assignments.push_bind_unseparated(value);
+                    changed += 1;
+                }
+            };
+        }
+
+        set_column!("name", patch.name);
+        set_column!("brand", patch.brand);
+        set_column!("image_url", patch.image_url);
+        set_column!("serving_size_grams", patch.serving_size_grams);
+        set_column!("serving_unit", patch.serving_unit.map(|unit| unit.as_str()));
+        set_column!("calories", patch.calories);
+        set_column!("fat", patch.fat);
+        set_column!("sugar", patch.sugar);
+        set_column!("sodium", patch.sodium);
+        set_column!("protein", patch.protein);
+        set_column!("carbs", patch.carbs);
+        set_column!("saturated_fat", patch.saturated_fat);
+        set_column!("cholesterol", patch.cholesterol);
+        set_column!("vitamin_c", patch.vitamin_c);
+        set_column!("calcium", patch.calcium);
+        set_column!("vitamin_b1", patch.vitamin_b1);
+        set_column!("vitamin_a", patch.vitamin_a);
+        set_column!("fibre", patch.fibre);
+        set_column!("fruit_veg_percent", patch.fruit_veg_percent);
+        set_column!("price", patch.price);
+        set_column!("is_upf", patch.is_upf);
+        set_column!("is_healthier", patch.is_healthier);
+
+        if changed > 0 {
+            builder.push(" WHERE id = ");
+            builder.push_bind(id);
+            builder.build()
                 .execute(&mut *tx)
                 .await
-                .map_err(|e| AppError::DatabaseError(e))?;
-
-            // Insert new categories
-            let insert_query = "INSERT INTO product_category (product_id, category_id) VALUES ($1, $2)";
-            
-            for category_id in &product.categories_ids {
-                sqlx::query(insert_query)
-                    .bind(id)
-                    .bind(category_id)
-                    .execute(&mut *tx)
-                    .await
-                    .map_err(|e| AppError::DatabaseError(e))?;
-            }
+                .map_err(|e| {
+                    error!("Error patching product {}: {:?}", id, e);
+                    AppError::DatabaseError(e)
+                })?;
+            debug!("✅ Patched {} columns of product {}", changed, id);
         }
 
-        // Commit transaction
+        if let Some(categories_ids) = &categories_ids {
+            replace_product_categories(&mut tx, id, categories_ids).await?;
+        }
+        if let Some(ingredients) = &patch.ingredients {
+            replace_product_ingredients(&mut tx, id, ingredients).await?;
+        }
+        if let Some(barcodes) = &patch.barcodes {
+            replace_product_barcodes(&mut tx, id, barcodes).await?;
+        }
+        write_derived_scores(&mut tx, [(id, &scores)]).await?;
+
         tx.commit().await
-            .map_err(|e| AppError::DatabaseError(e))?;
+            .map_err(AppError::DatabaseError)?;
 
-        // Fetch updated product
         self.get_product_by_id(id).await
     }
 
     // 🔧 ADD: Missing delete method implementation
     async fn delete_product_by_id(&self, id: Uuid) -> Result<u64, AppError> {
         let mut tx = self.pool.begin().await
-            .map_err(|e| AppError::DatabaseError(e))?;
+            .map_err(AppError::DatabaseError)?;
 
         // Delete categories first (foreign key constraint)
         sqlx::query("DELETE FROM product_category WHERE product_id = $1")
             .bind(id)
             .execute(&mut *tx)
             .await
-            .map_err(|e| AppError::DatabaseError(e))?;
+            .map_err(AppError::DatabaseError)?;
 
-        // Delete product
+        // Delete product; meal items and food log entries RESTRICT the delete
         let result = sqlx::query("DELETE FROM products WHERE id = $1")
             .bind(id)
             .execute(&mut *tx)
             .await
-            .map_err(|e| AppError::DatabaseError(e))?;
+            .map_err(|e| match &e {
+                sqlx::Error::Database(db_err) if db_err.is_foreign_key_violation() => AppError::Conflict(
+                    format!("product {} is still used by meals or food log entries", id)
+                ),
+                _ => AppError::DatabaseError(e),
+            })?;
 
         let affected_rows = result.rows_affected();
 
         // Commit transaction
         tx.commit().await
-            .map_err(|e| AppError::DatabaseError(e))?;
+            .map_err(AppError::DatabaseError)?;
 
         info!("Successfully deleted product with id: {}, affected rows: {}", id, affected_rows);
         Ok(affected_rows)
     }
-}
\ No newline at end of file
+
+    async fn get_product_with_food_groups(&self, id: Uuid) -> Result<ProductWithFoodGroups, AppError> {
+        let query = format!("{}{} FROM products p {} WHERE p.id = $1 GROUP BY p.id", PRODUCT_SELECT, FOOD_GROUPS_COLUMN, PRODUCT_JOINS);
+
+        let result = sqlx::query_as::<_, ProductWithFoodGroups>(&query)
+            .bind(id)
+            .fetch_one(&*self.pool)
+            .await;
+
+        match result {
+            Ok(entry) => Ok(entry),
+            Err(sqlx::Error::RowNotFound) => {
+                warn!("Product with id {} not found", id);
+                Err(AppError::NotFound)
+            }
+            Err(e) => {
+                error!("Error fetching food groups of product {}: {:?}", id, e);
+                Err(AppError::DatabaseError(e))
+            }
+        }
+    }
+
+    async fn get_products_with_food_groups(&self) -> Result<Vec<ProductWithFoodGroups>, AppError> {
+        let query = format!("{}{} FROM products p {} GROUP BY p.id ORDER BY p.name, p.id", PRODUCT_SELECT, FOOD_GROUPS_COLUMN, PRODUCT_JOINS);
+
+        let entries = sqlx::query_as::<_, ProductWithFoodGroups>(&query)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| {
+                error!("Error fetching products with food groups: {:?}", e);
+                AppError::DatabaseError(e)
+            })?;
+
+        info!("Fetched {} products for the Healthier Choice check", entries.len());
+        Ok(entries)
+    }
+
+    async fn get_all_product_ingredients(&self) -> Result<Vec<(Uuid, Vec<String>)>, AppError> {
+        let rows: Vec<(Uuid, Vec<String>)> = sqlx::query_as(
+            "SELECT product_id, ARRAY_AGG(name::TEXT ORDER BY position) FROM product_ingredients GROUP BY product_id"
+        )
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| {
+                error!("Error fetching product ingredients: {:?}", e);
+                AppError::DatabaseError(e)
+            })?;
+        Ok(rows)
+    }
+
+    // UPDATE เดียวทั้ง batch: สำเร็จทั้งหมดหรือไม่เปลี่ยนเลย และเขียนเฉพาะแถวที่ค่าต่างจากเดิม
+    async fn save_nova_classifications(&self, classifications: &[(Uuid, NovaClassification)]) -> Result<u64, AppError> {
+        let ids: Vec<Uuid> = classifications.iter().map(|(id, _)| *id).collect();
+        let groups: Vec<Option<i16>> = classifications.iter().map(|(_, nova)| nova.group).collect();
+        let markers: Vec<Json<&[NovaMarker]>> = classifications.iter()
+            .map(|(_, nova)| Json(nova.markers.as_slice()))
+            .collect();
+
+        let result = sqlx::query(
+            r#"
+            UPDATE products p SET nova_group = b.nova_group, nova_markers = b.nova_markers
+            FROM UNNEST($1::UUID[], $2::SMALLINT[], $3::JSONB[]) AS b(id, nova_group, nova_markers)
+            WHERE p.id = b.id
+              AND (p.nova_group IS DISTINCT FROM b.nova_group OR p.nova_markers <> b.nova_markers)
+            "#
+        )
+            .bind(ids)
+            .bind(groups)
+            .bind(markers)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| {
+                error!("Error saving NOVA groups of {} products: {:?}", classifications.len(), e);
+                AppError::DatabaseError(e)
+            })?;
+        Ok(result.rows_affected())
+    }
+
+    // UPDATE เดียวใน transaction: สำเร็จทั้ง catalog หรือไม่เปลี่ยนเลย
+    async fn save_derived_scores(&self, scores: &[(Uuid, DerivedScores)]) -> Result<u64, AppError> {
+        let mut tx = self.pool.begin().await
+            .map_err(AppError::DatabaseError)?;
+        let saved = write_derived_scores(&mut tx, scores.iter().map(|(id, scores)| (*id, scores))).await?;
+        tx.commit().await
+            .map_err(AppError::DatabaseError)?;
+        Ok(saved)
+    }
+
+    async fn get_meal_plan_candidates(&self, exclude: &PlanExclusions, objective: PlanObjective) -> Result<(Vec<ProductResponse>, bool), AppError> {
+        let mut builder = QueryBuilder::<Postgres>::new(format!("{} FROM products p {}", PRODUCT_SELECT, PRODUCT_JOINS));
+        // product ที่ไม่มีราคาใช้คำนวณงบไม่ได้
+        builder.push(" WHERE p.price > 0");
+        if !exclude.product_ids.is_empty() {
+            builder.push(" AND NOT (p.id = ANY(");
+            builder.push_bind(exclude.product_ids.clone());
+            builder.push("))");
+        }
+        if !exclude.category_ids.is_empty() {
+            builder.push(" AND NOT EXISTS (SELECT 1 FROM product_category xpc WHERE xpc.product_id = p.id AND xpc.category_id = ANY(");
+            builder.push_bind(exclude.category_ids.clone());
+            builder.push("))");
+        }
+        if exclude.upf {
+            builder.push(" AND NOT p.is_upf AND p.nova_group IS DISTINCT FROM 4");
+        }
+        builder.push(format!(" GROUP BY p.id ORDER BY {} LIMIT ", objective.candidate_order_sql()));
+        // แถวเกินหนึ่งแถวบอกว่ามี product ที่ถูกตัดออก
+        builder.push_bind(MAX_MEAL_PLAN_CANDIDATES + 1);
+
+        let mut candidates = builder.build_query_as::<ProductResponse>()
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| {
+                error!("Error fetching meal plan candidates: {:?}", e);
+                AppError::DatabaseError(e)
+            })?;
+
+        let truncated = candidates.len() as i64 > MAX_MEAL_PLAN_CANDIDATES;
+        if truncated {
+            candidates.truncate(MAX_MEAL_PLAN_CANDIDATES as usize);
+            warn!("Meal plan candidates capped at {} products", MAX_MEAL_PLAN_CANDIDATES);
+        }
+        Ok((candidates, truncated))
+    }
+
+    async fn get_best_value_products(&self, category_id: i32, query: &BestValueQuery) -> Result<Vec<ProductResponse>, AppError> {
+        let mut builder = QueryBuilder::<Postgres>::new(format!("{} FROM products p {}", PRODUCT_SELECT, PRODUCT_JOINS));
+        // ค่าต่อบาทไม่มีความหมายเมื่อไม่มีราคา
+        builder.push(" WHERE p.price > 0 AND EXISTS (SELECT 1 FROM product_category bpc WHERE bpc.product_id = p.id AND bpc.category_id = ");
+        builder.push_bind(category_id);
+        builder.push(")");
+        if query.non_upf {
+            builder.push(" AND NOT p.is_upf AND p.nova_group IS DISTINCT FROM 4");
+        }
+        if query.healthier {
+            builder.push(" AND p.is_healthier");
+        }
+        builder.push(format!(
+            " GROUP BY p.id ORDER BY {} DESC, p.price ASC, p.id ASC LIMIT ",
+            query.metric.sort_field().sql(NutrientBasis::Serving)
+        ));
+        builder.push_bind(query.limit());
+
+        builder.build_query_as::<ProductResponse>()
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| {
+                error!("Error ranking best value products of category {}: {:?}", category_id, e);
+                AppError::DatabaseError(e)
+            })
+    }
+
+    async fn get_alternative_candidates(&self, criteria: &AlternativeCriteria) -> Result<Vec<ProductResponse>, AppError> {
+        let mut builder = QueryBuilder::<Postgres>::new(format!("{} FROM products p {}", PRODUCT_SELECT, PRODUCT_JOINS));
+        builder.push(" WHERE p.id <> ");
+        builder.push_bind(criteria.product_id);
+        builder.push(" AND EXISTS (SELECT 1 FROM product_category apc JOIN product_category opc ON opc.category_id = apc.category_id WHERE apc.product_id = p.id AND opc.product_id = ");
+        builder.push_bind(criteria.product_id);
+        builder.push(")");
+        if criteria.basis.requires_serving_size() {
+            builder.push(" AND p.serving_size_grams > 0");
+        }
+        if let Some(max_price) = criteria.max_price {
+            builder.push(" AND p.price > 0 AND p.price <= ");
+            builder.push_bind(max_price);
+        }
+
+        // ไม่สูงกว่าเดิมทั้งสามตัว และลดได้อย่างน้อยหนึ่งตัว
+        let limited = [
+            ("sugar", criteria.sugar),
+            ("sodium", criteria.sodium),
+            ("saturated_fat", criteria.saturated_fat),
+        ];
+        for (column, reference) in limited {
+            builder.push(format!(" AND {} <= ", criteria.basis.column_sql(column)));
+            builder.push_bind(reference);
+        }
+        let reduced_ratio = 1.0 - f64::from(MIN_REDUCTION_PERCENT) / 100.0;
+        builder.push(" AND (FALSE");
+        for (column, reference) in limited.into_iter().filter(|(_, reference)| *reference > 0.0) {
+            builder.push(format!(" OR {} <= ", criteria.basis.column_sql(column)));
+            builder.push_bind(reference * reduced_ratio);
+        }
+        builder.push(")");
+
+        builder.push(" GROUP BY p.id ORDER BY (p.is_healthier OR (NOT p.is_upf AND p.nova_group IS DISTINCT FROM 4)) DESC, p.price ASC, p.id ASC LIMIT ");
+        builder.push_bind(MAX_ALTERNATIVE_CANDIDATES);
+
+        builder.build_query_as::<ProductResponse>()
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| {
+                error!("Error fetching alternatives of product {}: {:?}", criteria.product_id, e);
+                AppError::DatabaseError(e)
+            })
+    }
+}
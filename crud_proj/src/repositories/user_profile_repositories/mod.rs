@@ -0,0 +1,143 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    errors::AppError,
+    models::user_profiles::{UserProfile, UserProfileForm},
+};
+
+#[async_trait]
+pub trait UserProfileRepositoryTrait: Send + Sync {
+    async fn get_user_profile_list(&self) -> Result<Vec<UserProfile>, AppError>;
+    async fn create_user_profile(&self, profile: UserProfileForm) -> Result<UserProfile, AppError>;
+    async fn get_user_profile_by_id(&self, id: Uuid) -> Result<UserProfile, AppError>;
+    async fn update_user_profile_by_id(&self, id: Uuid, profile: UserProfileForm) -> Result<UserProfile, AppError>;
+    async fn delete_user_profile_by_id(&self, id: Uuid) -> Result<u64, AppError>;
+}
+
+pub struct UserProfileRepository {
+    pool: Arc<PgPool>,
+}
+
+impl UserProfileRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        UserProfileRepository { pool }
+    }
+}
+
+const USER_PROFILE_COLUMNS: &str = "id, name, age, sex, height_cm, weight_kg, activity_level, goal, timezone, daily_budget";
+
+#[async_trait]
+impl UserProfileRepositoryTrait for UserProfileRepository {
+    async fn get_user_profile_list(&self) -> Result<Vec<UserProfile>, AppError> {
+        let query = format!("SELECT {} FROM user_profiles ORDER BY name, id", USER_PROFILE_COLUMNS);
+        let profiles = sqlx::query_as::<_, UserProfile>(&query)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| {
+                error!("Error fetching user profiles: {:?}", e);
+                AppError::DatabaseError(e)
+            })?;
+
+        info!("Successfully fetched {} user profiles.", profiles.len());
+        Ok(profiles)
+    }
+
+    async fn create_user_profile(&self, profile: UserProfileForm) -> Result<UserProfile, AppError> {
+        let query = format!(
+            "INSERT INTO user_profiles (name, age, sex, height_cm, weight_kg, activity_level, goal, timezone, daily_budget) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING {}",
+            USER_PROFILE_COLUMNS
+        );
+        let row = sqlx::query_as::<_, UserProfile>(&query)
+            .bind(profile.name.trim())
+            .bind(profile.age)
+            .bind(profile.sex.as_str())
+            .bind(profile.height_cm)
+            .bind(profile.weight_kg)
+            .bind(profile.activity_level.as_str())
+            .bind(profile.goal.as_str())
+            .bind(profile.timezone.trim())
+            .bind(profile.daily_budget)
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| {
+                error!("Error creating user profile {:?}: {:?}", profile.name, e);
+                AppError::DatabaseError(e)
+            })?;
+
+        debug!("✅ User profile created successfully: id={}", row.id);
+        Ok(row)
+    }
+
+    async fn get_user_profile_by_id(&self, id: Uuid) -> Result<UserProfile, AppError> {
+        let query = format!("SELECT {} FROM user_profiles WHERE id = $1", USER_PROFILE_COLUMNS);
+        let result = sqlx::query_as::<_, UserProfile>(&query)
+            .bind(id)
+            .fetch_one(&*self.pool)
+            .await;
+
+        match result {
+            Ok(profile) => Ok(profile),
+            Err(sqlx::Error::RowNotFound) => {
+                warn!("User profile with id {} not found", id);
+                Err(AppError::NotFound)
+            }
+            Err(e) => {
+                error!("Error fetching user profile by id {}: {:?}", id, e);
+                Err(AppError::DatabaseError(e))
+            }
+        }
+    }
+
+    async fn update_user_profile_by_id(&self, id: Uuid, profile: UserProfileForm) -> Result<UserProfile, AppError> {
+        let query = format!(
+            "UPDATE user_profiles SET name = $2, age = $3, sex = $4, height_cm = $5, weight_kg = $6, \
+             activity_level = $7, goal = $8, timezone = $9, daily_budget = $10 WHERE id = $1 RETURNING {}",
+            USER_PROFILE_COLUMNS
+        );
+        let result = sqlx::query_as::<_, UserProfile>(&query)
+            .bind(id)
+            .bind(profile.name.trim())
+            .bind(profile.age)
+            .bind(profile.sex.as_str())
+            .bind(profile.height_cm)
+            .bind(profile.weight_kg)
+            .bind(profile.activity_level.as_str())
+            .bind(profile.goal.as_str())
+            .bind(profile.timezone.trim())
+            .bind(profile.daily_budget)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(|e| {
+                error!("Error updating user profile {}: {:?}", id, e);
+                AppError::DatabaseError(e)
+            })?;
+
+        match result {
+            Some(row) => {
+                info!("Successfully updated user profile {}", id);
+                Ok(row)
+            }
+            None => {
+                warn!("User profile with id {} not found", id);
+                Err(AppError::NotFound)
+            }
+        }
+    }
+
+    async fn delete_user_profile_by_id(&self, id: Uuid) -> Result<u64, AppError> {
+        let result = sqlx::query("DELETE FROM user_profiles WHERE id = $1")
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(AppError::DatabaseError)?;
+
+        let affected_rows = result.rows_affected();
+        info!("Successfully deleted user profile with id: {}, affected rows: {}", id, affected_rows);
+        Ok(affected_rows)
+    }
+}
@@ -0,0 +1,14 @@
+use std::sync::Arc;
+use axum::{routing::{get}, Router};
+use sqlx::{Pool, Postgres};
+
+use crate::handlers::additive_handler;
+
+pub fn create_router() -> Router<Arc<Pool<Postgres>>> {
+    Router::new()
+        .route(
+            "/",
+            get(additive_handler::get_additive_list)
+                .post(additive_handler::add_additive),
+        )
+}
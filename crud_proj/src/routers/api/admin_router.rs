@@ -0,0 +1,17 @@
+use std::sync::Arc;
+use axum::{routing::{get, post}, Router};
+use sqlx::{Pool, Postgres};
+
+use crate::handlers::{healthier_choice_handler, product_handler};
+
+pub fn create_router() -> Router<Arc<Pool<Postgres>>> {
+    Router::new()
+        .route(
+            "/healthier-choice/mismatches",
+            get(healthier_choice_handler::get_healthier_choice_mismatches),
+        )
+        .route(
+            "/products/recompute-scores",
+            post(product_handler::recompute_derived_scores),
+        )
+}
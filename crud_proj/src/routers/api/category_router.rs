@@ -0,0 +1,24 @@
+use std::sync::Arc;
+use axum::{routing::{get}, Router};
+use sqlx::{Pool, Postgres};
+
+use crate::handlers::{category_handler, value_metrics_handler};
+
+pub fn create_router() -> Router<Arc<Pool<Postgres>>> {
+    Router::new()
+        .route(
+            "/",
+            get(category_handler::get_category_list)
+                .post(category_handler::add_category),
+        )
+        .route(
+            "/{id}",
+            get(category_handler::get_category_with_products)
+                .patch(category_handler::rename_category_with_id)
+                .delete(category_handler::delete_category_with_id),
+        )
+        .route(
+            "/{id}/best-value",
+            get(value_metrics_handler::get_category_best_value),
+        )
+}
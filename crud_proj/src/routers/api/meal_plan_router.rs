@@ -0,0 +1,10 @@
+use std::sync::Arc;
+use axum::{routing::{post}, Router};
+use sqlx::{Pool, Postgres};
+
+use crate::handlers::meal_plan_handler;
+
+pub fn create_router() -> Router<Arc<Pool<Postgres>>> {
+    Router::new()
+        .route("/optimize", post(meal_plan_handler::optimize_meal_plan))
+}
@@ -0,0 +1,20 @@
+use std::sync::Arc;
+use axum::{routing::{get}, Router};
+use sqlx::{Pool, Postgres};
+
+use crate::handlers::meal_handler;
+
+pub fn create_router() -> Router<Arc<Pool<Postgres>>> {
+    Router::new()
+        .route(
+            "/",
+            get(meal_handler::get_meal_list)
+                .post(meal_handler::add_meal),
+        )
+        .route(
+            "/{id}",
+            get(meal_handler::get_meal_from_id)
+                .put(meal_handler::update_meal_with_id)
+                .delete(meal_handler::delete_meal_with_id),
+        )
+}
@@ -1 +1,7 @@
-pub mod product_router;
\ No newline at end of file
+pub mod product_router;
+pub mod category_router;
+pub mod admin_router;
+pub mod additive_router;
+pub mod user_profile_router;
+pub mod meal_plan_router;
+pub mod meal_router;
\ No newline at end of file
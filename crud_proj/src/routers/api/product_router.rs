@@ -1,8 +1,8 @@
 use std::sync::Arc;
-use axum::{routing::{get}, Router};
+use axum::{routing::{get, post}, Router};
 use sqlx::{Pool, Postgres};
 
-use crate::handlers::product_handler;
+use crate::handlers::{alternatives_handler, healthier_choice_handler, product_comparison_handler, product_handler};
 
 pub fn create_router() -> Router<Arc<Pool<Postgres>>> {
     Router::new()
@@ -11,10 +11,27 @@ pub fn create_router() -> Router<Arc<Pool<Postgres>>> {
             get(product_handler::get_product_list)
                 .post(product_handler::add_product),
         )
+        .route(
+            "/compare",
+            post(product_comparison_handler::compare_products),
+        )
+        .route(
+            "/barcode/{code}",
+            get(product_handler::get_product_from_barcode),
+        )
         .route(
             "/{id}",
             get(product_handler::get_product_from_id)
-                .patch(product_handler::update_product_with_id)
+                .put(product_handler::update_product_with_id)
+                .patch(product_handler::patch_product_with_id)
                 .delete(product_handler::delete_product_with_id),
         )
+        .route(
+            "/{id}/healthier-choice",
+            get(healthier_choice_handler::get_product_healthier_choice),
+        )
+        .route(
+            "/{id}/alternatives",
+            get(alternatives_handler::get_product_alternatives),
+        )
 }
\ No newline at end of file
@@ -0,0 +1,35 @@
+use std::sync::Arc;
+use axum::{routing::{get}, Router};
+use sqlx::{Pool, Postgres};
+
+use crate::handlers::{food_log_handler, user_profile_handler};
+
+pub fn create_router() -> Router<Arc<Pool<Postgres>>> {
+    Router::new()
+        .route(
+            "/",
+            get(user_profile_handler::get_user_profile_list)
+                .post(user_profile_handler::add_user_profile),
+        )
+        .route(
+            "/{id}",
+            get(user_profile_handler::get_user_profile_from_id)
+                .put(user_profile_handler::update_user_profile_with_id)
+                .delete(user_profile_handler::delete_user_profile_with_id),
+        )
+        .route(
+            "/{id}/food-log",
+            get(food_log_handler::get_food_log)
+                .post(food_log_handler::add_food_log_entry),
+        )
+        .route(
+            "/{id}/food-log/summary",
+            get(food_log_handler::get_daily_summary),
+        )
+        .route(
+            "/{id}/food-log/{entry_id}",
+            get(food_log_handler::get_food_log_entry_from_id)
+                .put(food_log_handler::update_food_log_entry_with_id)
+                .delete(food_log_handler::delete_food_log_entry_with_id),
+        )
+}
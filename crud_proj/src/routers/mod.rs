@@ -1,9 +1,11 @@
-use axum::{response::Json, routing::get, Router};
+use axum::{middleware, response::Json, routing::get, Router};
 use serde_json::{Value, json};
 use chrono::Utc;
 use sqlx::{PgPool};
 use std::sync::Arc;
 
+use crate::middlewares::request_id;
+
 
 // Modules
 pub mod api;
@@ -17,13 +19,19 @@ pub fn create_app_router(db_pool: Arc<PgPool>) -> Router {
         
         // api route
         .nest("/api/v1", api_v1_routes(db_pool))
+        .layer(middleware::from_fn(request_id::request_id_middleware))
 }
 
 // สร้าง API v1 routes
 fn api_v1_routes(db_pool: Arc<PgPool>) -> Router {
     Router::new()
         .nest("/products", api::product_router::create_router())  
-        // .nest("/categories", api::categories_router::create_app_router())
+        .nest("/categories", api::category_router::create_router())
+        .nest("/additives", api::additive_router::create_router())
+        .nest("/profiles", api::user_profile_router::create_router())
+        .nest("/meal-plans", api::meal_plan_router::create_router())
+        .nest("/meals", api::meal_router::create_router())
+        .nest("/admin", api::admin_router::create_router())
         .with_state(db_pool)
 }
 
@@ -34,7 +42,15 @@ async fn root_handler() -> Json<Value> {
         "endpoints": [
             "/hello",
             "/health",
-            "/api/v1/products"
+            "/api/v1/products",
+            "/api/v1/products/compare",
+            "/api/v1/categories",
+            "/api/v1/additives",
+            "/api/v1/profiles",
+            "/api/v1/meal-plans/optimize",
+            "/api/v1/meals",
+            "/api/v1/admin/healthier-choice/mismatches",
+            "/api/v1/admin/products/recompute-scores"
         ]
     }))
 }
@@ -0,0 +1,177 @@
+use std::{cmp::Ordering, sync::Arc};
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    errors::AppError,
+    models::{
+        alternatives::{
+            Alternative, AlternativeCriteria, AlternativesQuery, NutrientChange, ProductAlternatives,
+            MIN_REDUCTION_PERCENT,
+        },
+        front_of_pack::warning_profile,
+        nutrition::{round_to, NutrientBasis},
+        products::ProductResponse,
+    },
+    repositories::product_repositories::ProductRepositoryTrait,
+    services::product_service::present,
+};
+
+type LimitedValue = fn(&ProductResponse) -> f32;
+
+// สารอาหารที่ทางเลือกต้องลดได้ (ชื่อในเหตุผล, หน่วย, ค่าต่อ serving)
+const LIMITED_NUTRIENTS: [(&str, &str, LimitedValue); 3] = [
+    ("sugar", "g", |product| product.sugar),
+    ("sodium", "mg", |product| product.sodium),
+    ("saturated fat", "g", |product| product.saturated_fat),
+];
+
+// คำนวณแบบเดียวกับ `NutrientBasis::column_sql` เพื่อให้ค่าตรงกับที่ query เทียบ
+fn on_basis(value: f32, product: &ProductResponse, basis: NutrientBasis) -> f64 {
+    match basis {
+        NutrientBasis::Serving => f64::from(value),
+        NutrientBasis::Per100 => f64::from(value) * 100.0 / f64::from(product.serving_size_grams.unwrap_or(0.0)),
+    }
+}
+
+fn has_serving_size(product: &ProductResponse) -> bool {
+    product.serving_size_grams.is_some_and(|size| size.is_finite() && size > 0.0)
+}
+
+fn not_upf(product: &ProductResponse) -> bool {
+    !product.is_upf && product.nova_group != Some(4)
+}
+
+fn is_preferred(product: &ProductResponse) -> bool {
+    product.is_healthier || not_upf(product)
+}
+
+fn changes(original: &ProductResponse, candidate: &ProductResponse, basis: NutrientBasis) -> Vec<NutrientChange> {
+    LIMITED_NUTRIENTS.iter()
+        .map(|(nutrient, unit, value)| {
+            let before = on_basis(value(original), original, basis) as f32;
+            let after = on_basis(value(candidate), candidate, basis) as f32;
+            NutrientChange {
+                nutrient: nutrient.to_string(),
+                unit: unit.to_string(),
+                original: round_to(before, 2),
+                alternative: round_to(after, 2),
+                percent: (before > 0.0).then(|| round_to((after - before) / before * 100.0, 1)),
+            }
+        })
+        .collect()
+}
+
+// ผลรวม % ที่ลดได้ของสารอาหารที่ลดถึงเกณฑ์
+fn reduction_score(changes: &[NutrientChange]) -> f32 {
+    changes.iter()
+        .filter_map(|change| change.percent)
+        .filter(|percent| *percent <= -MIN_REDUCTION_PERCENT)
+        .fold(0.0, |score, percent| score - percent)
+}
+
+fn reason(original: &ProductResponse, candidate: &ProductResponse, changes: &[NutrientChange], price_difference: Option<f32>) -> String {
+    let mut reductions: Vec<(f32, &str)> = changes.iter()
+        .filter_map(|change| change.percent.map(|percent| (percent, change.nutrient.as_str())))
+        .filter(|(percent, _)| *percent <= -MIN_REDUCTION_PERCENT)
+        .collect();
+    reductions.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+
+    let mut parts: Vec<String> = reductions.into_iter()
+        .map(|(percent, nutrient)| format!("{}% {}", percent.round(), nutrient))
+        .collect();
+    match price_difference {
+        Some(0.0) => parts.push("same price".to_string()),
+        Some(difference) => parts.push(format!("{:+} THB", difference)),
+        None => {}
+    }
+    if candidate.is_healthier && !original.is_healthier {
+        parts.push("Healthier Choice".to_string());
+    }
+    if not_upf(candidate) && !not_upf(original) {
+        parts.push("not ultra-processed".to_string());
+    }
+    parts.join(", ")
+}
+
+#[async_trait]
+pub trait AlternativesServiceTrait: Send + Sync {
+    async fn alternatives_for_product(&self, id: Uuid, query: AlternativesQuery) -> Result<Option<ProductAlternatives>, AppError>;
+}
+
+pub struct AlternativesService {
+    products: Arc<dyn ProductRepositoryTrait + Send + Sync>,
+}
+
+impl AlternativesService {
+    pub fn new(products: Arc<dyn ProductRepositoryTrait + Send + Sync>) -> Self {
+        Self { products }
+    }
+}
+
+#[async_trait]
+impl AlternativesServiceTrait for AlternativesService {
+    async fn alternatives_for_product(&self, id: Uuid, query: AlternativesQuery) -> Result<Option<ProductAlternatives>, AppError> {
+        query.validate()?;
+        let original = match self.products.get_product_by_id(id).await {
+            Ok(product) => product,
+            Err(AppError::NotFound) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        let basis = if has_serving_size(&original) { NutrientBasis::Per100 } else { NutrientBasis::Serving };
+        let price_margin = query.price_margin();
+        let max_price = (original.price > 0.0).then(|| round_to(original.price * (1.0 + price_margin / 100.0), 2));
+        let criteria = AlternativeCriteria {
+            product_id: original.id,
+            basis,
+            sugar: on_basis(original.sugar, &original, basis),
+            sodium: on_basis(original.sodium, &original, basis),
+            saturated_fat: on_basis(original.saturated_fat, &original, basis),
+            max_price,
+        };
+
+        let mut ranked: Vec<(f32, Alternative)> = self.products.get_alternative_candidates(&criteria).await?
+            .into_iter()
+            .map(|candidate| {
+                let changes = changes(&original, &candidate, basis);
+                let price_difference = (original.price > 0.0 && candidate.price > 0.0)
+                    .then(|| round_to(candidate.price - original.price, 2));
+                let alternative = Alternative {
+                    rank: 0,
+                    reason: reason(&original, &candidate, &changes, price_difference),
+                    preferred: is_preferred(&candidate),
+                    price_difference,
+                    changes,
+                    product: candidate,
+                };
+                (reduction_score(&alternative.changes), alternative)
+            })
+            .collect();
+        ranked.sort_by(|(a_score, a), (b_score, b)| {
+            b.preferred.cmp(&a.preferred)
+                .then(b_score.partial_cmp(a_score).unwrap_or(Ordering::Equal))
+                .then(a.product.price.partial_cmp(&b.product.price).unwrap_or(Ordering::Equal))
+                .then(a.product.id.cmp(&b.product.id))
+        });
+
+        let profile = warning_profile(None)?;
+        let items = ranked.into_iter()
+            .take(query.limit())
+            .enumerate()
+            .map(|(index, (_, alternative))| Alternative {
+                rank: index + 1,
+                product: present(alternative.product, None, profile),
+                ..alternative
+            })
+            .collect();
+
+        Ok(Some(ProductAlternatives {
+            product: present(original, None, profile),
+            basis,
+            price_margin,
+            max_price,
+            items,
+        }))
+    }
+}
@@ -0,0 +1,94 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+
+use crate::{
+    errors::AppError,
+    models::categories::{Categories, CategoriesForm, CategoriesWithProductsResponse},
+    repositories::category_repositories::CategoryRepositoryTrait,
+    services::healthier_choice::FoodGroup,
+};
+
+#[async_trait]
+pub trait CategoryServiceTrait: Send + Sync {
+    async fn list_categories(&self) -> Result<Vec<Categories>, AppError>;
+    async fn add_category(&self, category: CategoriesForm) -> Result<Categories, AppError>;
+    async fn rename_category_from_id(&self, id: i32, category: CategoriesForm) -> Result<Option<Categories>, AppError>;
+    async fn delete_category_from_id(&self, id: i32) -> Result<(), AppError>;
+    async fn get_category_with_products(&self, id: i32) -> Result<Option<CategoriesWithProductsResponse>, AppError>;
+}
+
+pub struct CategoryService {
+    repo: Arc<dyn CategoryRepositoryTrait + Send + Sync>,
+}
+
+impl CategoryService {
+    pub fn new(repo: Arc<dyn CategoryRepositoryTrait + Send + Sync>) -> Self {
+        Self { repo }
+    }
+}
+
+// food_group ต้องเป็นกลุ่มที่ rules engine ของ Healthier Choice รู้จัก
+fn normalize_food_group(mut category: CategoriesForm) -> Result<CategoriesForm, AppError> {
+    if let Some(Some(food_group)) = &category.food_group {
+        let food_group = FoodGroup::validate(&food_group.trim().to_lowercase())?;
+        category.food_group = Some(Some(food_group.as_str().to_string()));
+    }
+    Ok(category)
+}
+
+// tag ของ Open Food Facts อยู่ในรูป `<ภาษา>:<ชื่อ>` ตัวพิมพ์เล็ก เช่น `en:instant-noodles`
+fn normalize_off_tags(mut category: CategoriesForm) -> Result<CategoriesForm, AppError> {
+    if let Some(Some(tags)) = &category.off_tags {
+        let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+        for tag in tags {
+            let tag = tag.trim().to_lowercase();
+            if !tag.split_once(':').is_some_and(|(language, name)| !language.is_empty() && !name.is_empty()) {
+                return Err(AppError::ValidationError(format!(
+                    "off_tags must look like \"en:snacks\", got {:?}", tag
+                )));
+            }
+            if !normalized.contains(&tag) {
+                normalized.push(tag);
+            }
+        }
+        category.off_tags = Some(Some(normalized));
+    }
+    Ok(category)
+}
+
+#[async_trait]
+impl CategoryServiceTrait for CategoryService {
+    async fn list_categories(&self) -> Result<Vec<Categories>, AppError> {
+        self.repo.get_category_list().await
+    }
+
+    async fn add_category(&self, category: CategoriesForm) -> Result<Categories, AppError> {
+        let category = normalize_off_tags(normalize_food_group(category)?)?;
+        self.repo.create_category(category).await
+    }
+
+    async fn rename_category_from_id(&self, id: i32, category: CategoriesForm) -> Result<Option<Categories>, AppError> {
+        let category = normalize_off_tags(normalize_food_group(category)?)?;
+        match self.repo.rename_category_by_id(id, category).await {
+            Ok(category) => Ok(Some(category)),
+            Err(AppError::NotFound) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn delete_category_from_id(&self, id: i32) -> Result<(), AppError> {
+        let affected_rows = self.repo.delete_category_by_id(id).await?;
+        if affected_rows == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+
+    async fn get_category_with_products(&self, id: i32) -> Result<Option<CategoriesWithProductsResponse>, AppError> {
+        match self.repo.get_category_with_products(id).await {
+            Ok(response) => Ok(Some(response)),
+            Err(AppError::NotFound) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
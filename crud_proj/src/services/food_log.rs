@@ -0,0 +1,265 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use chrono::{DateTime, Days, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use uuid::Uuid;
+
+use crate::{
+    errors::{AppError, FieldError},
+    models::{
+        food_log::{
+            DailySpending, DailySummary, FoodLogDateQuery, FoodLogEntry, FoodLogEntryForm, FoodLogEntryResponse,
+            MealSlot, MealSlotSummary, ProgressStatus, QuantityUnit, TargetKind, TargetProgress,
+            ON_TRACK_MAX_PERCENT, ON_TRACK_MIN_PERCENT,
+        },
+        nutrition::{round_to, NutrientAmounts},
+        user_profiles::{DailyTargets, UserProfile},
+    },
+    repositories::{
+        food_log_repositories::FoodLogRepositoryTrait, product_repositories::ProductRepositoryTrait,
+        user_profile_repositories::UserProfileRepositoryTrait,
+    },
+    services::user_profile_service,
+};
+/*
+บันทึกอาหารและสรุปรายวันเทียบเป้าหมายของ user profile
+ - ขอบวัน = เที่ยงคืนตามเขตเวลาของ profile ถึงเที่ยงคืนถัดไป (แปลงเป็น UTC ก่อน query)
+ - ค่าสารอาหาร = ค่าต่อ serving ของ product × จำนวน serving, ราคา = `price` × จำนวน serving
+ - เป้าหมายแบบ `goal` ตรงเป้าเมื่ออยู่ในช่วง 90–110%, แบบ `limit` เกินเมื่อมากกว่า 100%
+*/
+
+// `Iterator::sum` ของ float ว่างได้ -0.0
+fn total(values: impl Iterator<Item = f32>) -> f32 {
+    values.fold(0.0, |total, value| total + value)
+}
+
+fn present(entry: FoodLogEntry, tz: Tz) -> FoodLogEntryResponse {
+    let servings = entry.quantity_unit.servings(entry.quantity, entry.product.serving_size_grams);
+    let eaten_at = entry.eaten_at.with_timezone(&tz);
+    FoodLogEntryResponse {
+        id: entry.id,
+        profile_id: entry.profile_id,
+        product_id: entry.product_id,
+        brand: entry.product.brand.clone(),
+        quantity: entry.quantity,
+        quantity_unit: entry.quantity_unit,
+        meal_slot: entry.meal_slot,
+        eaten_at: eaten_at.fixed_offset(),
+        date: eaten_at.date_naive(),
+        servings: servings.map(|servings| round_to(servings, 2)),
+        cost: servings.map(|servings| round_to(entry.product.price * servings, 2)),
+        nutrients: servings.map(|servings| entry.product.nutrient_amounts().scaled(servings)),
+        product_name: entry.product.name,
+    }
+}
+
+fn today(tz: Tz) -> NaiveDate {
+    Utc::now().with_timezone(&tz).date_naive()
+}
+
+// เที่ยงคืนตามเวลาท้องถิ่น; ถ้าเที่ยงคืนไม่มีอยู่จริง (เลื่อนเวลา DST) ใช้เวลาเดียวกันใน UTC
+fn local_midnight(tz: Tz, date: NaiveDate) -> DateTime<Utc> {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+    tz.from_local_datetime(&midnight)
+        .earliest()
+        .map(|start| start.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&midnight))
+}
+
+fn day_bounds(tz: Tz, date: NaiveDate) -> Result<(DateTime<Utc>, DateTime<Utc>), AppError> {
+    let next = date.checked_add_days(Days::new(1))
+        .ok_or_else(|| AppError::ValidationError(format!("date {} is out of range", date)))?;
+    Ok((local_midnight(tz, date), local_midnight(tz, next)))
+}
+
+fn progress(nutrient: &str, unit: &str, kind: TargetKind, intake: f32, target: f32) -> TargetProgress {
+    let percent = if target > 0.0 { round_to(intake / target * 100.0, 1) } else { 0.0 };
+    let status = match kind {
+        TargetKind::Goal if percent < ON_TRACK_MIN_PERCENT => ProgressStatus::Under,
+        TargetKind::Goal if percent > ON_TRACK_MAX_PERCENT => ProgressStatus::Over,
+        TargetKind::Limit if intake > target => ProgressStatus::Over,
+        _ => ProgressStatus::OnTrack,
+    };
+    TargetProgress {
+        nutrient: nutrient.to_string(),
+        unit: unit.to_string(),
+        kind,
+        intake: round_to(intake, 1),
+        target,
+        percent,
+        status,
+    }
+}
+
+fn target_progress(totals: &NutrientAmounts, targets: &DailyTargets) -> Vec<TargetProgress> {
+    vec![
+        progress("energy", "kcal", TargetKind::Goal, totals.calories, targets.energy_kcal),
+        progress("protein", "g", TargetKind::Goal, totals.protein, targets.protein_g),
+        progress("carbs", "g", TargetKind::Goal, totals.carbs, targets.carbs_g),
+        progress("fat", "g", TargetKind::Goal, totals.fat, targets.fat_g),
+        progress("sugar", "g", TargetKind::Limit, totals.sugar, targets.sugar_g),
+        progress("sodium", "mg", TargetKind::Limit, totals.sodium, targets.sodium_mg),
+    ]
+}
+
+fn summarize(profile: &UserProfile, date: NaiveDate, entries: Vec<FoodLogEntryResponse>) -> Result<DailySummary, AppError> {
+    // วันที่ไม่ได้กินอะไรเลยรู้ค่าสารอาหารทุกตัว (เป็น 0)
+    let totals = entries.iter()
+        .filter_map(|entry| entry.nutrients.as_ref())
+        .fold(NutrientAmounts::zero(), |totals, nutrients| totals.plus(nutrients))
+        .scaled(1.0);
+    let spent = round_to(total(entries.iter().filter_map(|entry| entry.cost)), 2);
+    let by_meal = MealSlot::ALL.into_iter()
+        .map(|meal_slot| {
+            let slot: Vec<&FoodLogEntryResponse> = entries.iter().filter(|entry| entry.meal_slot == meal_slot).collect();
+            MealSlotSummary {
+                meal_slot,
+                entries: slot.len(),
+                calories: round_to(total(slot.iter().filter_map(|entry| entry.nutrients.as_ref()).map(|nutrients| nutrients.calories)), 1),
+                spent: round_to(total(slot.iter().filter_map(|entry| entry.cost)), 2),
+            }
+        })
+        .collect();
+    let targets = user_profile_service::daily_targets(profile)?;
+
+    Ok(DailySummary {
+        profile_id: profile.id,
+        date,
+        timezone: profile.timezone.clone(),
+        entries: entries.len(),
+        unconverted_entries: entries.iter().filter(|entry| entry.servings.is_none()).count(),
+        progress: target_progress(&totals, &targets),
+        totals,
+        targets,
+        by_meal,
+        spending: DailySpending {
+            spent,
+            daily_budget: profile.daily_budget,
+            remaining: profile.daily_budget.map(|budget| round_to(budget - spent, 2)),
+            over_budget: profile.daily_budget.is_some_and(|budget| spent > budget),
+        },
+    })
+}
+
+#[async_trait]
+pub trait FoodLogServiceTrait: Send + Sync {
+    async fn list_food_log(&self, profile_id: Uuid, query: FoodLogDateQuery) -> Result<Option<Vec<FoodLogEntryResponse>>, AppError>;
+    async fn add_food_log_entry(&self, profile_id: Uuid, entry: FoodLogEntryForm) -> Result<Option<FoodLogEntryResponse>, AppError>;
+    async fn get_food_log_entry_from_id(&self, profile_id: Uuid, id: Uuid) -> Result<Option<FoodLogEntryResponse>, AppError>;
+    async fn update_food_log_entry_from_id(&self, profile_id: Uuid, id: Uuid, entry: FoodLogEntryForm) -> Result<Option<FoodLogEntryResponse>, AppError>;
+    async fn delete_food_log_entry_from_id(&self, profile_id: Uuid, id: Uuid) -> Result<(), AppError>;
+    async fn daily_summary(&self, profile_id: Uuid, query: FoodLogDateQuery) -> Result<Option<DailySummary>, AppError>;
+}
+
+pub struct FoodLogService {
+    entries: Arc<dyn FoodLogRepositoryTrait + Send + Sync>,
+    profiles: Arc<dyn UserProfileRepositoryTrait + Send + Sync>,
+    products: Arc<dyn ProductRepositoryTrait + Send + Sync>,
+}
+
+impl FoodLogService {
+    pub fn new(
+        entries: Arc<dyn FoodLogRepositoryTrait + Send + Sync>,
+        profiles: Arc<dyn UserProfileRepositoryTrait + Send + Sync>,
+        products: Arc<dyn ProductRepositoryTrait + Send + Sync>,
+    ) -> Self {
+        Self { entries, profiles, products }
+    }
+
+    async fn profile(&self, id: Uuid) -> Result<Option<UserProfile>, AppError> {
+        match self.profiles.get_user_profile_by_id(id).await {
+            Ok(profile) => Ok(Some(profile)),
+            Err(AppError::NotFound) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    // product ต้องมีอยู่จริง และถ้าบันทึกเป็นกรัมต้องมี serving size ให้แปลงเป็น serving
+    async fn validate_entry(&self, entry: &FoodLogEntryForm) -> Result<(), AppError> {
+        entry.validate()?;
+        let product = match self.products.get_product_by_id(entry.product_id).await {
+            Ok(product) => product,
+            Err(AppError::NotFound) => {
+                return Err(AppError::InvalidFields(vec![FieldError::new("product_id", "unknown product")]));
+            }
+            Err(e) => return Err(e),
+        };
+        if entry.quantity_unit == QuantityUnit::Grams && !product.serving_size_grams.is_some_and(|size| size > 0.0) {
+            return Err(AppError::InvalidFields(vec![FieldError::new(
+                "quantity_unit",
+                "grams needs a product with a serving size; log servings instead",
+            )]));
+        }
+        Ok(())
+    }
+
+    async fn entries_of_day(&self, profile: &UserProfile, query: &FoodLogDateQuery) -> Result<(NaiveDate, Vec<FoodLogEntryResponse>), AppError> {
+        let tz = profile.tz();
+        let date = query.date.unwrap_or_else(|| today(tz));
+        let (from, to) = day_bounds(tz, date)?;
+        let entries = self.entries.get_food_log_entries(profile.id, from, to).await?
+            .into_iter()
+            .map(|entry| present(entry, tz))
+            .collect();
+        Ok((date, entries))
+    }
+}
+
+#[async_trait]
+impl FoodLogServiceTrait for FoodLogService {
+    async fn list_food_log(&self, profile_id: Uuid, query: FoodLogDateQuery) -> Result<Option<Vec<FoodLogEntryResponse>>, AppError> {
+        let Some(profile) = self.profile(profile_id).await? else {
+            return Ok(None);
+        };
+        let (_, entries) = self.entries_of_day(&profile, &query).await?;
+        Ok(Some(entries))
+    }
+
+    async fn add_food_log_entry(&self, profile_id: Uuid, entry: FoodLogEntryForm) -> Result<Option<FoodLogEntryResponse>, AppError> {
+        let Some(profile) = self.profile(profile_id).await? else {
+            return Ok(None);
+        };
+        self.validate_entry(&entry).await?;
+        let entry = self.entries.create_food_log_entry(profile_id, entry).await?;
+        Ok(Some(present(entry, profile.tz())))
+    }
+
+    async fn get_food_log_entry_from_id(&self, profile_id: Uuid, id: Uuid) -> Result<Option<FoodLogEntryResponse>, AppError> {
+        let Some(profile) = self.profile(profile_id).await? else {
+            return Ok(None);
+        };
+        match self.entries.get_food_log_entry_by_id(profile_id, id).await {
+            Ok(entry) => Ok(Some(present(entry, profile.tz()))),
+            Err(AppError::NotFound) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn update_food_log_entry_from_id(&self, profile_id: Uuid, id: Uuid, entry: FoodLogEntryForm) -> Result<Option<FoodLogEntryResponse>, AppError> {
+        let Some(profile) = self.profile(profile_id).await? else {
+            return Ok(None);
+        };
+        self.validate_entry(&entry).await?;
+        match self.entries.update_food_log_entry_by_id(profile_id, id, entry).await {
+            Ok(entry) => Ok(Some(present(entry, profile.tz()))),
+            Err(AppError::NotFound) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn delete_food_log_entry_from_id(&self, profile_id: Uuid, id: Uuid) -> Result<(), AppError> {
+        let affected_rows = self.entries.delete_food_log_entry_by_id(profile_id, id).await?;
+        if affected_rows == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+
+    async fn daily_summary(&self, profile_id: Uuid, query: FoodLogDateQuery) -> Result<Option<DailySummary>, AppError> {
+        let Some(profile) = self.profile(profile_id).await? else {
+            return Ok(None);
+        };
+        let (date, entries) = self.entries_of_day(&profile, &query).await?;
+        summarize(&profile, date, entries).map(Some)
+    }
+}
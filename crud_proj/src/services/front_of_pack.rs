@@ -0,0 +1,59 @@
+use crate::models::{
+    front_of_pack::{FrontOfPack, HighInWarning, LightColour, TrafficLight, WarningProfile, TRAFFIC_LIGHT_BANDS},
+    nutrition::{NutrientBasis, NutritionFacts},
+};
+/*
+คำนวณ traffic lights และ "HIGH IN" warnings ของ product จาก `NutritionFacts`
+ - เกณฑ์และ profile อยู่ใน `models::front_of_pack` เพราะ filter ของ product list ใช้ชุดเดียวกัน
+*/
+
+// สีและคำเตือนใช้ค่าที่ยังไม่ปัด (`LabelNutrient::exact_amount`) ให้ตรงกับ filter
+// `no_red_lights`/`no_high_in` ของ product list; ปัดเฉพาะค่าที่แสดง
+pub fn traffic_lights(nutrition: &NutritionFacts) -> Option<Vec<TrafficLight>> {
+    TRAFFIC_LIGHT_BANDS.iter()
+        .map(|bands| {
+            let amount = bands.nutrient.exact_amount(nutrition, NutrientBasis::Per100)?;
+            let colour = if amount <= f64::from(bands.green_max.for_unit(nutrition.serving_unit)) {
+                LightColour::Green
+            } else if amount > f64::from(bands.red_above.for_unit(nutrition.serving_unit)) {
+                LightColour::Red
+            } else {
+                LightColour::Amber
+            };
+            Some(TrafficLight {
+                nutrient: bands.nutrient.as_str().to_string(),
+                colour,
+                per_100: ((amount * 100.0).round() / 100.0) as f32,
+                unit: bands.nutrient.unit().to_string(),
+            })
+        })
+        .collect()
+}
+
+pub fn assess(nutrition: &NutritionFacts, profile: &WarningProfile) -> FrontOfPack {
+    let mut high_in = Vec::new();
+    let mut unevaluated = Vec::new();
+    for threshold in profile.thresholds {
+        let Some(amount) = threshold.nutrient.exact_amount(nutrition, threshold.basis) else {
+            unevaluated.push(threshold.nutrient.as_str().to_string());
+            continue;
+        };
+        let limit = threshold.limit.for_unit(nutrition.serving_unit);
+        if amount > f64::from(limit) {
+            high_in.push(HighInWarning {
+                nutrient: threshold.nutrient.as_str().to_string(),
+                amount: ((amount * 100.0).round() / 100.0) as f32,
+                limit,
+                unit: threshold.nutrient.unit().to_string(),
+                basis: threshold.basis,
+            });
+        }
+    }
+
+    FrontOfPack {
+        traffic_lights: traffic_lights(nutrition),
+        warning_profile: profile.name.to_string(),
+        high_in,
+        unevaluated,
+    }
+}
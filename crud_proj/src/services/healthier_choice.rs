@@ -0,0 +1,309 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    errors::AppError,
+    models::{
+        healthier_choice::{HealthierChoiceReport, HealthierChoiceResult, HealthierChoiceStatus, ProductWithFoodGroups, ThresholdCheck},
+        nutrition::{NutrientAmounts, NutrientBasis, NutritionFacts},
+    },
+    repositories::product_repositories::ProductRepositoryTrait,
+};
+/*
+Rules engine ของสัญลักษณ์ทางเลือกสุขภาพ (http://healthierlogo.com/)
+ - เกณฑ์ประกาศแยกตามกลุ่มอาหาร: ค่าสูงสุดของน้ำตาล, โซเดียม, ไขมัน และไขมันอิ่มตัว
+   ต่อหนึ่งหน่วยบริโภคหรือต่อ 100 g/ml
+ - กลุ่มอาหารของ product มาจาก `categories.food_group`; ถ้า product อยู่หลายกลุ่มต้องผ่านเกณฑ์ทุกกลุ่ม
+ - เกณฑ์แต่ละชุดมี version เมื่อประกาศเกณฑ์ใหม่ให้เพิ่ม `CriteriaSet` ใหม่ใน `CRITERIA_SETS`
+   แทนการแก้ชุดเดิม เพื่อให้ประเมินย้อนหลังตาม version เดิมได้
+*/
+
+pub const CURRENT_CRITERIA_VERSION: &str = "2022";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FoodGroup {
+    Beverages,
+    Dairy,
+    InstantNoodles,
+    Snacks,
+    SaucesCondiments,
+    ReadyMeals,
+}
+
+impl FoodGroup {
+    pub const ALL: [FoodGroup; 6] = [
+        FoodGroup::Beverages,
+        FoodGroup::Dairy,
+        FoodGroup::InstantNoodles,
+        FoodGroup::Snacks,
+        FoodGroup::SaucesCondiments,
+        FoodGroup::ReadyMeals,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FoodGroup::Beverages => "beverages",
+            FoodGroup::Dairy => "dairy",
+            FoodGroup::InstantNoodles => "instant_noodles",
+            FoodGroup::Snacks => "snacks",
+            FoodGroup::SaucesCondiments => "sauces_condiments",
+            FoodGroup::ReadyMeals => "ready_meals",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<FoodGroup> {
+        FoodGroup::ALL.into_iter().find(|group| group.as_str() == name)
+    }
+
+    /// Validates a `food_group` coming from a category write.
+    pub fn validate(name: &str) -> Result<FoodGroup, AppError> {
+        FoodGroup::parse(name).ok_or_else(|| {
+            let allowed: Vec<&str> = FoodGroup::ALL.iter().map(FoodGroup::as_str).collect();
+            AppError::ValidationError(format!(
+                "Unknown food_group {:?}; allowed: {}", name, allowed.join(", ")
+            ))
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitedNutrient {
+    Sugar,
+    Sodium,
+    Fat,
+    SaturatedFat,
+}
+
+impl LimitedNutrient {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LimitedNutrient::Sugar => "sugar",
+            LimitedNutrient::Sodium => "sodium",
+            LimitedNutrient::Fat => "fat",
+            LimitedNutrient::SaturatedFat => "saturated_fat",
+        }
+    }
+
+    pub fn unit(&self) -> &'static str {
+        match self {
+            LimitedNutrient::Sodium => "mg",
+            _ => "g",
+        }
+    }
+
+    pub fn amount(&self, amounts: &NutrientAmounts) -> f32 {
+        match self {
+            LimitedNutrient::Sugar => amounts.sugar,
+            LimitedNutrient::Sodium => amounts.sodium,
+            LimitedNutrient::Fat => amounts.fat,
+            LimitedNutrient::SaturatedFat => amounts.saturated_fat,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Threshold {
+    pub nutrient: LimitedNutrient,
+    pub max: f32,
+    pub basis: NutrientBasis,
+}
+
+#[derive(Debug)]
+pub struct GroupCriteria {
+    pub food_group: FoodGroup,
+    pub thresholds: &'static [Threshold],
+}
+
+#[derive(Debug)]
+pub struct CriteriaSet {
+    pub version: &'static str,
+    pub groups: &'static [GroupCriteria],
+}
+
+impl CriteriaSet {
+    fn for_group(&self, food_group: FoodGroup) -> Option<&GroupCriteria> {
+        self.groups.iter().find(|criteria| criteria.food_group == food_group)
+    }
+}
+
+const fn max_per_100(nutrient: LimitedNutrient, max: f32) -> Threshold {
+    Threshold { nutrient, max, basis: NutrientBasis::Per100 }
+}
+
+const fn max_per_serving(nutrient: LimitedNutrient, max: f32) -> Threshold {
+    Threshold { nutrient, max, basis: NutrientBasis::Serving }
+}
+
+pub static CRITERIA_SETS: &[CriteriaSet] = &[
+    CriteriaSet {
+        version: "2022",
+        groups: &[
+            GroupCriteria {
+                food_group: FoodGroup::Beverages,
+                thresholds: &[max_per_100(LimitedNutrient::Sugar, 6.0)],
+            },
+            GroupCriteria {
+                food_group: FoodGroup::Dairy,
+                thresholds: &[
+                    max_per_100(LimitedNutrient::Sugar, 6.0),
+                    max_per_100(LimitedNutrient::Fat, 3.5),
+                    max_per_100(LimitedNutrient::SaturatedFat, 2.0),
+                ],
+            },
+            GroupCriteria {
+                food_group: FoodGroup::InstantNoodles,
+                thresholds: &[
+                    max_per_serving(LimitedNutrient::Fat, 11.0),
+                    max_per_serving(LimitedNutrient::SaturatedFat, 5.0),
+                    max_per_serving(LimitedNutrient::Sodium, 1000.0),
+                ],
+            },
+            GroupCriteria {
+                food_group: FoodGroup::Snacks,
+                thresholds: &[
+                    max_per_serving(LimitedNutrient::Fat, 8.0),
+                    max_per_serving(LimitedNutrient::SaturatedFat, 2.5),
+                    max_per_serving(LimitedNutrient::Sugar, 8.0),
+                    max_per_serving(LimitedNutrient::Sodium, 200.0),
+                ],
+            },
+            GroupCriteria {
+                food_group: FoodGroup::SaucesCondiments,
+                thresholds: &[
+                    max_per_100(LimitedNutrient::Sodium, 3000.0),
+                    max_per_100(LimitedNutrient::Sugar, 20.0),
+                ],
+            },
+            GroupCriteria {
+                food_group: FoodGroup::ReadyMeals,
+                thresholds: &[
+                    max_per_serving(LimitedNutrient::Fat, 13.0),
+                    max_per_serving(LimitedNutrient::SaturatedFat, 4.0),
+                    max_per_serving(LimitedNutrient::Sodium, 600.0),
+                ],
+            },
+        ],
+    },
+];
+
+pub fn criteria(version: Option<&str>) -> Result<&'static CriteriaSet, AppError> {
+    let version = version.map(str::trim).filter(|version| !version.is_empty()).unwrap_or(CURRENT_CRITERIA_VERSION);
+    CRITERIA_SETS.iter()
+        .find(|set| set.version == version)
+        .ok_or_else(|| {
+            let known: Vec<&str> = CRITERIA_SETS.iter().map(|set| set.version).collect();
+            AppError::ValidationError(format!(
+                "Unknown criteria version {:?}; known: {}", version, known.join(", ")
+            ))
+        })
+}
+
+/// Checks a product against the criteria of every food group of its categories.
+pub fn evaluate(entry: &ProductWithFoodGroups, criteria: &CriteriaSet) -> HealthierChoiceResult {
+    let product = &entry.product;
+    let nutrition = NutritionFacts::new(product.nutrient_amounts(), product.serving_size_grams, product.serving_unit);
+
+    let mut applicable = 0;
+    let mut failed = Vec::new();
+    let mut unevaluated = Vec::new();
+    for group in entry.food_groups.iter().filter_map(|name| FoodGroup::parse(name)) {
+        let Some(group_criteria) = criteria.for_group(group) else {
+            continue;
+        };
+        applicable += 1;
+
+        for threshold in group_criteria.thresholds {
+            let amounts = match threshold.basis {
+                NutrientBasis::Serving => Some(&nutrition.per_serving),
+                NutrientBasis::Per100 => nutrition.per_100.as_ref(),
+            };
+            let actual = amounts.map(|amounts| threshold.nutrient.amount(amounts));
+            let check = ThresholdCheck {
+                food_group: group.as_str().to_string(),
+                nutrient: threshold.nutrient.as_str().to_string(),
+                max: threshold.max,
+                unit: threshold.nutrient.unit().to_string(),
+                basis: threshold.basis,
+                actual,
+            };
+            match actual {
+                Some(actual) if actual > threshold.max => failed.push(check),
+                Some(_) => {}
+                None => unevaluated.push(check),
+            }
+        }
+    }
+
+    let status = if applicable == 0 {
+        HealthierChoiceStatus::NotApplicable
+    } else if !failed.is_empty() {
+        HealthierChoiceStatus::Fail
+    } else if !unevaluated.is_empty() {
+        HealthierChoiceStatus::InsufficientData
+    } else {
+        HealthierChoiceStatus::Pass
+    };
+
+    HealthierChoiceResult {
+        product_id: product.id,
+        product_name: product.name.clone(),
+        criteria_version: criteria.version.to_string(),
+        food_groups: entry.food_groups.clone(),
+        status,
+        eligible: status == HealthierChoiceStatus::Pass,
+        stored_is_healthier: product.is_healthier,
+        failed,
+        unevaluated,
+    }
+}
+
+#[async_trait]
+pub trait HealthierChoiceServiceTrait: Send + Sync {
+    async fn evaluate_product(&self, id: Uuid, version: Option<String>) -> Result<Option<HealthierChoiceResult>, AppError>;
+    async fn mismatch_report(&self, version: Option<String>) -> Result<HealthierChoiceReport, AppError>;
+}
+
+pub struct HealthierChoiceService {
+    repo: Arc<dyn ProductRepositoryTrait + Send + Sync>,
+}
+
+impl HealthierChoiceService {
+    pub fn new(repo: Arc<dyn ProductRepositoryTrait + Send + Sync>) -> Self {
+        Self { repo }
+    }
+}
+
+#[async_trait]
+impl HealthierChoiceServiceTrait for HealthierChoiceService {
+    async fn evaluate_product(&self, id: Uuid, version: Option<String>) -> Result<Option<HealthierChoiceResult>, AppError> {
+        let criteria = criteria(version.as_deref())?;
+        match self.repo.get_product_with_food_groups(id).await {
+            Ok(entry) => Ok(Some(evaluate(&entry, criteria))),
+            Err(AppError::NotFound) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn mismatch_report(&self, version: Option<String>) -> Result<HealthierChoiceReport, AppError> {
+        let criteria = criteria(version.as_deref())?;
+        let entries = self.repo.get_products_with_food_groups().await?;
+
+        // เทียบกับ flag เฉพาะผลที่ตัดสินได้ (pass/fail); ที่เหลือแยกเป็นรายการที่ตรวจสอบไม่ได้
+        let (decided, unverifiable): (Vec<HealthierChoiceResult>, Vec<HealthierChoiceResult>) = entries.iter()
+            .map(|entry| evaluate(entry, criteria))
+            .partition(|result| matches!(result.status, HealthierChoiceStatus::Pass | HealthierChoiceStatus::Fail));
+        let mismatches: Vec<HealthierChoiceResult> = decided.into_iter()
+            .filter(|result| result.eligible != result.stored_is_healthier)
+            .collect();
+
+        Ok(HealthierChoiceReport {
+            criteria_version: criteria.version.to_string(),
+            checked: entries.len(),
+            mismatch_count: mismatches.len(),
+            mismatches,
+            unverifiable_count: unverifiable.len(),
+            unverifiable,
+        })
+    }
+}
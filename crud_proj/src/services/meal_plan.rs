@@ -0,0 +1,561 @@
+use std::{sync::Arc, time::{Duration, Instant}};
+use async_trait::async_trait;
+use microlp::{ComparisonOp, LinearExpr, OptimizationDirection, Problem};
+use tracing::info;
+
+use crate::{
+    errors::{AppError, FieldError},
+    models::{
+        meal_plan::{MealPlan, MealPlanItem, MealPlanRequest, PlanConstraint, PlanObjective, PlanRelaxation, HEALTH_POINTS_CEILING},
+        nutrition::NutrientAmounts,
+        products::ProductResponse,
+        user_profiles::DailyTargets,
+    },
+    repositories::{product_repositories::ProductRepositoryTrait, user_profile_repositories::UserProfileRepositoryTrait},
+    services::user_profile_service,
+};
+/*
+จัดอาหารรายวันภายใต้งบประมาณด้วย mixed-integer linear programming (microlp)
+ - ตัวแปร: จำนวน serving ของแต่ละ product (จำนวนเต็ม 0..=`max_servings_per_product`)
+ - เงื่อนไข: ราคารวม <= งบ, ช่วงพลังงาน, โปรตีนขั้นต่ำ, โซเดียม/น้ำตาล/ไขมันอิ่มตัวสูงสุด
+ - `min_cost`: ราคารวมต่ำสุด / `max_health`: คะแนนสุขภาพรวมสูงสุด (20 − คะแนน Nutri-Score ต่อ serving,
+   product ที่ไม่มี Nutri-Score ได้ 0) โดยเลือกตัวที่ถูกกว่าเมื่อคะแนนเท่ากัน
+ - ตัด product ที่ถูกข่ม (dominated) ออกก่อนแก้: มี product อื่นที่ดีกว่าหรือเท่ากันทุกด้าน และถ้าใช้ตัวที่ข่มครบ
+   `max_servings_per_product` ทุกตัวแล้วไม่เหลืองบพอซื้อตัวนี้ ตัดออกแล้วคำตอบที่ดีที่สุดไม่เปลี่ยน
+ - เงื่อนไขที่ "binding" คือเงื่อนไขที่คำตอบของ LP relaxation (ไม่บังคับจำนวนเต็ม) ชนขอบพอดี (slack = 0)
+ - ถ้าไม่มีคำตอบ จะหาค่าที่เงื่อนไขแต่ละข้อต้องผ่อนลง (ทีละข้อ) เพื่อให้มีคำตอบ
+   โดยแก้ LP relaxation ก่อน และแก้ MILP เฉพาะเมื่อ LP ยังมีคำตอบ
+ - ทุกการแก้ตรวจ deadline ก่อน เกินเวลาตอบ 503
+*/
+
+const COST_TIE_BREAK: f64 = 1e-3;
+const IMPROVEMENT_TOLERANCE: f64 = 1e-6;
+// slack ที่ถือว่าชนขอบ เทียบกับขนาดของ limit
+const SLACK_TOLERANCE: f64 = 1e-6;
+
+/// Time the optimizer gets per request, including the relaxation suggestions.
+pub const OPTIMIZER_DEADLINE: Duration = Duration::from_secs(5);
+const OUT_OF_TIME: &str = "Meal plan optimizer ran out of time; try fewer limits or exclude more products";
+
+// ช่วงพลังงานรอบเป้าหมายของ user profile
+const PROFILE_CALORIE_MIN_RATIO: f32 = 0.9;
+const PROFILE_CALORIE_MAX_RATIO: f32 = 1.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bound {
+    Min,
+    Max,
+}
+
+#[derive(Debug, Clone)]
+struct LinearConstraint {
+    name: &'static str,
+    unit: &'static str,
+    bound: Bound,
+    limit: f32,
+    coefficients: Vec<f64>,
+}
+
+impl LinearConstraint {
+    fn new(
+        name: &'static str,
+        unit: &'static str,
+        bound: Bound,
+        limit: f32,
+        candidates: &[ProductResponse],
+        amount: impl Fn(&ProductResponse) -> f32,
+    ) -> Self {
+        let coefficients = candidates.iter().map(|product| amount(product) as f64).collect();
+        LinearConstraint { name, unit, bound, limit, coefficients }
+    }
+
+    fn total(&self, servings: &[f64]) -> f64 {
+        self.coefficients.iter().zip(servings).map(|(amount, servings)| amount * servings).sum()
+    }
+
+    fn holds(&self, servings: &[f64]) -> bool {
+        let total = self.total(servings);
+        let limit = self.limit as f64;
+        match self.bound {
+            Bound::Min => total >= limit - IMPROVEMENT_TOLERANCE,
+            Bound::Max => total <= limit + IMPROVEMENT_TOLERANCE,
+        }
+    }
+
+    fn is_tight(&self, servings: &[f64]) -> bool {
+        let limit = self.limit as f64;
+        (self.total(servings) - limit).abs() <= SLACK_TOLERANCE * limit.abs().max(1.0)
+    }
+
+    // ค่าต่อ serving ของ `a` ดีกว่าหรือเท่ากับของ `b` สำหรับเงื่อนไขนี้
+    fn prefers(&self, a: usize, b: usize) -> bool {
+        match self.bound {
+            Bound::Min => self.coefficients[a] >= self.coefficients[b],
+            Bound::Max => self.coefficients[a] <= self.coefficients[b],
+        }
+    }
+
+    fn restricted_to(&self, kept: &[usize]) -> Self {
+        LinearConstraint { coefficients: kept.iter().map(|index| self.coefficients[*index]).collect(), ..self.clone() }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Objective {
+    direction: OptimizationDirection,
+    coefficients: Vec<f64>,
+}
+
+impl Objective {
+    fn prefers(&self, a: usize, b: usize) -> bool {
+        match self.direction {
+            OptimizationDirection::Minimize => self.coefficients[a] <= self.coefficients[b],
+            OptimizationDirection::Maximize => self.coefficients[a] >= self.coefficients[b],
+        }
+    }
+
+    fn restricted_to(&self, kept: &[usize]) -> Self {
+        Objective {
+            direction: self.direction,
+            coefficients: kept.iter().map(|index| self.coefficients[*index]).collect(),
+        }
+    }
+}
+
+/// Indices of the candidates worth solving for, in their original order.
+///
+/// `dominated` is dropped when another kept candidate is at least as good on the objective and every
+/// constraint and filling all such candidates to `max_servings` would leave no budget for it: some
+/// optimal plan then never buys it. `constraints[0]` must be the budget.
+fn undominated(objective: &Objective, constraints: &[LinearConstraint], max_servings: i32) -> Vec<usize> {
+    let budget = &constraints[0];
+    let count = objective.coefficients.len();
+    let mut kept = vec![true; count];
+    for dominated in 0..count {
+        let dominators_cost: f64 = (0..count)
+            .filter(|other| *other != dominated && kept[*other])
+            .filter(|other| {
+                objective.prefers(*other, dominated)
+                    && constraints.iter().all(|constraint| constraint.prefers(*other, dominated))
+            })
+            .map(|other| budget.coefficients[other] * max_servings as f64)
+            .sum();
+        if dominators_cost + budget.coefficients[dominated] > budget.limit as f64 + IMPROVEMENT_TOLERANCE {
+            kept[dominated] = false;
+        }
+    }
+    (0..count).filter(|index| kept[*index]).collect()
+}
+
+fn check_deadline(deadline: Instant) -> Result<(), AppError> {
+    if Instant::now() >= deadline {
+        return Err(AppError::ServiceUnavailable(OUT_OF_TIME.to_string()));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Servings {
+    /// Whole servings (the plan itself).
+    Integer,
+    /// Fractional servings: the LP relaxation, cheap to solve and a bound on the integer optimum.
+    Relaxed,
+}
+
+/// Optimum value and servings per candidate, or `None` when the constraints can't all hold.
+fn solve(
+    objective: &Objective,
+    constraints: &[&LinearConstraint],
+    max_servings: i32,
+    servings: Servings,
+    deadline: Instant,
+) -> Result<Option<(f64, Vec<f64>)>, AppError> {
+    check_deadline(deadline)?;
+    // ไม่มี product ให้เลือก: คำตอบเดียวคือไม่ซื้ออะไรเลย
+    if objective.coefficients.is_empty() {
+        let feasible = constraints.iter().all(|constraint| constraint.holds(&[]));
+        return Ok(feasible.then(|| (0.0, Vec::new())));
+    }
+
+    let mut problem = Problem::new(objective.direction);
+    let variables: Vec<_> = objective.coefficients.iter()
+        .map(|coefficient| match servings {
+            Servings::Integer => problem.add_integer_var(*coefficient, (0, max_servings)),
+            Servings::Relaxed => problem.add_var(*coefficient, (0.0, max_servings as f64)),
+        })
+        .collect();
+    for constraint in constraints {
+        let expression: LinearExpr = variables.iter()
+            .zip(&constraint.coefficients)
+            .filter(|(_, coefficient)| **coefficient != 0.0)
+            .map(|(variable, coefficient)| (*variable, *coefficient))
+            .collect();
+        let operator = match constraint.bound {
+            Bound::Min => ComparisonOp::Ge,
+            Bound::Max => ComparisonOp::Le,
+        };
+        problem.add_constraint(expression, operator, constraint.limit as f64);
+    }
+
+    match problem.solve() {
+        Ok(solution) => {
+            let values = variables.iter()
+                .map(|variable| match servings {
+                    Servings::Integer => solution.var_value(*variable).round(),
+                    Servings::Relaxed => *solution.var_value(*variable),
+                })
+                .collect();
+            Ok(Some((solution.objective(), values)))
+        }
+        Err(microlp::Error::Infeasible) => Ok(None),
+        Err(e) => Err(AppError::ServiceUnavailable(format!("Meal plan optimizer failed: {}", e))),
+    }
+}
+
+fn health_points(product: &ProductResponse) -> f32 {
+    product.nutri_score.as_ref()
+        .map(|score| (HEALTH_POINTS_CEILING - score.points as f32).max(0.0))
+        .unwrap_or(0.0)
+}
+
+fn round_to_cents(value: f64) -> f32 {
+    ((value * 100.0).round() / 100.0) as f32
+}
+
+// รวมค่าสารอาหาร; ค่าที่ไม่บังคับจะมีก็ต่อเมื่อทุก product ที่เลือกมีค่านั้น
+fn plan_totals(chosen: &[(&ProductResponse, i32)]) -> NutrientAmounts {
+    let sum = |amount: &dyn Fn(&NutrientAmounts) -> f32| {
+        chosen.iter().map(|(product, servings)| amount(&product.nutrient_amounts()) * *servings as f32).sum::<f32>()
+    };
+    let sum_optional = |amount: &dyn Fn(&NutrientAmounts) -> Option<f32>| {
+        chosen.iter()
+            .map(|(product, servings)| amount(&product.nutrient_amounts()).map(|value| value * *servings as f32))
+            .sum::<Option<f32>>()
+    };
+    NutrientAmounts {
+        calories: sum(&|amounts| amounts.calories),
+        fat: sum(&|amounts| amounts.fat),
+        sugar: sum(&|amounts| amounts.sugar),
+        sodium: sum(&|amounts| amounts.sodium),
+        protein: sum(&|amounts| amounts.protein),
+        carbs: sum(&|amounts| amounts.carbs),
+        saturated_fat: sum(&|amounts| amounts.saturated_fat),
+        cholesterol: sum(&|amounts| amounts.cholesterol),
+        vitamin_c: sum_optional(&|amounts| amounts.vitamin_c),
+        calcium: sum_optional(&|amounts| amounts.calcium),
+        vitamin_b1: sum_optional(&|amounts| amounts.vitamin_b1),
+        vitamin_a: sum_optional(&|amounts| amounts.vitamin_a),
+        fibre: sum_optional(&|amounts| amounts.fibre),
+    }
+    .scaled(1.0)
+}
+
+// (ชื่อ, หน่วย, ขอบเขต, ค่าที่ขอ, ค่าต่อ serving)
+type NutrientLimit = (&'static str, &'static str, Bound, Option<f32>, fn(&ProductResponse) -> f32);
+
+fn build_constraints(request: &MealPlanRequest, candidates: &[ProductResponse]) -> Vec<LinearConstraint> {
+    let mut constraints = vec![
+        LinearConstraint::new("budget", "THB", Bound::Max, request.budget, candidates, |product| product.price),
+    ];
+    let limits: [NutrientLimit; 6] = [
+        ("min_calories", "kcal", Bound::Min, request.min_calories, |product| product.calories as f32),
+        ("max_calories", "kcal", Bound::Max, request.max_calories, |product| product.calories as f32),
+        ("min_protein", "g", Bound::Min, request.min_protein, |product| product.protein),
+        ("max_sodium", "mg", Bound::Max, request.max_sodium, |product| product.sodium),
+        ("max_sugar", "g", Bound::Max, request.max_sugar, |product| product.sugar),
+        ("max_saturated_fat", "g", Bound::Max, request.max_saturated_fat, |product| product.saturated_fat),
+    ];
+    for (name, unit, bound, limit, amount) in limits {
+        if let Some(limit) = limit {
+            constraints.push(LinearConstraint::new(name, unit, bound, limit, candidates, amount));
+        }
+    }
+    constraints
+}
+
+fn build_objective(objective: PlanObjective, candidates: &[ProductResponse]) -> Objective {
+    match objective {
+        PlanObjective::MinCost => Objective {
+            direction: OptimizationDirection::Minimize,
+            coefficients: candidates.iter().map(|product| product.price as f64).collect(),
+        },
+        PlanObjective::MaxHealth => Objective {
+            direction: OptimizationDirection::Maximize,
+            coefficients: candidates.iter()
+                .map(|product| health_points(product) as f64 - COST_TIE_BREAK * product.price as f64)
+                .collect(),
+        },
+    }
+}
+
+fn without(constraints: &[LinearConstraint], index: usize) -> Vec<&LinearConstraint> {
+    constraints.iter().enumerate()
+        .filter(|(other, _)| *other != index)
+        .map(|(_, constraint)| constraint)
+        .collect()
+}
+
+// ค่าที่ดีที่สุดของผลรวมในเงื่อนไขนี้เมื่อเงื่อนไขอื่นยังคงอยู่ = ค่าที่ต้องผ่อนเงื่อนไขนี้ลงไป
+// LP ไม่มีคำตอบแปลว่า MILP ก็ไม่มี จึงแก้ MILP เฉพาะเมื่อ LP มีคำตอบ
+fn suggest_relaxation(constraints: &[LinearConstraint], index: usize, max_servings: i32, deadline: Instant) -> Result<PlanRelaxation, AppError> {
+    let constraint = &constraints[index];
+    let direction = match constraint.bound {
+        Bound::Max => OptimizationDirection::Minimize,
+        Bound::Min => OptimizationDirection::Maximize,
+    };
+    let objective = Objective { direction, coefficients: constraint.coefficients.clone() };
+    let others = without(constraints, index);
+    let optimum = match solve(&objective, &others, max_servings, Servings::Relaxed, deadline)? {
+        Some(_) => solve(&objective, &others, max_servings, Servings::Integer, deadline)?,
+        None => None,
+    };
+    let suggested_limit = optimum.map(|(value, _)| {
+        // ปัดออกจากเงื่อนไขเดิมเพื่อให้ค่าที่แนะนำใช้ได้จริง
+        match constraint.bound {
+            Bound::Max => (((value - IMPROVEMENT_TOLERANCE) * 100.0).ceil() / 100.0) as f32,
+            Bound::Min => (((value + IMPROVEMENT_TOLERANCE) * 100.0).floor() / 100.0) as f32,
+        }
+    });
+    Ok(PlanRelaxation {
+        name: constraint.name.to_string(),
+        limit: constraint.limit,
+        suggested_limit,
+        unit: constraint.unit.to_string(),
+    })
+}
+
+/// Solves the plan for already resolved limits; CPU bound, run it off the async runtime.
+///
+/// Fails with `ServiceUnavailable` once `deadline` has passed between two solver runs.
+pub fn optimize(
+    request: &MealPlanRequest,
+    candidates: &[ProductResponse],
+    candidates_truncated: bool,
+    profile_targets: Option<DailyTargets>,
+    deadline: Instant,
+) -> Result<MealPlan, AppError> {
+    let max_servings = request.max_servings();
+    let unpruned = build_constraints(request, candidates);
+    let objective = build_objective(request.objective, candidates);
+    let kept = undominated(&objective, &unpruned, max_servings);
+    let constraints: Vec<LinearConstraint> = unpruned.iter().map(|constraint| constraint.restricted_to(&kept)).collect();
+    let objective = objective.restricted_to(&kept);
+    let all: Vec<&LinearConstraint> = constraints.iter().collect();
+
+    let mut plan = MealPlan {
+        feasible: false,
+        objective: request.objective,
+        budget: request.budget,
+        profile_targets,
+        candidates: candidates.len(),
+        candidates_truncated,
+        dominated_candidates: candidates.len() - kept.len(),
+        items: Vec::new(),
+        total_cost: 0.0,
+        health_points: 0.0,
+        totals: NutrientAmounts::default(),
+        constraints: Vec::new(),
+        relax: Vec::new(),
+    };
+
+    let Some((_, servings)) = solve(&objective, &all, max_servings, Servings::Integer, deadline)? else {
+        plan.relax = (0..constraints.len())
+            .map(|index| {
+                // การตัดตัวที่ถูกข่มอาศัยงบ เมื่อผ่อนงบ (ข้อแรก) จึงต้องใช้ product ทั้งหมด
+                let constraints = if index == 0 { &unpruned } else { &constraints };
+                suggest_relaxation(constraints, index, max_servings, deadline)
+            })
+            .collect::<Result<_, _>>()?;
+        return Ok(plan);
+    };
+
+    // LP relaxation มีคำตอบเสมอเมื่อ MILP มี
+    let relaxed = solve(&objective, &all, max_servings, Servings::Relaxed, deadline)?
+        .map(|(_, relaxed)| relaxed)
+        .unwrap_or_default();
+    for constraint in &constraints {
+        plan.constraints.push(PlanConstraint {
+            name: constraint.name.to_string(),
+            limit: constraint.limit,
+            value: round_to_cents(constraint.total(&servings)),
+            unit: constraint.unit.to_string(),
+            binding: constraint.is_tight(&relaxed),
+        });
+    }
+
+    let chosen: Vec<(&ProductResponse, i32)> = kept.iter()
+        .map(|index| &candidates[*index])
+        .zip(servings)
+        .map(|(product, servings)| (product, servings as i32))
+        .filter(|(_, servings)| *servings > 0)
+        .collect();
+    plan.feasible = true;
+    plan.items = chosen.iter()
+        .map(|(product, servings)| MealPlanItem {
+            product_id: product.id,
+            name: product.name.clone(),
+            brand: product.brand.clone(),
+            servings: *servings,
+            unit_price: product.price,
+            subtotal: round_to_cents(product.price as f64 * *servings as f64),
+            nutri_score_grade: product.nutri_score.as_ref().map(|score| score.grade.clone()),
+        })
+        .collect();
+    plan.total_cost = round_to_cents(chosen.iter().map(|(product, servings)| product.price as f64 * *servings as f64).sum());
+    plan.health_points = chosen.iter().map(|(product, servings)| health_points(product) * *servings as f32).sum();
+    plan.totals = plan_totals(&chosen);
+    Ok(plan)
+}
+
+#[async_trait]
+pub trait MealPlanServiceTrait: Send + Sync {
+    async fn optimize_meal_plan(&self, request: MealPlanRequest) -> Result<MealPlan, AppError>;
+}
+
+pub struct MealPlanService {
+    products: Arc<dyn ProductRepositoryTrait + Send + Sync>,
+    profiles: Arc<dyn UserProfileRepositoryTrait + Send + Sync>,
+}
+
+impl MealPlanService {
+    pub fn new(
+        products: Arc<dyn ProductRepositoryTrait + Send + Sync>,
+        profiles: Arc<dyn UserProfileRepositoryTrait + Send + Sync>,
+    ) -> Self {
+        Self { products, profiles }
+    }
+
+    // เงื่อนไขที่ไม่ได้ส่งมาใช้เป้าหมายต่อวันของ profile
+    async fn apply_profile_targets(&self, request: &mut MealPlanRequest) -> Result<Option<DailyTargets>, AppError> {
+        let Some(profile_id) = request.profile_id else {
+            return Ok(None);
+        };
+        let profile = match self.profiles.get_user_profile_by_id(profile_id).await {
+            Ok(profile) => profile,
+            Err(AppError::NotFound) => {
+                return Err(AppError::InvalidFields(vec![FieldError::new("profile_id", "unknown user profile")]));
+            }
+            Err(e) => return Err(e),
+        };
+        let targets = user_profile_service::daily_targets(&profile)?;
+        request.min_calories.get_or_insert(targets.energy_kcal * PROFILE_CALORIE_MIN_RATIO);
+        request.max_calories.get_or_insert(targets.energy_kcal * PROFILE_CALORIE_MAX_RATIO);
+        request.min_protein.get_or_insert(targets.protein_g);
+        request.max_sodium.get_or_insert(targets.sodium_mg);
+        request.max_sugar.get_or_insert(targets.sugar_g);
+        Ok(Some(targets))
+    }
+}
+
+#[async_trait]
+impl MealPlanServiceTrait for MealPlanService {
+    async fn optimize_meal_plan(&self, mut request: MealPlanRequest) -> Result<MealPlan, AppError> {
+        request.validate()?;
+        let profile_targets = self.apply_profile_targets(&mut request).await?;
+        request.validate()?;
+        if request.objective == PlanObjective::MinCost && request.min_calories.is_none() && request.min_protein.is_none() {
+            return Err(AppError::ValidationError(
+                "objective min_cost needs min_calories, min_protein or profile_id; otherwise the cheapest plan is empty".to_string()
+            ));
+        }
+
+        let (candidates, truncated) = self.products.get_meal_plan_candidates(&request.exclude, request.objective).await?;
+        // solver ของ microlp หยุดกลางทางไม่ได้: `optimize` ตรวจ deadline ระหว่างการแก้แต่ละครั้ง
+        // ส่วน timeout นี้กันกรณีที่การแก้ครั้งเดียวนานเกิน (thread ทำงานต่อจนจบแต่ไม่มีใครรอผล)
+        let deadline = Instant::now() + OPTIMIZER_DEADLINE;
+        let solver = tokio::task::spawn_blocking(move || optimize(&request, &candidates, truncated, profile_targets, deadline));
+        let plan = tokio::time::timeout(OPTIMIZER_DEADLINE, solver)
+            .await
+            .map_err(|_| AppError::ServiceUnavailable(OUT_OF_TIME.to_string()))?
+            .map_err(|e| AppError::ServiceUnavailable(format!("Meal plan optimizer failed: {}", e)))??;
+
+        info!(
+            "🧮 Meal plan over {} candidates ({} dominated): feasible={}, cost={}",
+            plan.candidates, plan.dominated_candidates, plan.feasible, plan.total_cost
+        );
+        Ok(plan)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn product(name: &str, price: f32, calories: i32, protein: f32) -> ProductResponse {
+        ProductResponse { name: name.to_string(), price, calories, protein, ..ProductResponse::default() }
+    }
+
+    fn catalog() -> Vec<ProductResponse> {
+        vec![product("rice", 10.0, 300, 5.0), product("egg", 8.0, 80, 7.0)]
+    }
+
+    fn request(body: serde_json::Value) -> MealPlanRequest {
+        serde_json::from_value(body).unwrap()
+    }
+
+    fn plan(request: &MealPlanRequest, candidates: &[ProductResponse]) -> MealPlan {
+        optimize(request, candidates, false, None, Instant::now() + OPTIMIZER_DEADLINE).unwrap()
+    }
+
+    fn relaxation<'a>(plan: &'a MealPlan, name: &str) -> &'a PlanRelaxation {
+        plan.relax.iter().find(|relaxation| relaxation.name == name).unwrap()
+    }
+
+    #[test]
+    fn finds_the_cheapest_plan_and_its_binding_limits() {
+        let plan = plan(&request(serde_json::json!({ "budget": 100, "min_calories": 800 })), &catalog());
+        assert!(plan.feasible);
+        assert_eq!(plan.total_cost, 30.0);
+        assert_eq!(plan.items.len(), 1);
+        assert_eq!((plan.items[0].name.as_str(), plan.items[0].servings), ("rice", 3));
+
+        let binding: Vec<(&str, bool)> = plan.constraints.iter()
+            .map(|constraint| (constraint.name.as_str(), constraint.binding))
+            .collect();
+        assert_eq!(binding, vec![("budget", false), ("min_calories", true)]);
+    }
+
+    #[test]
+    fn infeasible_plan_suggests_limits_that_make_it_feasible() {
+        let infeasible = request(serde_json::json!({ "budget": 20, "min_calories": 800 }));
+        let plan_of = |request: &MealPlanRequest| plan(request, &catalog());
+        let result = plan_of(&infeasible);
+        assert!(!result.feasible);
+
+        let budget = relaxation(&result, "budget").suggested_limit.unwrap();
+        assert_eq!(budget, 30.0);
+        let relaxed = MealPlanRequest { budget, ..infeasible.clone() };
+        assert!(plan_of(&relaxed).feasible);
+
+        let min_calories = relaxation(&result, "min_calories").suggested_limit.unwrap();
+        assert_eq!(min_calories, 600.0);
+        let relaxed = MealPlanRequest { min_calories: Some(min_calories), ..infeasible.clone() };
+        assert!(plan_of(&relaxed).feasible);
+    }
+
+    #[test]
+    fn suggests_nothing_when_one_limit_alone_cannot_be_relaxed_enough() {
+        // ข้าว 3 + ไข่ 3 ได้ไม่ถึง 2000 kcal ไม่ว่างบจะเท่าไร
+        let result = plan(&request(serde_json::json!({ "budget": 20, "min_calories": 2000 })), &catalog());
+        assert!(!result.feasible);
+        assert_eq!(relaxation(&result, "budget").suggested_limit, None);
+        assert_eq!(relaxation(&result, "min_calories").suggested_limit, Some(600.0));
+    }
+
+    #[test]
+    fn drops_dominated_candidates_without_changing_the_plan() {
+        let mut candidates = catalog();
+        candidates.push(product("pricier rice", 12.0, 300, 5.0));
+        let request = request(serde_json::json!({ "budget": 30, "min_calories": 800 }));
+        let plan = plan(&request, &candidates);
+        assert_eq!(plan.dominated_candidates, 1);
+        assert_eq!(plan.total_cost, 30.0);
+    }
+
+    #[test]
+    fn fails_as_unavailable_past_the_deadline() {
+        let request = request(serde_json::json!({ "budget": 100, "min_calories": 800 }));
+        let result = optimize(&request, &catalog(), false, None, Instant::now());
+        assert!(matches!(result, Err(AppError::ServiceUnavailable(_))));
+    }
+}
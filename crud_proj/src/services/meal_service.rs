@@ -0,0 +1,135 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    errors::AppError,
+    models::{
+        meals::{Meal, MealForm, MealItem, MealItemResponse, MealItemShare, MealResponse},
+        nutrition::{round_to, NutrientAmounts},
+    },
+    repositories::meal_repositories::MealRepositoryTrait,
+};
+/*
+รวมค่าสารอาหารและราคาของมื้อจาก item ที่อ่านพร้อม product ปัจจุบัน
+ - ค่าของ item = ค่าต่อ serving ของ product × จำนวน serving
+ - `share` = ร้อยละของยอดรวมมื้อ (0 เมื่อยอดรวมเป็น 0)
+*/
+
+fn share_of(value: f32, total: f32) -> f32 {
+    if total > 0.0 { round_to(value / total * 100.0, 1) } else { 0.0 }
+}
+
+fn item_share(price: f32, nutrients: &NutrientAmounts, total_price: f32, totals: &NutrientAmounts) -> MealItemShare {
+    MealItemShare {
+        price: share_of(price, total_price),
+        calories: share_of(nutrients.calories, totals.calories),
+        protein: share_of(nutrients.protein, totals.protein),
+        fat: share_of(nutrients.fat, totals.fat),
+        saturated_fat: share_of(nutrients.saturated_fat, totals.saturated_fat),
+        carbs: share_of(nutrients.carbs, totals.carbs),
+        sugar: share_of(nutrients.sugar, totals.sugar),
+        sodium: share_of(nutrients.sodium, totals.sodium),
+    }
+}
+
+fn present(meal: Meal, items: Vec<MealItem>) -> MealResponse {
+    let mut items: Vec<MealItemResponse> = items.into_iter()
+        .map(|item| {
+            let servings = item.quantity_unit.servings(item.quantity, item.product.serving_size_grams);
+            MealItemResponse {
+                position: item.position,
+                product_id: item.product_id,
+                brand: item.product.brand.clone(),
+                quantity: item.quantity,
+                quantity_unit: item.quantity_unit,
+                servings: servings.map(|servings| round_to(servings, 2)),
+                price: servings.map(|servings| round_to(item.product.price * servings, 2)),
+                nutrients: servings.map(|servings| item.product.nutrient_amounts().scaled(servings)),
+                share: None,
+                product_name: item.product.name,
+            }
+        })
+        .collect();
+
+    // มื้อที่ไม่มี item ที่แปลงได้รู้ค่าสารอาหารทุกตัว (เป็น 0)
+    let totals = items.iter()
+        .filter_map(|item| item.nutrients.as_ref())
+        .fold(NutrientAmounts::zero(), |totals, nutrients| totals.plus(nutrients))
+        .scaled(1.0);
+    let total_price = round_to(items.iter().filter_map(|item| item.price).fold(0.0, |total, price| total + price), 2);
+    for item in &mut items {
+        if let (Some(price), Some(nutrients)) = (item.price, &item.nutrients) {
+            item.share = Some(item_share(price, nutrients, total_price, &totals));
+        }
+    }
+
+    MealResponse {
+        id: meal.id,
+        name: meal.name,
+        unconverted_items: items.iter().filter(|item| item.servings.is_none()).count(),
+        items,
+        total_price,
+        totals,
+    }
+}
+
+#[async_trait]
+pub trait MealServiceTrait: Send + Sync {
+    async fn list_meals(&self) -> Result<Vec<MealResponse>, AppError>;
+    async fn add_meal(&self, meal: MealForm) -> Result<MealResponse, AppError>;
+    async fn get_meal_from_id(&self, id: Uuid) -> Result<Option<MealResponse>, AppError>;
+    async fn update_meal_from_id(&self, id: Uuid, meal: MealForm) -> Result<Option<MealResponse>, AppError>;
+    async fn delete_meal_from_id(&self, id: Uuid) -> Result<(), AppError>;
+}
+
+pub struct MealService {
+    repo: Arc<dyn MealRepositoryTrait + Send + Sync>,
+}
+
+impl MealService {
+    pub fn new(repo: Arc<dyn MealRepositoryTrait + Send + Sync>) -> Self {
+        Self { repo }
+    }
+}
+
+#[async_trait]
+impl MealServiceTrait for MealService {
+    async fn list_meals(&self) -> Result<Vec<MealResponse>, AppError> {
+        Ok(self.repo.get_meal_list().await?
+            .into_iter()
+            .map(|(meal, items)| present(meal, items))
+            .collect())
+    }
+
+    async fn add_meal(&self, meal: MealForm) -> Result<MealResponse, AppError> {
+        meal.validate()?;
+        let (meal, items) = self.repo.create_meal(meal).await?;
+        Ok(present(meal, items))
+    }
+
+    async fn get_meal_from_id(&self, id: Uuid) -> Result<Option<MealResponse>, AppError> {
+        match self.repo.get_meal_by_id(id).await {
+            Ok((meal, items)) => Ok(Some(present(meal, items))),
+            Err(AppError::NotFound) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn update_meal_from_id(&self, id: Uuid, meal: MealForm) -> Result<Option<MealResponse>, AppError> {
+        meal.validate()?;
+        match self.repo.update_meal_by_id(id, meal).await {
+            Ok((meal, items)) => Ok(Some(present(meal, items))),
+            Err(AppError::NotFound) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn delete_meal_from_id(&self, id: Uuid) -> Result<(), AppError> {
+        let affected_rows = self.repo.delete_meal_by_id(id).await?;
+        if affected_rows == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+}
@@ -1 +1,15 @@
-pub mod product_service;
\ No newline at end of file
+pub mod product_service;
+pub mod category_service;
+pub mod nutrition_reference;
+pub mod healthier_choice;
+pub mod nova;
+pub mod nutri_score;
+pub mod front_of_pack;
+pub mod user_profile_service;
+pub mod meal_plan;
+pub mod value_metrics;
+pub mod food_log;
+pub mod meal_service;
+pub mod product_comparison;
+pub mod alternatives;
+pub mod off_import;
\ No newline at end of file
@@ -0,0 +1,322 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    errors::{AppError, FieldError},
+    models::ingredients::{Additive, AdditiveForm, NovaClassification, NovaMarker},
+    repositories::{additive_repositories::AdditiveRepositoryTrait, product_repositories::ProductRepositoryTrait},
+};
+/*
+จัดกลุ่ม NOVA จากรายการส่วนประกอบ
+ - 4 (ultra-processed): พบวัตถุเจือปน/ส่วนผสมทางอุตสาหกรรมในทะเบียน `additives` ที่มี nova_group = 4
+ - 3 (processed): พบ additive ที่มี nova_group = 3 หรือเป็นอาหารที่เติมส่วนผสมปรุงอาหาร (น้ำตาล, เกลือ, น้ำมัน ...)
+ - 2 (processed culinary ingredient): ประกอบด้วยส่วนผสมปรุงอาหารล้วน
+ - 1 (unprocessed/minimally processed): ไม่พบตัวบ่งชี้ใดเลย
+ - ไม่มีรายการส่วนประกอบ → ไม่จัดกลุ่ม (ไม่ถือว่าเป็นกลุ่ม 1)
+การจับคู่ไม่สนตัวพิมพ์: ตรงกับ E/INS number (เช่น `E621`, `INS 621`) หรือมีชื่อใน `aliases`
+*/
+
+const PROCESSED_CULINARY_FUNCTION: &str = "processed_culinary_ingredient";
+
+/// A processed culinary ingredient (NOVA 2) that turns a food it is added to into NOVA 3.
+struct CulinaryIngredient {
+    name: &'static str,
+    /// English terms match whole words; Thai terms are full names, never bare prefixes such as "แป้ง".
+    terms: &'static [&'static str],
+    /// Bare words that only count when they are the whole ingredient (e.g. "เนย", not "เนยถั่ว").
+    exact: &'static [&'static str],
+    /// Other foods whose names contain a term; removed from the ingredient before matching.
+    excluded: &'static [&'static str],
+}
+
+const CULINARY_INGREDIENTS: &[CulinaryIngredient] = &[
+    CulinaryIngredient {
+        name: "sugar",
+        terms: &["sugar", "น้ำตาล"],
+        exact: &[],
+        excluded: &["สีน้ำตาล"],
+    },
+    CulinaryIngredient {
+        name: "salt",
+        terms: &["salt", "เกลือ"],
+        exact: &[],
+        excluded: &["เกลือแร่"],
+    },
+    CulinaryIngredient {
+        name: "oil",
+        terms: &[
+            "oil", "น้ำมันพืช", "น้ำมันปาล์ม", "น้ำมันถั่วเหลือง", "น้ำมันรำข้าว", "น้ำมันมะพร้าว",
+            "น้ำมันงา", "น้ำมันทานตะวัน", "น้ำมันข้าวโพด", "น้ำมันคาโนลา", "น้ำมันมะกอก", "น้ำมันหมู",
+        ],
+        exact: &["น้ำมัน"],
+        excluded: &["น้ำมันหอย"],
+    },
+    CulinaryIngredient {
+        name: "butter",
+        terms: &["butter", "เนยสด", "เนยจืด", "เนยเค็ม"],
+        exact: &["เนย"],
+        excluded: &["peanut butter", "nut butter", "เนยถั่ว", "เนยเทียม"],
+    },
+    CulinaryIngredient {
+        name: "honey",
+        terms: &["honey", "น้ำผึ้ง"],
+        exact: &[],
+        excluded: &[],
+    },
+    CulinaryIngredient {
+        name: "vinegar",
+        terms: &["vinegar", "น้ำส้มสายชู"],
+        exact: &[],
+        excluded: &[],
+    },
+    CulinaryIngredient {
+        name: "starch",
+        terms: &["starch", "แป้งมันสำปะหลัง", "แป้งมัน", "แป้งข้าวโพด", "แป้งท้าวยายม่อม", "แป้งมันฝรั่ง"],
+        exact: &[],
+        excluded: &[],
+    },
+];
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn is_ascii_word(c: char) -> bool {
+    c.is_ascii_alphanumeric()
+}
+
+// คำภาษาอังกฤษต้องตรงทั้งคำ ("oil" ไม่ตรงกับ "boiled") ส่วนภาษาไทยไม่มีช่องว่างระหว่างคำจึงจับแบบ substring
+fn contains_term(ingredient: &str, term: &str) -> bool {
+    !term.is_empty() && ingredient.match_indices(term).any(|(index, _)| {
+        let before = ingredient[..index].chars().last();
+        let after = ingredient[index + term.len()..].chars().next();
+        let starts_word = term.chars().next().is_some_and(is_ascii_word);
+        let ends_word = term.chars().last().is_some_and(is_ascii_word);
+        let joined_before = starts_word && before.is_some_and(is_ascii_word);
+        let joined_after = ends_word && after.is_some_and(is_ascii_word);
+        !joined_before && !joined_after
+    })
+}
+
+// `E621`, `e-621`, `INS 621` แต่ไม่ตรงกับ `E6210`
+fn mentions_code(ingredient: &str, code: &str) -> bool {
+    let number = code.trim_start_matches(['e', 'E']).to_lowercase();
+    ["e", "ins"].iter().any(|prefix| {
+        ingredient.match_indices(prefix).any(|(index, _)| {
+            if ingredient[..index].chars().last().is_some_and(|c| c.is_ascii_alphabetic()) {
+                return false;
+            }
+            let rest = ingredient[index + prefix.len()..].trim_start_matches([' ', '-', '.']);
+            rest.strip_prefix(number.as_str())
+                .is_some_and(|after| !after.starts_with(|c: char| c.is_ascii_digit()))
+        })
+    })
+}
+
+fn matches_additive(ingredient: &str, additive: &Additive) -> bool {
+    additive.e_number.as_deref().is_some_and(|code| mentions_code(ingredient, code))
+        || additive.aliases.iter().any(|alias| contains_term(ingredient, &normalize(alias)))
+}
+
+impl CulinaryIngredient {
+    fn matches(&self, ingredient: &str) -> bool {
+        if self.exact.contains(&ingredient) {
+            return true;
+        }
+        let remaining = self.excluded.iter()
+            .fold(ingredient.to_string(), |text, excluded| text.replace(excluded, " "));
+        self.terms.iter().any(|term| contains_term(&remaining, term))
+    }
+}
+
+fn culinary_marker(ingredient: &str) -> Option<&'static str> {
+    CULINARY_INGREDIENTS.iter()
+        .find(|culinary| culinary.matches(ingredient))
+        .map(|culinary| culinary.name)
+}
+
+pub fn classify(ingredients: &[String], additives: &[Additive]) -> NovaClassification {
+    if ingredients.is_empty() {
+        return NovaClassification::default();
+    }
+
+    let mut additive_markers = Vec::new();
+    let mut culinary_markers = Vec::new();
+    for ingredient in ingredients {
+        let normalized = normalize(ingredient);
+        let matched: Vec<&Additive> = additives.iter()
+            .filter(|additive| matches_additive(&normalized, additive))
+            .collect();
+
+        if !matched.is_empty() {
+            additive_markers.extend(matched.into_iter().map(|additive| NovaMarker {
+                ingredient: ingredient.clone(),
+                marker: additive.name.clone(),
+                e_number: additive.e_number.clone(),
+                function: additive.function.clone(),
+                nova_group: additive.nova_group,
+            }));
+        } else if let Some(marker) = culinary_marker(&normalized) {
+            culinary_markers.push(NovaMarker {
+                ingredient: ingredient.clone(),
+                marker: marker.to_string(),
+                e_number: None,
+                function: PROCESSED_CULINARY_FUNCTION.to_string(),
+                nova_group: 2,
+            });
+        }
+    }
+
+    // กลุ่มสูงสุดของ additive เป็นตัวกำหนด และแสดงเฉพาะ marker ของกลุ่มนั้น
+    if let Some(group) = additive_markers.iter().map(|marker| marker.nova_group).max() {
+        additive_markers.retain(|marker| marker.nova_group == group);
+        return NovaClassification { group: Some(group), markers: additive_markers };
+    }
+    if culinary_markers.is_empty() {
+        return NovaClassification { group: Some(1), markers: Vec::new() };
+    }
+    let group = if culinary_markers.len() == ingredients.len() { 2 } else { 3 };
+    NovaClassification { group: Some(group), markers: culinary_markers }
+}
+
+#[async_trait]
+pub trait AdditiveServiceTrait: Send + Sync {
+    async fn list_additives(&self) -> Result<Vec<Additive>, AppError>;
+    async fn add_additive(&self, additive: AdditiveForm) -> Result<Additive, AppError>;
+}
+
+pub struct AdditiveService {
+    repo: Arc<dyn AdditiveRepositoryTrait + Send + Sync>,
+    products: Arc<dyn ProductRepositoryTrait + Send + Sync>,
+}
+
+impl AdditiveService {
+    pub fn new(
+        repo: Arc<dyn AdditiveRepositoryTrait + Send + Sync>,
+        products: Arc<dyn ProductRepositoryTrait + Send + Sync>,
+    ) -> Self {
+        Self { repo, products }
+    }
+
+    // ทะเบียนเปลี่ยน → จัดกลุ่ม NOVA ของทุก product ที่มีส่วนประกอบใหม่ แล้วบันทึกใน UPDATE เดียว
+    async fn reclassify_products(&self) -> Result<u64, AppError> {
+        let additives = self.repo.get_additive_list().await?;
+        let classifications: Vec<(Uuid, NovaClassification)> = self.products.get_all_product_ingredients().await?
+            .into_iter()
+            .map(|(id, ingredients)| (id, classify(&ingredients, &additives)))
+            .collect();
+        let changed = self.products.save_nova_classifications(&classifications).await?;
+        info!("Reclassified NOVA group of {} products ({} changed)", classifications.len(), changed);
+        Ok(changed)
+    }
+}
+
+#[async_trait]
+impl AdditiveServiceTrait for AdditiveService {
+    async fn list_additives(&self) -> Result<Vec<Additive>, AppError> {
+        self.repo.get_additive_list().await
+    }
+
+    async fn add_additive(&self, additive: AdditiveForm) -> Result<Additive, AppError> {
+        let mut violations = Vec::new();
+        if additive.name.trim().is_empty() {
+            violations.push(FieldError::new("name", "must not be empty"));
+        }
+        if additive.function.trim().is_empty() {
+            violations.push(FieldError::new("function", "must not be empty"));
+        }
+        if !matches!(additive.nova_group, None | Some(3) | Some(4)) {
+            violations.push(FieldError::new("nova_group", "must be 3 or 4"));
+        }
+        if !violations.is_empty() {
+            return Err(AppError::InvalidFields(violations));
+        }
+
+        let created = self.repo.create_additive(additive).await?;
+        self.reclassify_products().await?;
+        Ok(created)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn additive(e_number: Option<&str>, name: &str, nova_group: i16, aliases: &[&str]) -> Additive {
+        Additive {
+            id: 0,
+            e_number: e_number.map(str::to_string),
+            name: name.to_string(),
+            function: "flavour_enhancer".to_string(),
+            nova_group,
+            aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+        }
+    }
+
+    fn registry() -> Vec<Additive> {
+        vec![
+            additive(Some("E621"), "monosodium glutamate", 4, &["MSG", "ผงชูรส"]),
+            additive(Some("E330"), "citric acid", 3, &["กรดซิตริก"]),
+        ]
+    }
+
+    fn ingredients(items: &[&str]) -> Vec<String> {
+        items.iter().map(|item| item.to_string()).collect()
+    }
+
+    fn classify_items(items: &[&str]) -> NovaClassification {
+        classify(&ingredients(items), &registry())
+    }
+
+    #[test]
+    fn matches_e_and_ins_numbers_but_not_longer_codes() {
+        assert!(mentions_code("flavour enhancer (e621)", "E621"));
+        assert!(mentions_code("วัตถุปรุงแต่งรสอาหาร (ins 621)", "E621"));
+        assert!(mentions_code("e-621", "E621"));
+        assert!(!mentions_code("e6210", "E621"));
+        assert!(!mentions_code("shine621", "E621"));
+    }
+
+    #[test]
+    fn registry_additive_makes_group_of_its_nova_group() {
+        let classification = classify_items(&["rice", "salt", "flavour enhancer (E621)"]);
+        assert_eq!(classification.group, Some(4));
+        assert_eq!(classification.markers.len(), 1);
+        assert_eq!(classification.markers[0].marker, "monosodium glutamate");
+
+        let by_alias = classify_items(&["หมูสับ", "ผงชูรส"]);
+        assert_eq!(by_alias.group, Some(4));
+        assert_eq!(classify_items(&["orange juice", "citric acid (e330)"]).group, Some(3));
+    }
+
+    #[test]
+    fn aliases_match_whole_english_words() {
+        assert!(!matches_additive("msgs", &registry()[0]));
+        assert!(matches_additive("seasoning (msg)", &registry()[0]));
+    }
+
+    #[test]
+    fn culinary_ingredients_make_group_2_or_3() {
+        assert_eq!(classify_items(&["น้ำตาลทราย"]).group, Some(2));
+        assert_eq!(classify_items(&["ถั่วลิสง", "เกลือ", "น้ำมันปาล์ม"]).group, Some(3));
+        assert_eq!(classify_items(&["rice"]).group, Some(1));
+        assert_eq!(classify(&[], &registry()).group, None);
+    }
+
+    #[test]
+    fn culinary_terms_skip_foods_that_only_contain_them() {
+        // สีน้ำตาล, เกลือแร่, น้ำมันหอย, เนยถั่ว, boiled egg ไม่ใช่ส่วนผสมปรุงอาหาร
+        for ingredient in ["ข้าวกล้องสีน้ำตาล", "เกลือแร่", "น้ำมันหอย", "เนยถั่ว", "peanut butter", "boiled egg", "แป้งสาลี"] {
+            assert_eq!(culinary_marker(&normalize(ingredient)), None, "{}", ingredient);
+        }
+        assert_eq!(culinary_marker("เนย"), Some("butter"));
+        assert_eq!(culinary_marker("น้ำมัน"), Some("oil"));
+        assert_eq!(culinary_marker("sunflower oil"), Some("oil"));
+        assert_eq!(culinary_marker("แป้งมันสำปะหลัง"), Some("starch"));
+    }
+}
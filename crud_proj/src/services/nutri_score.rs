@@ -0,0 +1,202 @@
+use crate::models::{
+    nutri_score::{NegativePoints, NutriScore, PositivePoints},
+    nutrition::{NutrientAmounts, NutritionFacts, ServingUnit},
+    products::ProductForm,
+};
+/*
+คำนวณ Nutri-Score ตามตารางคะแนนปี 2017 (Santé publique France)
+ - ใช้ค่าต่อ 100 g (อาหาร) หรือ 100 ml (เครื่องดื่ม = product ที่ `serving_unit` เป็น `ml`)
+ - product ที่ไม่มี serving size คำนวณไม่ได้ (ไม่เดาว่าเป็น 100 g)
+ - ใยอาหาร/สัดส่วนผักผลไม้ที่ไม่ทราบนับเป็น 0 คะแนนและระบุไว้ใน `missing`
+ - เครื่องดื่มได้เกรด A เฉพาะน้ำเปล่า (ส่วนประกอบมีแต่น้ำ)
+*/
+
+const KJ_PER_KCAL: f32 = 4.184;
+
+// คะแนน = จำนวน threshold ที่ค่าเกิน (มากกว่า) เช่น พลังงาน 700 kJ เกิน 335 และ 670 → 2 คะแนน
+const FOOD_ENERGY_KJ: [f32; 10] = [335.0, 670.0, 1005.0, 1340.0, 1675.0, 2010.0, 2345.0, 2680.0, 3015.0, 3350.0];
+const FOOD_SUGARS_G: [f32; 10] = [4.5, 9.0, 13.5, 18.0, 22.5, 27.0, 31.0, 36.0, 40.0, 45.0];
+const BEVERAGE_ENERGY_KJ: [f32; 10] = [0.0, 30.0, 60.0, 90.0, 120.0, 150.0, 180.0, 210.0, 240.0, 270.0];
+const BEVERAGE_SUGARS_G: [f32; 10] = [0.0, 1.5, 3.0, 4.5, 6.0, 7.5, 9.0, 10.5, 12.0, 13.5];
+const SATURATED_FAT_G: [f32; 10] = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+const SODIUM_MG: [f32; 10] = [90.0, 180.0, 270.0, 360.0, 450.0, 540.0, 630.0, 720.0, 810.0, 900.0];
+const FIBRE_G: [f32; 5] = [0.9, 1.9, 2.8, 3.7, 4.7];
+const PROTEIN_G: [f32; 5] = [1.6, 3.2, 4.8, 6.4, 8.0];
+
+// ถ้าคะแนนลบ >= 11 จะไม่นับโปรตีน เว้นแต่ได้คะแนนผักผลไม้เต็ม
+const PROTEIN_CAP_NEGATIVE_POINTS: i16 = 11;
+
+const WATER_INGREDIENTS: &[&str] = &["water", "drinking water", "mineral water", "น้ำ", "น้ำดื่ม", "น้ำแร่"];
+
+fn points_above(value: f32, thresholds: &[f32]) -> i16 {
+    thresholds.iter().filter(|threshold| value > **threshold).count() as i16
+}
+
+fn fruit_veg_points(percent: f32, beverage: bool) -> i16 {
+    let points = if percent > 80.0 {
+        5
+    } else if percent > 60.0 {
+        2
+    } else if percent > 40.0 {
+        1
+    } else {
+        0
+    };
+    // ตารางเครื่องดื่มให้คะแนนผักผลไม้เป็นสองเท่า
+    if beverage { points * 2 } else { points }
+}
+
+fn max_fruit_veg_points(beverage: bool) -> i16 {
+    fruit_veg_points(100.0, beverage)
+}
+
+fn is_water(ingredients: &[String]) -> bool {
+    !ingredients.is_empty()
+        && ingredients.iter().all(|ingredient| WATER_INGREDIENTS.contains(&ingredient.trim().to_lowercase().as_str()))
+}
+
+fn food_grade(points: i16) -> &'static str {
+    match points {
+        i16::MIN..=-1 => "A",
+        0..=2 => "B",
+        3..=10 => "C",
+        11..=18 => "D",
+        _ => "E",
+    }
+}
+
+fn beverage_grade(points: i16, water: bool) -> &'static str {
+    if water {
+        return "A";
+    }
+    match points {
+        i16::MIN..=1 => "B",
+        2..=5 => "C",
+        6..=9 => "D",
+        _ => "E",
+    }
+}
+
+/// Scores `per_100` values; `None` fibre/fruit-veg count as 0 points and are reported as missing.
+pub fn score(per_100: &NutrientAmounts, fruit_veg_percent: Option<f32>, beverage: bool, water: bool) -> NutriScore {
+    let (energy_table, sugars_table) = if beverage {
+        (&BEVERAGE_ENERGY_KJ, &BEVERAGE_SUGARS_G)
+    } else {
+        (&FOOD_ENERGY_KJ, &FOOD_SUGARS_G)
+    };
+
+    let mut negative = NegativePoints {
+        energy: points_above(per_100.calories * KJ_PER_KCAL, energy_table),
+        sugars: points_above(per_100.sugar, sugars_table),
+        saturated_fat: points_above(per_100.saturated_fat, &SATURATED_FAT_G),
+        sodium: points_above(per_100.sodium, &SODIUM_MG),
+        total: 0,
+    };
+    negative.total = negative.energy + negative.sugars + negative.saturated_fat + negative.sodium;
+
+    let mut missing = Vec::new();
+    let fruit_veg = match fruit_veg_percent {
+        Some(percent) => fruit_veg_points(percent, beverage),
+        None => {
+            missing.push("fruit_veg_percent".to_string());
+            0
+        }
+    };
+    let fibre = match per_100.fibre {
+        Some(fibre) => points_above(fibre, &FIBRE_G),
+        None => {
+            missing.push("fibre".to_string());
+            0
+        }
+    };
+    let protein = points_above(per_100.protein, &PROTEIN_G);
+
+    let protein_counted = negative.total < PROTEIN_CAP_NEGATIVE_POINTS || fruit_veg >= max_fruit_veg_points(beverage);
+    let mut positive = PositivePoints { fruit_veg, fibre, protein, total: 0 };
+    positive.total = fruit_veg + fibre + if protein_counted { protein } else { 0 };
+
+    let points = negative.total - positive.total;
+    let grade = if beverage { beverage_grade(points, water) } else { food_grade(points) };
+
+    NutriScore {
+        grade: grade.to_string(),
+        points,
+        beverage,
+        negative,
+        positive,
+        protein_counted,
+        missing,
+    }
+}
+
+/// Nutri-Score of a product as it will be stored, or `None` when it has no serving size.
+pub fn compute(product: &ProductForm) -> Option<NutriScore> {
+    let nutrition = NutritionFacts::new(product.nutrient_amounts(), product.serving_size_grams, product.serving_unit);
+    let per_100 = nutrition.per_100?;
+    let beverage = product.serving_unit == ServingUnit::Ml;
+    Some(score(&per_100, product.fruit_veg_percent, beverage, beverage && is_water(&product.ingredients)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn per_100(kcal: f32, sugar: f32, saturated_fat: f32, sodium: f32, protein: f32, fibre: Option<f32>) -> NutrientAmounts {
+        NutrientAmounts { calories: kcal, sugar, saturated_fat, sodium, protein, fibre, ..NutrientAmounts::default() }
+    }
+
+    #[test]
+    fn cola_is_e() {
+        // 42 kcal (176 kJ), น้ำตาล 10.6 g ต่อ 100 ml
+        let score = score(&per_100(42.0, 10.6, 0.0, 0.0, 0.0, Some(0.0)), Some(0.0), true, false);
+        assert_eq!(score.negative.energy, 6);
+        assert_eq!(score.negative.sugars, 8);
+        assert_eq!(score.points, 14);
+        assert_eq!(score.grade, "E");
+    }
+
+    #[test]
+    fn hazelnut_spread_is_e_without_protein() {
+        let score = score(&per_100(539.0, 56.3, 10.6, 42.8, 6.3, Some(3.4)), Some(0.0), false, false);
+        assert_eq!(score.negative.total, 6 + 10 + 10);
+        assert!(!score.protein_counted);
+        assert_eq!(score.positive.total, 3);
+        assert_eq!(score.points, 23);
+        assert_eq!(score.grade, "E");
+    }
+
+    #[test]
+    fn rolled_oats_are_a() {
+        let score = score(&per_100(375.0, 1.0, 1.3, 6.0, 12.0, Some(10.0)), Some(0.0), false, false);
+        assert_eq!(score.negative.total, 5);
+        assert!(score.protein_counted);
+        assert_eq!(score.positive.total, 10);
+        assert_eq!(score.points, -5);
+        assert_eq!(score.grade, "A");
+    }
+
+    #[test]
+    fn only_water_is_a_beverage_a() {
+        let zero = per_100(0.0, 0.0, 0.0, 0.0, 0.0, Some(0.0));
+        assert_eq!(score(&zero, Some(0.0), true, true).grade, "A");
+        assert_eq!(score(&zero, Some(0.0), true, false).grade, "B");
+        assert!(is_water(&["Drinking water".to_string(), "น้ำแร่".to_string()]));
+        assert!(!is_water(&["water".to_string(), "sugar".to_string()]));
+        assert!(!is_water(&[]));
+    }
+
+    #[test]
+    fn unknown_fibre_and_fruit_veg_are_reported_missing() {
+        let score = score(&per_100(100.0, 0.0, 0.0, 0.0, 0.0, None), None, false, false);
+        assert_eq!(score.missing, vec!["fruit_veg_percent".to_string(), "fibre".to_string()]);
+        assert_eq!(score.positive.total, 0);
+    }
+
+    #[test]
+    fn thresholds_count_values_strictly_above() {
+        assert_eq!(points_above(335.0, &FOOD_ENERGY_KJ), 0);
+        assert_eq!(points_above(335.1, &FOOD_ENERGY_KJ), 1);
+        assert_eq!(points_above(5000.0, &FOOD_ENERGY_KJ), 10);
+        assert_eq!(fruit_veg_points(81.0, true), 10);
+    }
+}
@@ -0,0 +1,98 @@
+use crate::{
+    errors::AppError,
+    models::nutrition::{DailyValueQuery, DailyValues, NutrientAmounts, NutritionFacts},
+};
+/*
+Thai Recommended Daily Intakes (Thai RDI) สำหรับคนไทยอายุ 6 ปีขึ้นไป บนฐานพลังงาน 2,000 kcal
+(ประกาศกระทรวงสาธารณสุขเรื่องฉลากโภชนาการ และ GDA สำหรับน้ำตาล)
+ - หน่วยตรงกับ column ใน `products`: g สำหรับ macro, mg สำหรับ sodium/cholesterol/vitamin C/B1/calcium,
+   µg RE สำหรับ vitamin A
+ - เมื่อกำหนดพลังงานเอง ค่าที่ขึ้นกับพลังงาน (fat, saturated fat, carbs, sugar, protein, fibre) จะถูกปรับ
+   ตามสัดส่วน ส่วน sodium, cholesterol และวิตามิน/แร่ธาตุคงที่
+*/
+
+pub const THAI_RDI_ENERGY_KCAL: f32 = 2000.0;
+
+// ช่วงพลังงานเป้าหมายที่รับได้ (ต่ำ/สูงกว่านี้ไม่สมเหตุสมผลสำหรับการคำนวณ %DV)
+pub const MIN_ENERGY_TARGET_KCAL: f32 = 800.0;
+pub const MAX_ENERGY_TARGET_KCAL: f32 = 6000.0;
+
+pub fn thai_rdi() -> NutrientAmounts {
+    NutrientAmounts {
+        calories: THAI_RDI_ENERGY_KCAL,
+        fat: 65.0,
+        sugar: 65.0,
+        sodium: 2400.0,
+        protein: 50.0,
+        carbs: 300.0,
+        saturated_fat: 20.0,
+        cholesterol: 300.0,
+        vitamin_c: Some(60.0),
+        calcium: Some(800.0),
+        vitamin_b1: Some(1.5),
+        vitamin_a: Some(800.0),
+        fibre: Some(25.0),
+    }
+}
+
+/// Reference intakes for a custom energy target, scaled from the Thai RDI.
+pub fn reference_intakes(energy_kcal: f32) -> Result<NutrientAmounts, AppError> {
+    if !(MIN_ENERGY_TARGET_KCAL..=MAX_ENERGY_TARGET_KCAL).contains(&energy_kcal) {
+        return Err(AppError::ValidationError(format!(
+            "energy_kcal must be between {} and {}", MIN_ENERGY_TARGET_KCAL, MAX_ENERGY_TARGET_KCAL
+        )));
+    }
+
+    let rdi = thai_rdi();
+    let factor = energy_kcal / THAI_RDI_ENERGY_KCAL;
+    Ok(NutrientAmounts {
+        calories: energy_kcal,
+        fat: rdi.fat * factor,
+        sugar: rdi.sugar * factor,
+        protein: rdi.protein * factor,
+        carbs: rdi.carbs * factor,
+        saturated_fat: rdi.saturated_fat * factor,
+        fibre: rdi.fibre.map(|fibre| fibre * factor),
+        ..rdi
+    })
+}
+
+/// Reference intakes the client asked `%DV` for, or `None` when `%DV` was not requested.
+pub fn requested_reference(query: &DailyValueQuery) -> Result<Option<NutrientAmounts>, AppError> {
+    if !query.requested() {
+        return Ok(None);
+    }
+    reference_intakes(query.energy_kcal.unwrap_or(THAI_RDI_ENERGY_KCAL)).map(Some)
+}
+
+/// Each nutrient as a percentage of `reference`, rounded to one decimal.
+pub fn percent_of_reference(amounts: &NutrientAmounts, reference: &NutrientAmounts) -> NutrientAmounts {
+    let percent = |value: f32, reference: f32| (value / reference * 1000.0).round() / 10.0;
+    let optional = |value: Option<f32>, reference: Option<f32>| {
+        value.zip(reference).map(|(value, reference)| percent(value, reference))
+    };
+    NutrientAmounts {
+        calories: percent(amounts.calories, reference.calories),
+        fat: percent(amounts.fat, reference.fat),
+        sugar: percent(amounts.sugar, reference.sugar),
+        sodium: percent(amounts.sodium, reference.sodium),
+        protein: percent(amounts.protein, reference.protein),
+        carbs: percent(amounts.carbs, reference.carbs),
+        saturated_fat: percent(amounts.saturated_fat, reference.saturated_fat),
+        cholesterol: percent(amounts.cholesterol, reference.cholesterol),
+        vitamin_c: optional(amounts.vitamin_c, reference.vitamin_c),
+        calcium: optional(amounts.calcium, reference.calcium),
+        vitamin_b1: optional(amounts.vitamin_b1, reference.vitamin_b1),
+        vitamin_a: optional(amounts.vitamin_a, reference.vitamin_a),
+        fibre: optional(amounts.fibre, reference.fibre),
+    }
+}
+
+pub fn daily_values(nutrition: &NutritionFacts, reference: &NutrientAmounts) -> DailyValues {
+    DailyValues {
+        energy_kcal: reference.calories,
+        reference: reference.clone(),
+        per_serving: percent_of_reference(&nutrition.per_serving, reference),
+        per_100: nutrition.per_100.as_ref().map(|per_100| percent_of_reference(per_100, reference)),
+    }
+}
@@ -0,0 +1,562 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    error::Error,
+    fs::File,
+    io::{BufRead, BufReader},
+    sync::Arc,
+};
+use serde_json::Value;
+use sqlx::PgPool;
+use tracing::{info, warn};
+
+use crate::{
+    errors::{AppError, FieldError},
+    models::{
+        barcodes::Barcode,
+        categories::Categories,
+        front_of_pack::FrontOfPackQuery,
+        nutrition::{DailyValueQuery, ServingUnit},
+        off_import::{ImportAction, ImportFormat, ImportOptions, ImportReport, OffProduct, RowIssue},
+        products::{ProductForm, ProductPatch},
+    },
+    repositories::{
+        additive_repositories::AdditiveRepository,
+        category_repositories::{CategoryRepository, CategoryRepositoryTrait},
+        product_repositories::ProductRepository,
+    },
+    services::product_service::{ProductService, ProductServiceTrait},
+};
+
+type Rows = Box<dyn Iterator<Item = (usize, Result<OffProduct, String>)>>;
+
+const KJ_PER_KCAL: f64 = 4.184;
+// โซเดียม (mg) ต่อเกลือ 1 g
+const SODIUM_MG_PER_SALT_G: f64 = 400.0;
+const DEFAULT_SERVING: f64 = 100.0;
+// serving_quantity ที่มากกว่านี้ถือว่ากรอกผิด ใช้ 100 g/ml แทน
+const MAX_SERVING: f64 = 5000.0;
+const MAX_INGREDIENT_LENGTH: usize = 255;
+
+fn text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.trim().to_string()).filter(|text| !text.is_empty()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().replace(',', ".").parse().ok(),
+        _ => None,
+    }
+}
+
+// JSONL เก็บ tag เป็น array ส่วน CSV คั่นด้วย comma
+fn tags(value: &str) -> Vec<String> {
+    value.split(',')
+        .map(|tag| tag.trim().to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+fn first_text(record: &HashMap<&str, Value>, keys: &[&str]) -> Option<String> {
+    keys.iter().find_map(|key| record.get(key).and_then(text))
+}
+
+/// One OFF product from its JSON object (JSONL export) or CSV columns, both keyed by OFF field name.
+fn off_product(record: HashMap<&str, Value>, nutriments: HashMap<String, f64>) -> Result<OffProduct, String> {
+    let tag_list = |key: &str| match record.get(key) {
+        Some(Value::Array(items)) => items.iter().filter_map(text).map(|tag| tag.to_lowercase()).collect(),
+        Some(value) => text(value).map(|value| tags(&value)).unwrap_or_default(),
+        None => Vec::new(),
+    };
+    Ok(OffProduct {
+        code: record.get("code").and_then(text).ok_or("missing code")?,
+        product_name: first_text(&record, &["product_name_th", "product_name", "product_name_en"]),
+        brands: first_text(&record, &["brands"]),
+        image_url: first_text(&record, &["image_url", "image_front_url"]),
+        countries_tags: tag_list("countries_tags"),
+        categories_tags: tag_list("categories_tags"),
+        ingredients_text: first_text(&record, &["ingredients_text_th", "ingredients_text", "ingredients_text_en"]),
+        serving_quantity: record.get("serving_quantity").and_then(number),
+        serving_unit: first_text(&record, &["serving_quantity_unit"]),
+        quantity: first_text(&record, &["quantity"]),
+        nova_group: record.get("nova_group").and_then(number).map(|group| group as i16),
+        nutriments,
+    })
+}
+
+fn parse_json_line(line: &str) -> Result<OffProduct, String> {
+    let value: Value = serde_json::from_str(line).map_err(|e| format!("malformed JSON: {}", e))?;
+    let Value::Object(object) = value else {
+        return Err("expected a JSON object".to_string());
+    };
+    let nutriments = object.get("nutriments")
+        .and_then(Value::as_object)
+        .map(|nutriments| nutriments.iter()
+            .filter_map(|(key, value)| Some((key.strip_suffix("_100g")?.to_string(), number(value)?)))
+            .collect())
+        .unwrap_or_default();
+    let record = object.iter().map(|(key, value)| (key.as_str(), value.clone())).collect();
+    off_product(record, nutriments)
+}
+
+fn jsonl_rows(file: File) -> Rows {
+    Box::new(BufReader::new(file).lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.map_err(|e| format!("unreadable line: {}", e))))
+        .filter(|(_, line)| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+        .map(|(line_number, line)| (line_number, line.and_then(|line| parse_json_line(&line)))))
+}
+
+// export หลักของ OFF คั่นด้วย tab และไม่ใช้ quote; CSV ทั่วไปคั่นด้วย comma
+fn csv_rows(path: &str) -> Result<Rows, Box<dyn Error>> {
+    let mut header = String::new();
+    BufReader::new(File::open(path)?).read_line(&mut header)?;
+    let delimiter = if header.contains('\t') { b'\t' } else { b',' };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .quoting(delimiter != b'\t')
+        .flexible(true)
+        .from_path(path)?;
+    let headers = reader.headers()?.clone();
+    Ok(Box::new(reader.into_records().enumerate().map(move |(index, record)| {
+        let line_number = record.as_ref().ok()
+            .and_then(|record| record.position())
+            .map(|position| position.line() as usize)
+            .unwrap_or(index + 2);
+        let product = record.map_err(|e| format!("malformed CSV row: {}", e)).and_then(|record| {
+            let nutriments = headers.iter().zip(record.iter())
+                .filter_map(|(column, value)| {
+                    Some((column.strip_suffix("_100g")?.to_string(), number(&Value::String(value.to_string()))?))
+                })
+                .collect();
+            let columns = headers.iter().zip(record.iter())
+                .map(|(column, value)| (column, Value::String(value.to_string())))
+                .collect();
+            off_product(columns, nutriments)
+        });
+        (line_number, product)
+    })))
+}
+
+/// Matches OFF category tags to categories by `off_tags` or by name (`en:instant-noodles` ↔ `Instant noodles`).
+struct CategoryMatcher {
+    categories: Vec<Categories>,
+}
+
+impl CategoryMatcher {
+    fn find(&self, tag: &str) -> Option<i32> {
+        let name = tag.split_once(':').map_or(tag, |(_, name)| name).replace('-', " ");
+        self.categories.iter()
+            .find(|category| category.off_tags.iter().any(|off_tag| off_tag == tag))
+            .or_else(|| self.categories.iter().find(|category| category.name.to_lowercase() == name))
+            .map(|category| category.id)
+    }
+
+    /// Matched category ids and the tags that matched nothing.
+    fn map(&self, tags: &[String]) -> (Vec<i32>, Vec<String>) {
+        let mut ids = BTreeSet::new();
+        let mut unmapped = Vec::new();
+        for tag in tags {
+            match self.find(tag) {
+                Some(id) => { ids.insert(id); }
+                None => unmapped.push(tag.clone()),
+            }
+        }
+        (ids.into_iter().collect(), unmapped)
+    }
+}
+
+fn serving_unit(product: &OffProduct) -> ServingUnit {
+    let is_ml = |unit: &str| {
+        let unit = unit.trim().to_lowercase();
+        unit.ends_with("ml") || unit.ends_with("cl") || unit.ends_with(" l") || unit == "l"
+    };
+    match (&product.serving_unit, &product.quantity) {
+        (Some(unit), _) if is_ml(unit) => ServingUnit::Ml,
+        (None, Some(quantity)) if is_ml(quantity) => ServingUnit::Ml,
+        _ => ServingUnit::G,
+    }
+}
+
+// แยกส่วนประกอบระดับบนสุด (ไม่ตัดใน วงเล็บ) และตัดเครื่องหมาย allergen `_..._` ของ OFF
+fn ingredients(text: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    for c in text.chars() {
+        match c {
+            '(' | '[' => { depth += 1; current.push(c); }
+            ')' | ']' => { depth = depth.saturating_sub(1); current.push(c); }
+            ',' | ';' if depth == 0 => items.push(std::mem::take(&mut current)),
+            '_' => {}
+            _ => current.push(c),
+        }
+    }
+    items.push(current);
+    items.into_iter()
+        .map(|item| item.trim().trim_end_matches('.').trim().to_string())
+        .filter(|item| !item.is_empty() && item.chars().count() <= MAX_INGREDIENT_LENGTH)
+        .collect()
+}
+
+// serving บนฉลากตาม OFF หรือ None เมื่อไม่มีหรือค่าใช้ไม่ได้
+fn label_serving(product: &OffProduct) -> Option<f64> {
+    product.serving_quantity.filter(|serving| serving.is_finite() && *serving > 0.0 && *serving <= MAX_SERVING)
+}
+
+/// The product form for an OFF product, or the fields that could not be mapped.
+fn product_form(product: &OffProduct, barcode: &Barcode, category_ids: Vec<i32>) -> Result<ProductForm, Vec<FieldError>> {
+    let mut missing = Vec::new();
+    let mut required = |value: Option<f64>, field: &str| {
+        if value.is_none() {
+            missing.push(FieldError::new(field, "missing"));
+        }
+        value.unwrap_or(0.0)
+    };
+
+    let kcal = required(
+        product.per_100("energy-kcal")
+            .or_else(|| product.per_100("energy-kj").map(|kj| kj / KJ_PER_KCAL))
+            .or_else(|| product.per_100("energy").map(|kj| kj / KJ_PER_KCAL)),
+        "nutriments.energy-kcal_100g",
+    );
+    let fat = required(product.per_100("fat"), "nutriments.fat_100g");
+    let saturated_fat = required(product.per_100("saturated-fat"), "nutriments.saturated-fat_100g");
+    let carbs = required(product.per_100("carbohydrates"), "nutriments.carbohydrates_100g");
+    let sugar = required(product.per_100("sugars"), "nutriments.sugars_100g");
+    let protein = required(product.per_100("proteins"), "nutriments.proteins_100g");
+    let sodium_mg = required(
+        product.per_100("sodium").map(|grams| grams * 1000.0)
+            .or_else(|| product.per_100("salt").map(|grams| grams * SODIUM_MG_PER_SALT_G)),
+        "nutriments.sodium_100g",
+    );
+    let name = product.product_name.clone();
+    if name.is_none() {
+        missing.push(FieldError::new("product_name", "missing"));
+    }
+    if !missing.is_empty() {
+        return Err(missing);
+    }
+
+    let serving = label_serving(product).unwrap_or(DEFAULT_SERVING);
+    let factor = serving / 100.0;
+    // ปัดทศนิยม 2 ตำแหน่งเหมือน `NutrientAmounts::scaled`
+    let per_serving = |value: f64| ((value * factor * 100.0).round() / 100.0) as f32;
+    let optional = |nutrient: &str, unit_factor: f64| product.per_100(nutrient).map(|value| per_serving(value * unit_factor));
+
+    Ok(ProductForm {
+        id: None,
+        name: name.unwrap_or_default(),
+        brand: product.brands.as_deref()
+            .and_then(|brands| brands.split(',').map(str::trim).find(|brand| !brand.is_empty()))
+            .map(str::to_string),
+        image_url: product.image_url.clone(),
+        categories_ids: category_ids,
+        serving_size_grams: Some(serving as f32),
+        serving_unit: serving_unit(product),
+        calories: (kcal * factor).round() as i32,
+        fat: per_serving(fat),
+        sugar: per_serving(sugar),
+        sodium: per_serving(sodium_mg),
+        protein: per_serving(protein),
+        carbs: per_serving(carbs),
+        saturated_fat: per_serving(saturated_fat),
+        // ฉลากไทยมีคอเลสเตอรอลแต่ OFF มักไม่มี column นี้ไม่รับ NULL
+        cholesterol: optional("cholesterol", 1000.0).unwrap_or(0.0),
+        vitamin_c: optional("vitamin-c", 1000.0),
+        calcium: optional("calcium", 1000.0),
+        vitamin_b1: optional("vitamin-b1", 1000.0),
+        vitamin_a: optional("vitamin-a", 1_000_000.0),
+        fibre: optional("fiber", 1.0),
+        fruit_veg_percent: product.per_100("fruits-vegetables-nuts")
+            .or_else(|| product.per_100("fruits-vegetables-nuts-estimate-from-ingredients"))
+            .map(|percent| percent as f32),
+        price: 0.0,
+        is_upf: product.nova_group == Some(4),
+        is_healthier: false,
+        ingredients: product.ingredients_text.as_deref().map(ingredients).unwrap_or_default(),
+        barcodes: vec![barcode.as_str().to_string()],
+    })
+}
+
+// แก้ product เดิมเฉพาะสิ่งที่ OFF มีข้อมูล ไม่ล้างค่าที่ผู้ดูแลกรอกเอง
+fn product_patch(form: &ProductForm, product: &OffProduct) -> ProductPatch {
+    ProductPatch {
+        name: Some(form.name.clone()),
+        brand: form.brand.clone().map(Some),
+        image_url: form.image_url.clone().map(Some),
+        categories_ids: (!form.categories_ids.is_empty()).then(|| form.categories_ids.clone()),
+        serving_size_grams: Some(form.serving_size_grams),
+        serving_unit: Some(form.serving_unit),
+        calories: Some(form.calories),
+        fat: Some(form.fat),
+        sugar: Some(form.sugar),
+        sodium: Some(form.sodium),
+        protein: Some(form.protein),
+        carbs: Some(form.carbs),
+        saturated_fat: Some(form.saturated_fat),
+        cholesterol: product.per_100("cholesterol").map(|_| form.cholesterol),
+        vitamin_c: form.vitamin_c.map(Some),
+        calcium: form.calcium.map(Some),
+        vitamin_b1: form.vitamin_b1.map(Some),
+        vitamin_a: form.vitamin_a.map(Some),
+        fibre: form.fibre.map(Some),
+        fruit_veg_percent: form.fruit_veg_percent.map(Some),
+        is_upf: product.nova_group.map(|group| group == 4),
+        ingredients: (!form.ingredients.is_empty()).then(|| form.ingredients.clone()),
+        ..ProductPatch::default()
+    }
+}
+
+fn field_errors(error: AppError) -> Result<Vec<FieldError>, AppError> {
+    match error {
+        AppError::InvalidFields(details) => Ok(details),
+        AppError::ValidationError(message) | AppError::Conflict(message) => Ok(vec![FieldError::new("product", message)]),
+        AppError::UnknownCategories(ids) => Ok(vec![FieldError::new("categories_ids", format!("unknown categories {:?}", ids))]),
+        e => Err(e),
+    }
+}
+
+pub struct OffImportService {
+    products: Arc<dyn ProductServiceTrait + Send + Sync>,
+    categories: Arc<dyn CategoryRepositoryTrait + Send + Sync>,
+}
+
+impl OffImportService {
+    pub fn new(
+        products: Arc<dyn ProductServiceTrait + Send + Sync>,
+        categories: Arc<dyn CategoryRepositoryTrait + Send + Sync>,
+    ) -> Self {
+        Self { products, categories }
+    }
+
+    /// Creates or updates (or, in a dry run, only checks) the product of one row.
+    async fn upsert(&self, product: &OffProduct, form: ProductForm, dry_run: bool) -> Result<Result<ImportAction, Vec<FieldError>>, AppError> {
+        let barcode = form.barcodes[0].clone();
+        let existing = self.products
+            .get_product_from_barcode(&barcode, DailyValueQuery::default(), FrontOfPackQuery::default())
+            .await?;
+        let result = match (existing, dry_run) {
+            (Some(existing), true) => product_patch(&form, product).merged_with(&existing).validate().map(|_| ImportAction::Updated),
+            (None, true) => form.validate().map(|_| ImportAction::Created),
+            (Some(existing), false) => self.products.patch_product_from_id(existing.id, product_patch(&form, product)).await
+                .map(|_| ImportAction::Updated),
+            (None, false) => self.products.add_product(form).await.map(|_| ImportAction::Created),
+        };
+        match result {
+            Ok(action) => Ok(Ok(action)),
+            Err(e) => field_errors(e).map(Err),
+        }
+    }
+
+    pub async fn import(&self, rows: Rows, options: &ImportOptions) -> Result<ImportReport, AppError> {
+        let matcher = CategoryMatcher { categories: self.categories.get_category_list().await? };
+        let mut report = ImportReport::new(options);
+        let mut seen: HashMap<String, usize> = HashMap::new();
+
+        for (line, row) in rows {
+            report.rows += 1;
+            let issue = |code: Option<&str>, reason: String, details: Vec<FieldError>| RowIssue {
+                line,
+                code: code.map(str::to_string),
+                reason,
+                details,
+            };
+            let product = match row {
+                Ok(product) => product,
+                Err(reason) => {
+                    report.invalid.push(issue(None, reason, Vec::new()));
+                    continue;
+                }
+            };
+            if !product.sold_in_thailand() {
+                report.not_in_thailand += 1;
+                continue;
+            }
+            let barcode = match Barcode::parse(&product.code) {
+                Ok(barcode) => barcode,
+                Err(message) => {
+                    report.invalid.push(issue(Some(&product.code), format!("barcode {}", message), Vec::new()));
+                    continue;
+                }
+            };
+            // เทียบเฉพาะกับแถวที่นำเข้าได้แล้ว แถวที่ซ้ำกับแถวที่ไม่ผ่านยังได้ลองนำเข้า
+            if let Some(first_line) = seen.get(barcode.as_str()) {
+                report.skipped.push(issue(Some(&product.code), format!("duplicate of the barcode on line {}", first_line), Vec::new()));
+                continue;
+            }
+
+            let (category_ids, unmapped) = matcher.map(&product.categories_tags);
+            let form = match product_form(&product, &barcode, category_ids) {
+                Ok(form) => form,
+                Err(details) => {
+                    report.invalid.push(issue(Some(&product.code), "missing required fields".to_string(), details));
+                    continue;
+                }
+            };
+            match self.upsert(&product, form, options.dry_run).await? {
+                Ok(action) => {
+                    match action {
+                        ImportAction::Created => report.created += 1,
+                        ImportAction::Updated => report.updated += 1,
+                    }
+                    for tag in unmapped {
+                        *report.unmapped_categories.entry(tag).or_default() += 1;
+                    }
+                    if label_serving(&product).is_none() {
+                        report.warnings.push(issue(
+                            Some(&product.code),
+                            "no usable serving_quantity; nutrients stored per 100 g/ml as the serving".to_string(),
+                            Vec::new(),
+                        ));
+                    }
+                    seen.insert(barcode.as_str().to_string(), line);
+                }
+                Err(details) => report.invalid.push(issue(Some(&product.code), "failed product validation".to_string(), details)),
+            }
+        }
+        Ok(report)
+    }
+}
+
+/// Runs `import-off`: reads the export, imports (or checks) every row and writes the JSON report.
+pub async fn run(pool: Arc<PgPool>, options: &ImportOptions) -> Result<ImportReport, Box<dyn Error>> {
+    let rows = match options.format {
+        ImportFormat::Jsonl => jsonl_rows(File::open(&options.path)?),
+        ImportFormat::Csv => csv_rows(&options.path)?,
+    };
+    let products = Arc::new(ProductService::new(
+        Arc::new(ProductRepository::new(pool.clone())),
+        Arc::new(AdditiveRepository::new(pool.clone())),
+    ));
+    let categories = Arc::new(CategoryRepository::new(pool));
+    let service = OffImportService::new(products, categories);
+
+    info!("Importing Open Food Facts {:?} export {} (dry run: {})", options.format, options.path, options.dry_run);
+    let report = service.import(rows, options).await?;
+    serde_json::to_writer_pretty(File::create(&options.report_path)?, &report)?;
+    if !report.invalid.is_empty() {
+        warn!("{} invalid rows, see {}", report.invalid.len(), options.report_path);
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn form(product: &OffProduct) -> ProductForm {
+        let barcode = Barcode::parse(&product.code).unwrap();
+        product_form(product, &barcode, Vec::new()).unwrap()
+    }
+
+    #[test]
+    fn jsonl_row_maps_kj_salt_and_vitamin_a_to_the_serving() {
+        let product = parse_json_line(r#"{
+            "code": "8850000000010", "product_name": "Crackers", "product_name_th": "แครกเกอร์",
+            "brands": "Brand A, Brand B", "countries_tags": ["en:thailand"], "serving_quantity": "50",
+            "nutriments": {
+                "energy-kj_100g": 1674, "fat_100g": 10, "saturated-fat_100g": 4, "carbohydrates_100g": 60,
+                "sugars_100g": 8, "proteins_100g": 9, "salt_100g": 1.25, "vitamin-a_100g": 0.0003
+            }
+        }"#).unwrap();
+        assert!(product.sold_in_thailand());
+
+        let form = form(&product);
+        assert_eq!(form.name, "แครกเกอร์");
+        assert_eq!(form.brand.as_deref(), Some("Brand A"));
+        assert_eq!(form.serving_size_grams, Some(50.0));
+        assert_eq!(form.serving_unit, ServingUnit::G);
+        // 1674 kJ / 4.184 = 400 kcal ต่อ 100 g → 200 kcal ต่อ 50 g
+        assert_eq!(form.calories, 200);
+        assert_eq!((form.fat, form.saturated_fat, form.carbs, form.sugar, form.protein), (5.0, 2.0, 30.0, 4.0, 4.5));
+        // เกลือ 1.25 g × 400 = โซเดียม 500 mg ต่อ 100 g
+        assert_eq!(form.sodium, 250.0);
+        // 0.0003 g = 300 µg ต่อ 100 g
+        assert_eq!(form.vitamin_a, Some(150.0));
+        assert_eq!(form.cholesterol, 0.0);
+        assert_eq!(form.barcodes, vec!["8850000000010".to_string()]);
+    }
+
+    #[test]
+    fn sodium_in_grams_becomes_milligrams() {
+        let product = parse_json_line(r#"{
+            "code": "8850000000027", "product_name": "Soup", "countries_tags": ["en:thailand"],
+            "nutriments": {
+                "energy-kcal_100g": 40, "fat_100g": 1, "saturated-fat_100g": 0.5, "carbohydrates_100g": 5,
+                "sugars_100g": 1, "proteins_100g": 2, "sodium_100g": 0.4, "salt_100g": 99
+            }
+        }"#).unwrap();
+        let form = form(&product);
+        // ไม่มี serving_quantity: serving = 100 g
+        assert_eq!(form.serving_size_grams, Some(100.0));
+        assert_eq!(form.calories, 40);
+        assert_eq!(form.sodium, 400.0);
+    }
+
+    #[test]
+    fn tab_separated_row_falls_back_to_energy_in_kj() {
+        let path = std::env::temp_dir().join(format!("off-import-test-{}.csv", std::process::id()));
+        let columns = [
+            "code", "product_name", "countries_tags", "serving_quantity", "serving_quantity_unit", "energy-kcal_100g",
+            "energy_100g", "fat_100g", "saturated-fat_100g", "carbohydrates_100g", "sugars_100g", "proteins_100g", "salt_100g",
+        ];
+        let row = [
+            "8850000000034", "Green \"tea\"", "en:laos,en:thailand", "250", "ml", "",
+            "180", "0", "0", "10", "10", "0", "0.05",
+        ];
+        std::fs::write(&path, format!("{}\n{}\n", columns.join("\t"), row.join("\t"))).unwrap();
+        let rows: Vec<_> = csv_rows(path.to_str().unwrap()).unwrap().collect();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(rows.len(), 1);
+        let (line, product) = &rows[0];
+        assert_eq!(*line, 2);
+        let product = product.as_ref().unwrap();
+        // export แบบ tab ไม่ใช้ quote จึงเก็บเครื่องหมาย " ไว้ตามเดิม
+        assert_eq!(product.product_name.as_deref(), Some("Green \"tea\""));
+        assert_eq!(product.countries_tags, vec!["en:laos", "en:thailand"]);
+        assert!(product.sold_in_thailand());
+
+        let form = form(product);
+        assert_eq!(form.serving_unit, ServingUnit::Ml);
+        // 180 kJ / 4.184 × 2.5 = 107.6 kcal
+        assert_eq!(form.calories, 108);
+        assert_eq!(form.sugar, 25.0);
+        // เกลือ 0.05 g × 400 = โซเดียม 20 mg ต่อ 100 ml
+        assert_eq!(form.sodium, 50.0);
+    }
+
+    #[test]
+    fn only_products_sold_in_thailand_are_imported() {
+        let product = parse_json_line(r#"{"code": "3017620422003", "countries_tags": ["en:france", "en:thailand-border"]}"#).unwrap();
+        assert!(!product.sold_in_thailand());
+    }
+
+    #[test]
+    fn missing_nutriments_are_reported_per_field() {
+        let product = parse_json_line(r#"{"code": "8850000000034", "countries_tags": ["en:thailand"], "nutriments": {"energy-kcal_100g": 10}}"#).unwrap();
+        let barcode = Barcode::parse(&product.code).unwrap();
+        let missing: Vec<String> = product_form(&product, &barcode, Vec::new()).unwrap_err()
+            .into_iter()
+            .map(|error| error.field)
+            .collect();
+        assert!(missing.contains(&"nutriments.sodium_100g".to_string()));
+        assert!(missing.contains(&"product_name".to_string()));
+        assert!(!missing.contains(&"nutriments.energy-kcal_100g".to_string()));
+    }
+
+    #[test]
+    fn splits_top_level_ingredients_only() {
+        assert_eq!(
+            ingredients("Wheat flour (_wheat_, salt (iodized)), palm oil; [sugar, glucose], _milk_ powder."),
+            vec!["Wheat flour (wheat, salt (iodized))", "palm oil", "[sugar, glucose]", "milk powder"],
+        );
+        assert!(ingredients(" , ;").is_empty());
+    }
+}
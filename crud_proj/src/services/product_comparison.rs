@@ -0,0 +1,149 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    errors::{AppError, FieldError},
+    models::{
+        nutrition::{round_to, NutrientAmounts, NutrientBasis, NutritionFacts},
+        product_comparison::{
+            Better, ComparedProduct, ComparedValue, MetricComparison, ProductComparison, ProductComparisonRequest,
+        },
+        products::ProductResponse,
+    },
+    repositories::product_repositories::ProductRepositoryTrait,
+};
+
+type NutrientValue = fn(&NutrientAmounts) -> Option<f32>;
+
+// หน่วยตรงกับ column ใน `products` (ดู `nutrition_reference`)
+const COMPARED_NUTRIENTS: [(&str, &str, Better, NutrientValue); 13] = [
+    ("calories", "kcal", Better::Lower, |n| Some(n.calories)),
+    ("fat", "g", Better::Lower, |n| Some(n.fat)),
+    ("saturated_fat", "g", Better::Lower, |n| Some(n.saturated_fat)),
+    ("sugar", "g", Better::Lower, |n| Some(n.sugar)),
+    ("sodium", "mg", Better::Lower, |n| Some(n.sodium)),
+    ("cholesterol", "mg", Better::Lower, |n| Some(n.cholesterol)),
+    ("carbs", "g", Better::Lower, |n| Some(n.carbs)),
+    ("protein", "g", Better::Higher, |n| Some(n.protein)),
+    ("fibre", "g", Better::Higher, |n| n.fibre),
+    ("vitamin_a", "µg", Better::Higher, |n| n.vitamin_a),
+    ("vitamin_c", "mg", Better::Higher, |n| n.vitamin_c),
+    ("vitamin_b1", "mg", Better::Higher, |n| n.vitamin_b1),
+    ("calcium", "mg", Better::Higher, |n| n.calcium),
+];
+
+fn compared_product(product: &ProductResponse, basis: NutrientBasis) -> ComparedProduct {
+    let facts = NutritionFacts::new(product.nutrient_amounts(), product.serving_size_grams, product.serving_unit);
+    let price_per_100 = product.serving_size_grams
+        .filter(|size| size.is_finite() && *size > 0.0 && product.price > 0.0)
+        .map(|size| round_to(product.price * 100.0 / size, 2));
+    let nutrients = match basis {
+        NutrientBasis::Serving => facts.per_serving,
+        // ชิ้นที่ไม่มี per_100 ถูกปฏิเสธตั้งแต่ตอนโหลด
+        NutrientBasis::Per100 => facts.per_100.unwrap_or_default(),
+    };
+    ComparedProduct {
+        id: product.id,
+        name: product.name.clone(),
+        brand: product.brand.clone(),
+        serving_size: product.serving_size_grams,
+        serving_unit: product.serving_unit,
+        price: product.price,
+        price_per_100,
+        nutrients,
+    }
+}
+
+// ค่าเท่ากันให้ชิ้นที่มาก่อนในคำขอเป็นทั้ง best และ worst
+fn compare_metric(metric: &str, unit: &str, better: Better, values: &[(Uuid, Option<f32>)]) -> MetricComparison {
+    let known: Vec<ComparedValue> = values.iter()
+        .filter_map(|(product_id, value)| value.map(|value| ComparedValue { product_id: *product_id, value }))
+        .collect();
+    let lowest = known.iter().reduce(|lowest, value| if value.value < lowest.value { value } else { lowest });
+    let highest = known.iter().reduce(|highest, value| if value.value > highest.value { value } else { highest });
+    let (best, worst) = match better {
+        Better::Lower => (lowest, highest),
+        Better::Higher => (highest, lowest),
+    };
+    MetricComparison {
+        metric: metric.to_string(),
+        unit: unit.to_string(),
+        better,
+        difference: best.zip(worst).map(|(best, worst)| round_to((best.value - worst.value).abs(), 2)),
+        best: best.cloned(),
+        worst: worst.cloned(),
+    }
+}
+
+fn compare(products: &[ProductResponse], basis: NutrientBasis) -> ProductComparison {
+    let products: Vec<ComparedProduct> = products.iter().map(|product| compared_product(product, basis)).collect();
+    let nutrients = COMPARED_NUTRIENTS.iter()
+        .map(|(metric, unit, better, value)| {
+            let values: Vec<(Uuid, Option<f32>)> = products.iter().map(|product| (product.id, value(&product.nutrients))).collect();
+            compare_metric(metric, unit, *better, &values)
+        })
+        .collect();
+    let prices: Vec<(Uuid, Option<f32>)> = products.iter().map(|product| (product.id, product.price_per_100)).collect();
+    ProductComparison {
+        basis,
+        price_per_100: compare_metric("price_per_100", "THB", Better::Lower, &prices),
+        nutrients,
+        products,
+    }
+}
+
+#[async_trait]
+pub trait ProductComparisonServiceTrait: Send + Sync {
+    async fn compare_products(&self, request: ProductComparisonRequest) -> Result<ProductComparison, AppError>;
+}
+
+pub struct ProductComparisonService {
+    products: Arc<dyn ProductRepositoryTrait + Send + Sync>,
+}
+
+impl ProductComparisonService {
+    pub fn new(products: Arc<dyn ProductRepositoryTrait + Send + Sync>) -> Self {
+        Self { products }
+    }
+}
+
+#[async_trait]
+impl ProductComparisonServiceTrait for ProductComparisonService {
+    async fn compare_products(&self, request: ProductComparisonRequest) -> Result<ProductComparison, AppError> {
+        request.validate()?;
+        let basis = request.basis();
+
+        // โหลดครบทุกชิ้นก่อน เพื่อรายงาน id ที่ไม่พบทั้งหมดใน error เดียว
+        let mut products = Vec::with_capacity(request.product_ids.len());
+        let mut missing = Vec::new();
+        for id in &request.product_ids {
+            match self.products.get_product_by_id(*id).await {
+                Ok(product) => products.push(product),
+                Err(AppError::NotFound) => missing.push(*id),
+                Err(e) => return Err(e),
+            }
+        }
+        if !missing.is_empty() {
+            warn!("Comparison of unknown products: {:?}", missing);
+            return Err(AppError::UnknownProducts(missing));
+        }
+
+        if basis.requires_serving_size() {
+            let violations: Vec<FieldError> = products.iter()
+                .enumerate()
+                .filter(|(_, product)| !product.serving_size_grams.is_some_and(|size| size.is_finite() && size > 0.0))
+                .map(|(index, product)| FieldError::new(
+                    format!("product_ids[{}]", index),
+                    format!("product {} has no serving size, so it cannot be compared per 100; use basis=serving", product.id),
+                ))
+                .collect();
+            if !violations.is_empty() {
+                return Err(AppError::InvalidFields(violations));
+            }
+        }
+
+        Ok(compare(&products, basis))
+    }
+}
@@ -1,60 +1,150 @@
 use std::sync::Arc;
 use async_trait::async_trait;
+use tokio::sync::OnceCell;
+use tracing::info;
 use uuid::Uuid;
 
 use crate::{
     errors::AppError, 
-    models::{pagination::Pagination, products::{ ProductForm, ProductResponse}},
-    repositories::product_repositories::{ProductRepository, ProductRepositoryTrait}
+    models::{barcodes::Barcode, front_of_pack::{warning_profile, FrontOfPackQuery, WarningProfile}, ingredients::{Additive, NovaClassification}, nutrition::{DailyValueQuery, NutrientAmounts}, pagination::{PageResult, Pagination}, product_filter::ProductFilter, products::{DerivedScores, DerivedScoresReport, ProductForm, ProductPatch, ProductResponse}},
+    repositories::{additive_repositories::{AdditiveRepository, AdditiveRepositoryTrait}, product_repositories::{ProductRepository, ProductRepositoryTrait}},
+    services::{front_of_pack, nova, nutri_score, nutrition_reference, value_metrics},
 };
 
 #[async_trait]
 pub trait ProductServiceTrait: Send + Sync {
-    async fn list_products(&self, pagination: Pagination) -> Result<Vec<ProductResponse>, AppError>;
+    async fn list_products(&self, pagination: Pagination, filter: ProductFilter, daily_values: DailyValueQuery) -> Result<PageResult<ProductResponse>, AppError>;
     async fn add_product(&self, product: ProductForm) -> Result<ProductResponse, AppError>;
-    async fn get_product_from_id(&self, id: Uuid) -> Result<Option<ProductResponse>, AppError>;
+    async fn get_product_from_id(&self, id: Uuid, daily_values: DailyValueQuery, front_of_pack: FrontOfPackQuery) -> Result<Option<ProductResponse>, AppError>;
+    async fn get_product_from_barcode(&self, code: &str, daily_values: DailyValueQuery, front_of_pack: FrontOfPackQuery) -> Result<Option<ProductResponse>, AppError>;
     async fn update_product_from_id(&self, id: Uuid, product: ProductForm) -> Result<Option<ProductResponse>, AppError>;
+    async fn patch_product_from_id(&self, id: Uuid, patch: ProductPatch) -> Result<Option<ProductResponse>, AppError>;
     async fn delete_product_from_id(&self, id: Uuid) -> Result<(), AppError>;
+    async fn recompute_derived_scores(&self) -> Result<DerivedScoresReport, AppError>;
 }
 
 pub struct ProductService {
     repo: Arc<dyn ProductRepositoryTrait + Send + Sync>,
+    additives: Arc<dyn AdditiveRepositoryTrait + Send + Sync>,
+    // registry ของ additive โหลดครั้งเดียวต่อ service (ต่อ request หรือต่อ batch เช่น recompute/import)
+    additive_registry: OnceCell<Vec<Additive>>,
+}
+
+// NOVA group และ Nutri-Score คำนวณใหม่ทุกครั้งที่ product ถูกเขียน
+fn derive(product: &ProductForm, additives: &[Additive]) -> DerivedScores {
+    let nova = if product.ingredients.is_empty() {
+        NovaClassification::default()
+    } else {
+        nova::classify(&product.ingredients, additives)
+    };
+    DerivedScores {
+        nova,
+        nutri_score: nutri_score::compute(product),
+        nutrient_density: value_metrics::nutrient_density(&product.nutrient_amounts()),
+    }
 }
 
 #[allow(dead_code)]
 impl ProductService {
-    pub fn new(repo: Arc<dyn ProductRepositoryTrait + Send + Sync>) -> Self {
-        Self { repo }
+    pub fn new(
+        repo: Arc<dyn ProductRepositoryTrait + Send + Sync>,
+        additives: Arc<dyn AdditiveRepositoryTrait + Send + Sync>,
+    ) -> Self {
+        Self { repo, additives, additive_registry: OnceCell::new() }
     }
     
-    pub fn with_repository(repo: ProductRepository) -> Self {
+    pub fn with_repository(repo: ProductRepository, additives: AdditiveRepository) -> Self {
         Self {
             repo: Arc::new(repo),
+            additives: Arc::new(additives),
+            additive_registry: OnceCell::new(),
         }
     }
+
+    async fn additive_registry(&self) -> Result<&[Additive], AppError> {
+        self.additive_registry
+            .get_or_try_init(|| self.additives.get_additive_list())
+            .await
+            .map(Vec::as_slice)
+    }
+}
+
+// เติม block ที่คำนวณจากค่าสารอาหาร: nutrition (per serving/per 100), ฉลาก front-of-pack
+// และความคุ้มค่าต่อบาทเสมอ ส่วน %DV เมื่อ client ขอ
+pub fn present(product: ProductResponse, reference: Option<&NutrientAmounts>, profile: &WarningProfile) -> ProductResponse {
+    let mut product = product.with_nutrition();
+    product.value = value_metrics::metrics(&product);
+    if let Some(nutrition) = &product.nutrition {
+        product.front_of_pack = Some(front_of_pack::assess(nutrition, profile));
+        if let Some(reference) = reference {
+            product.daily_values = Some(nutrition_reference::daily_values(nutrition, reference));
+        }
+    }
+    product
 }
 
 #[async_trait]
 impl ProductServiceTrait for ProductService {
-    async fn list_products(&self, pagination: Pagination) -> Result<Vec<ProductResponse>, AppError> {
-        self.repo.get_product_list(pagination).await
+    async fn list_products(&self, pagination: Pagination, filter: ProductFilter, daily_values: DailyValueQuery) -> Result<PageResult<ProductResponse>, AppError> {
+        pagination.validate()?;
+        filter.validate()?;
+        let reference = nutrition_reference::requested_reference(&daily_values)?;
+        let profile = filter.warning_profile()?;
+        let mut page = self.repo.get_product_list(pagination, filter).await?;
+        page.items = page.items.into_iter()
+            .map(|product| present(product, reference.as_ref(), profile))
+            .collect();
+        Ok(page)
     }
     
     async fn add_product(&self, product: ProductForm) -> Result<ProductResponse, AppError> {
-        self.repo.create_product_with_categories(product).await
+        product.validate()?;
+        let scores = derive(&product, self.additive_registry().await?);
+        let product = self.repo.create_product_with_categories(product, scores).await?;
+        Ok(present(product, None, warning_profile(None)?))
     }
     
-    async fn get_product_from_id(&self, id: Uuid) -> Result<Option<ProductResponse>, AppError> {
+    async fn get_product_from_id(&self, id: Uuid, daily_values: DailyValueQuery, front_of_pack: FrontOfPackQuery) -> Result<Option<ProductResponse>, AppError> {
+        let reference = nutrition_reference::requested_reference(&daily_values)?;
+        let profile = warning_profile(front_of_pack.warning_profile.as_deref())?;
         match self.repo.get_product_by_id(id).await {
-            Ok(product) => Ok(Some(product)),
+            Ok(product) => Ok(Some(present(product, reference.as_ref(), profile))),
             Err(AppError::NotFound) => Ok(None),
             Err(e) => Err(e),
         }
     }
     
+    async fn get_product_from_barcode(&self, code: &str, daily_values: DailyValueQuery, front_of_pack: FrontOfPackQuery) -> Result<Option<ProductResponse>, AppError> {
+        let barcode = Barcode::parse(code)
+            .map_err(|message| AppError::ValidationError(format!("barcode {}", message)))?;
+        let reference = nutrition_reference::requested_reference(&daily_values)?;
+        let profile = warning_profile(front_of_pack.warning_profile.as_deref())?;
+        match self.repo.get_product_by_barcode(barcode.as_str()).await {
+            Ok(product) => Ok(Some(present(product, reference.as_ref(), profile))),
+            Err(AppError::NotFound) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
     async fn update_product_from_id(&self, id: Uuid, product: ProductForm) -> Result<Option<ProductResponse>, AppError> {
-        match self.repo.update_product_by_id(id, product).await {
-            Ok(product) => Ok(Some(product)),
+        product.validate()?;
+        let scores = derive(&product, self.additive_registry().await?);
+        match self.repo.update_product_by_id(id, product, scores).await {
+            Ok(product) => Ok(Some(present(product, None, warning_profile(None)?))),
+            Err(AppError::NotFound) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+    
+    async fn patch_product_from_id(&self, id: Uuid, patch: ProductPatch) -> Result<Option<ProductResponse>, AppError> {
+        // Validate and score the product as it will look after the patch, from the row the repository locked
+        let additives = self.additive_registry().await?;
+        let rescore = |merged: &ProductForm| {
+            merged.validate()?;
+            Ok(derive(merged, additives))
+        };
+        match self.repo.patch_product_by_id(id, patch, &rescore).await {
+            Ok(product) => Ok(Some(present(product, None, warning_profile(None)?))),
             Err(AppError::NotFound) => Ok(None),
             Err(e) => Err(e),
         }
@@ -67,4 +157,17 @@ impl ProductServiceTrait for ProductService {
         }
         Ok(())
     }
+
+    // สำหรับ product ที่มีอยู่ก่อนเพิ่ม score ใหม่ หรือหลังเปลี่ยนตารางคะแนน
+    async fn recompute_derived_scores(&self) -> Result<DerivedScoresReport, AppError> {
+        let entries = self.repo.get_products_with_food_groups().await?;
+        let additives = self.additive_registry().await?;
+        let scores: Vec<(Uuid, DerivedScores)> = entries.iter()
+            .map(|entry| (entry.product.id, derive(&ProductPatch::default().merged_with(&entry.product), additives)))
+            .collect();
+        let nutri_scored = scores.iter().filter(|(_, scores)| scores.nutri_score.is_some()).count();
+        self.repo.save_derived_scores(&scores).await?;
+        info!("Recomputed derived scores of {} products ({} with Nutri-Score)", entries.len(), nutri_scored);
+        Ok(DerivedScoresReport { products: entries.len(), nutri_scored })
+    }
 }
\ No newline at end of file
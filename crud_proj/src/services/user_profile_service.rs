@@ -0,0 +1,211 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    errors::AppError,
+    models::{
+        nutrition::round_to,
+        user_profiles::{DailyTargets, Goal, Sex, UserProfile, UserProfileForm, UserProfileResponse},
+    },
+    repositories::user_profile_repositories::UserProfileRepositoryTrait,
+    services::nutrition_reference::{self, MAX_ENERGY_TARGET_KCAL},
+};
+/*
+เป้าหมายพลังงานและสารอาหารต่อวันของแต่ละคน
+ - BMR: Mifflin-St Jeor = 10 × น้ำหนัก (kg) + 6.25 × ส่วนสูง (cm) − 5 × อายุ + 5 (ชาย) / − 161 (หญิง)
+ - TDEE = BMR × ตัวคูณของ `activity_level`
+ - พลังงานเป้าหมาย = TDEE − 500 kcal (lose) / + 300 kcal (gain) แต่ไม่ต่ำกว่า 1,500 (ชาย) / 1,200 (หญิง) kcal
+ - โปรตีนคิดตามน้ำหนักตัว, ไขมัน/น้ำตาล/โซเดียมมาจาก Thai RDI ที่ปรับตามพลังงานเป้าหมาย
+   (`nutrition_reference::reference_intakes`) และคาร์โบไฮเดรตคือพลังงานที่เหลือ
+*/
+
+const LOSE_DEFICIT_KCAL: f32 = 500.0;
+const GAIN_SURPLUS_KCAL: f32 = 300.0;
+
+const KCAL_PER_G_PROTEIN: f32 = 4.0;
+const KCAL_PER_G_CARBS: f32 = 4.0;
+const KCAL_PER_G_FAT: f32 = 9.0;
+
+pub fn bmr_kcal(profile: &UserProfile) -> f32 {
+    let sex_offset = match profile.sex {
+        Sex::Male => 5.0,
+        Sex::Female => -161.0,
+    };
+    10.0 * profile.weight_kg + 6.25 * profile.height_cm - 5.0 * profile.age as f32 + sex_offset
+}
+
+// พลังงานต่อวันขั้นต่ำที่ไม่ควรตั้งเป้าต่ำกว่าโดยไม่มีผู้เชี่ยวชาญดูแล
+fn minimum_energy_kcal(sex: Sex) -> f32 {
+    match sex {
+        Sex::Male => 1500.0,
+        Sex::Female => 1200.0,
+    }
+}
+
+// ลด/เพิ่มน้ำหนักต้องการโปรตีนมากขึ้นเพื่อรักษา/สร้างกล้ามเนื้อ
+fn protein_g_per_kg(goal: Goal) -> f32 {
+    match goal {
+        Goal::Lose => 1.6,
+        Goal::Maintain => 1.0,
+        Goal::Gain => 1.6,
+    }
+}
+
+pub fn daily_targets(profile: &UserProfile) -> Result<DailyTargets, AppError> {
+    let bmr = bmr_kcal(profile);
+    let tdee = bmr * profile.activity_level.factor();
+    let adjusted = match profile.goal {
+        Goal::Lose => tdee - LOSE_DEFICIT_KCAL,
+        Goal::Maintain => tdee,
+        Goal::Gain => tdee + GAIN_SURPLUS_KCAL,
+    };
+    let energy = adjusted.max(minimum_energy_kcal(profile.sex)).min(MAX_ENERGY_TARGET_KCAL).round();
+
+    let reference = nutrition_reference::reference_intakes(energy)?;
+    let protein = profile.weight_kg * protein_g_per_kg(profile.goal);
+    let fat = reference.fat;
+    let carbs = ((energy - protein * KCAL_PER_G_PROTEIN - fat * KCAL_PER_G_FAT) / KCAL_PER_G_CARBS).max(0.0);
+
+    Ok(DailyTargets {
+        bmr_kcal: bmr.round(),
+        tdee_kcal: tdee.round(),
+        energy_kcal: energy,
+        protein_g: round_to(protein, 1),
+        fat_g: round_to(fat, 1),
+        carbs_g: round_to(carbs, 1),
+        sugar_g: round_to(reference.sugar, 1),
+        sodium_mg: reference.sodium,
+    })
+}
+
+fn with_targets(profile: UserProfile) -> Result<UserProfileResponse, AppError> {
+    let targets = daily_targets(&profile)?;
+    Ok(UserProfileResponse { profile, targets })
+}
+
+#[async_trait]
+pub trait UserProfileServiceTrait: Send + Sync {
+    async fn list_user_profiles(&self) -> Result<Vec<UserProfileResponse>, AppError>;
+    async fn add_user_profile(&self, profile: UserProfileForm) -> Result<UserProfileResponse, AppError>;
+    async fn get_user_profile_from_id(&self, id: Uuid) -> Result<Option<UserProfileResponse>, AppError>;
+    async fn update_user_profile_from_id(&self, id: Uuid, profile: UserProfileForm) -> Result<Option<UserProfileResponse>, AppError>;
+    async fn delete_user_profile_from_id(&self, id: Uuid) -> Result<(), AppError>;
+}
+
+pub struct UserProfileService {
+    repo: Arc<dyn UserProfileRepositoryTrait + Send + Sync>,
+}
+
+impl UserProfileService {
+    pub fn new(repo: Arc<dyn UserProfileRepositoryTrait + Send + Sync>) -> Self {
+        Self { repo }
+    }
+}
+
+#[async_trait]
+impl UserProfileServiceTrait for UserProfileService {
+    async fn list_user_profiles(&self) -> Result<Vec<UserProfileResponse>, AppError> {
+        self.repo.get_user_profile_list().await?
+            .into_iter()
+            .map(with_targets)
+            .collect()
+    }
+
+    async fn add_user_profile(&self, profile: UserProfileForm) -> Result<UserProfileResponse, AppError> {
+        profile.validate()?;
+        with_targets(self.repo.create_user_profile(profile).await?)
+    }
+
+    async fn get_user_profile_from_id(&self, id: Uuid) -> Result<Option<UserProfileResponse>, AppError> {
+        match self.repo.get_user_profile_by_id(id).await {
+            Ok(profile) => with_targets(profile).map(Some),
+            Err(AppError::NotFound) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn update_user_profile_from_id(&self, id: Uuid, profile: UserProfileForm) -> Result<Option<UserProfileResponse>, AppError> {
+        profile.validate()?;
+        match self.repo.update_user_profile_by_id(id, profile).await {
+            Ok(profile) => with_targets(profile).map(Some),
+            Err(AppError::NotFound) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn delete_user_profile_from_id(&self, id: Uuid) -> Result<(), AppError> {
+        let affected_rows = self.repo.delete_user_profile_by_id(id).await?;
+        if affected_rows == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::user_profiles::ActivityLevel;
+
+    fn profile(sex: Sex, age: i16, height_cm: f32, weight_kg: f32, activity_level: ActivityLevel, goal: Goal) -> UserProfile {
+        UserProfile {
+            id: Uuid::nil(),
+            name: "test".to_string(),
+            age,
+            sex,
+            height_cm,
+            weight_kg,
+            activity_level,
+            goal,
+            timezone: "Asia/Bangkok".to_string(),
+            daily_budget: None,
+        }
+    }
+
+    #[test]
+    fn bmr_follows_mifflin_st_jeor() {
+        // 10 × 80 + 6.25 × 180 − 5 × 30 + 5
+        let male = profile(Sex::Male, 30, 180.0, 80.0, ActivityLevel::Moderate, Goal::Maintain);
+        assert_eq!(bmr_kcal(&male), 1780.0);
+        // 10 × 60 + 6.25 × 165 − 5 × 30 − 161
+        let female = profile(Sex::Female, 30, 165.0, 60.0, ActivityLevel::Moderate, Goal::Maintain);
+        assert_eq!(bmr_kcal(&female), 1320.25);
+    }
+
+    #[test]
+    fn energy_target_applies_activity_and_goal() {
+        let maintain = daily_targets(&profile(Sex::Male, 30, 180.0, 80.0, ActivityLevel::Moderate, Goal::Maintain)).unwrap();
+        assert_eq!((maintain.bmr_kcal, maintain.tdee_kcal, maintain.energy_kcal), (1780.0, 2759.0, 2759.0));
+
+        let lose = daily_targets(&profile(Sex::Male, 30, 180.0, 80.0, ActivityLevel::Moderate, Goal::Lose)).unwrap();
+        assert_eq!(lose.energy_kcal, 2259.0);
+        assert_eq!(lose.protein_g, 128.0);
+
+        let gain = daily_targets(&profile(Sex::Male, 30, 180.0, 80.0, ActivityLevel::Moderate, Goal::Gain)).unwrap();
+        assert_eq!(gain.energy_kcal, 3059.0);
+    }
+
+    #[test]
+    fn energy_target_never_goes_below_the_floor() {
+        // BMR 826.5 × 1.2 − 500 ≈ 492 kcal
+        let female = daily_targets(&profile(Sex::Female, 70, 150.0, 40.0, ActivityLevel::Sedentary, Goal::Lose)).unwrap();
+        assert_eq!(female.energy_kcal, 1200.0);
+        // BMR 1023.75 × 1.2 − 500 ≈ 729 kcal
+        let male = daily_targets(&profile(Sex::Male, 80, 155.0, 45.0, ActivityLevel::Sedentary, Goal::Lose)).unwrap();
+        assert_eq!(male.energy_kcal, 1500.0);
+    }
+
+    #[test]
+    fn carbs_are_the_remaining_energy_and_never_negative() {
+        let targets = daily_targets(&profile(Sex::Male, 30, 180.0, 80.0, ActivityLevel::Moderate, Goal::Maintain)).unwrap();
+        let remainder = (targets.energy_kcal - targets.protein_g * KCAL_PER_G_PROTEIN - targets.fat_g * KCAL_PER_G_FAT) / KCAL_PER_G_CARBS;
+        assert!((targets.carbs_g - remainder).abs() < 0.1);
+
+        // โปรตีน 240 g (960 kcal) + ไขมันตาม RDI เกินเป้าหมาย 1,200 kcal ที่ถูกยกขึ้นเป็นขั้นต่ำ
+        let heavy = daily_targets(&profile(Sex::Female, 120, 50.0, 150.0, ActivityLevel::Sedentary, Goal::Lose)).unwrap();
+        assert_eq!(heavy.energy_kcal, 1200.0);
+        assert!(heavy.protein_g * KCAL_PER_G_PROTEIN + heavy.fat_g * KCAL_PER_G_FAT > heavy.energy_kcal);
+        assert_eq!(heavy.carbs_g, 0.0);
+    }
+}
@@ -0,0 +1,108 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+
+use crate::{
+    errors::AppError,
+    models::{
+        front_of_pack::warning_profile,
+        nutrition::{round_to, NutrientAmounts},
+        products::ProductResponse,
+        value_metrics::{BestValueEntry, BestValueQuery, BestValueRanking, ValueMetrics},
+    },
+    repositories::{category_repositories::CategoryRepositoryTrait, product_repositories::ProductRepositoryTrait},
+    services::{nutrition_reference, product_service::present},
+};
+/*
+คำนวณความคุ้มค่าต่อบาทและจัดอันดับ product ที่คุ้มที่สุดใน category
+ - `nutrient_density` คำนวณเทียบ Thai RDI (2,000 kcal) จึงเทียบกันได้ข้าม product
+ - vitamin/แร่ธาตุ/ใยอาหารที่ไม่ได้กรอกนับเป็น 0 (ไม่เดาค่า)
+ - อันดับเรียงจากค่าที่เก็บใน DB (`nutrient_density` คำนวณทุกครั้งที่ product ถูกเขียน)
+*/
+
+// %RDI สูงสุดที่สารอาหารที่ดีแต่ละตัวนับได้ เพื่อไม่ให้สารอาหารตัวเดียวครอบงำคะแนน
+const QUALIFYING_PERCENT_CAP: f32 = 100.0;
+
+/// NRF-style score of one serving: capped `%RDI` of protein, fibre, vitamins A, C, B1 and calcium
+/// minus `%RDI` of saturated fat, sugar and sodium.
+pub fn nutrient_density(amounts: &NutrientAmounts) -> f32 {
+    let rdi = nutrition_reference::thai_rdi();
+    let percent = |value: f32, reference: f32| (value / reference * 100.0).clamp(0.0, QUALIFYING_PERCENT_CAP);
+    let optional = |value: Option<f32>, reference: Option<f32>| {
+        value.zip(reference).map(|(value, reference)| percent(value, reference)).unwrap_or(0.0)
+    };
+    let qualifying = percent(amounts.protein, rdi.protein)
+        + optional(amounts.fibre, rdi.fibre)
+        + optional(amounts.vitamin_a, rdi.vitamin_a)
+        + optional(amounts.vitamin_c, rdi.vitamin_c)
+        + optional(amounts.vitamin_b1, rdi.vitamin_b1)
+        + optional(amounts.calcium, rdi.calcium);
+    let limiting = (amounts.saturated_fat / rdi.saturated_fat
+        + amounts.sugar / rdi.sugar
+        + amounts.sodium / rdi.sodium) * 100.0;
+    round_to(qualifying - limiting, 1)
+}
+
+/// Nutrients per baht of `price`, or `None` when the product has no price.
+pub fn metrics(product: &ProductResponse) -> Option<ValueMetrics> {
+    if !(product.price.is_finite() && product.price > 0.0) {
+        return None;
+    }
+    let density = nutrient_density(&product.nutrient_amounts());
+    Some(ValueMetrics {
+        protein_per_baht: round_to(product.protein / product.price, 3),
+        kcal_per_baht: round_to(product.calories as f32 / product.price, 2),
+        nutrient_density: density,
+        nutrient_density_per_baht: round_to(density / product.price, 3),
+    })
+}
+
+#[async_trait]
+pub trait BestValueServiceTrait: Send + Sync {
+    async fn best_value_in_category(&self, category_id: i32, query: BestValueQuery) -> Result<Option<BestValueRanking>, AppError>;
+}
+
+pub struct BestValueService {
+    products: Arc<dyn ProductRepositoryTrait + Send + Sync>,
+    categories: Arc<dyn CategoryRepositoryTrait + Send + Sync>,
+}
+
+impl BestValueService {
+    pub fn new(
+        products: Arc<dyn ProductRepositoryTrait + Send + Sync>,
+        categories: Arc<dyn CategoryRepositoryTrait + Send + Sync>,
+    ) -> Self {
+        Self { products, categories }
+    }
+}
+
+#[async_trait]
+impl BestValueServiceTrait for BestValueService {
+    async fn best_value_in_category(&self, category_id: i32, query: BestValueQuery) -> Result<Option<BestValueRanking>, AppError> {
+        query.validate()?;
+        let category = match self.categories.get_category_by_id(category_id).await {
+            Ok(category) => category,
+            Err(AppError::NotFound) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        let profile = warning_profile(None)?;
+        let items = self.products.get_best_value_products(category_id, &query).await?
+            .into_iter()
+            .map(|product| present(product, None, profile))
+            .enumerate()
+            .map(|(index, product)| BestValueEntry {
+                rank: index + 1,
+                score: product.value.as_ref().map(|value| query.metric.value(value)).unwrap_or(0.0),
+                product,
+            })
+            .collect();
+
+        Ok(Some(BestValueRanking {
+            category,
+            metric: query.metric,
+            non_upf: query.non_upf,
+            healthier: query.healthier,
+            items,
+        }))
+    }
+}
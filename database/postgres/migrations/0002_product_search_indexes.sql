@@ -0,0 +1,12 @@
+-- Trigram search over product name, brand and category names.
+-- ภาษาไทยไม่มีการเว้นวรรคระหว่างคำ tsvector จึงตัดคำไม่ได้ ใช้ trigram แทน
+-- ซึ่งรองรับการพิมพ์ชื่อบางส่วนและพิมพ์ผิดเล็กน้อย
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+-- GIN trigram indexes serve both ILIKE '%...%' and the word-similarity operator (<%)
+CREATE INDEX IF NOT EXISTS idx_products_name_trgm ON products USING GIN (name gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS idx_products_brand_trgm ON products USING GIN (brand gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS idx_categories_name_trgm ON categories USING GIN (name gin_trgm_ops);
+
+-- Lookup of products by category (category filter and category-name matches)
+CREATE INDEX IF NOT EXISTS idx_product_category_category_id ON product_category (category_id);
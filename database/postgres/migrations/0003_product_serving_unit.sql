@@ -0,0 +1,5 @@
+-- หน่วยของ serving_size_grams: 'g' สำหรับอาหาร, 'ml' สำหรับเครื่องดื่ม
+-- ใช้บอกว่าค่าที่ normalize แล้วเป็นต่อ 100 g หรือ 100 ml
+ALTER TABLE products
+    ADD COLUMN IF NOT EXISTS serving_unit VARCHAR(2) NOT NULL DEFAULT 'g'
+        CONSTRAINT products_serving_unit_check CHECK (serving_unit IN ('g', 'ml'));
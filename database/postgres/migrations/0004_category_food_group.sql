@@ -0,0 +1,3 @@
+-- กลุ่มอาหารตามเกณฑ์สัญลักษณ์ทางเลือกสุขภาพ (Healthier Choice) ที่ใช้ประเมิน product ใน category นี้
+-- ค่าที่รับได้ตรวจสอบใน service (`healthier_choice::FoodGroup`) เพื่อให้เพิ่มกลุ่มใหม่ได้เมื่อเกณฑ์เปลี่ยนโดยไม่ต้อง migrate
+ALTER TABLE categories ADD COLUMN IF NOT EXISTS food_group VARCHAR(64);
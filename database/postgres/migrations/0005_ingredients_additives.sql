@@ -0,0 +1,51 @@
+-- ส่วนประกอบของ product ตามลำดับบนฉลาก
+CREATE TABLE IF NOT EXISTS product_ingredients (
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    position INT NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    PRIMARY KEY (product_id, position)
+);
+
+-- ทะเบียนวัตถุเจือปนอาหาร (E/INS number) และส่วนผสมทางอุตสาหกรรมที่บ่งชี้การแปรรูปตาม NOVA
+--  - `e_number`: NULL สำหรับส่วนผสมที่ไม่มีเลขกำกับ (เช่น maltodextrin)
+--  - `nova_group`: กลุ่ม NOVA ขั้นต่ำที่ product จะถูกจัดเมื่อพบ (4 = ultra-processed, 3 = processed)
+--  - `aliases`: ชื่อที่พบบนฉลากทั้งภาษาไทยและอังกฤษ ใช้จับคู่กับ ingredient
+CREATE TABLE IF NOT EXISTS additives (
+    id INT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    e_number VARCHAR(16) UNIQUE,
+    name VARCHAR(255) NOT NULL UNIQUE,
+    function VARCHAR(64) NOT NULL,
+    nova_group SMALLINT NOT NULL DEFAULT 4 CHECK (nova_group IN (3, 4)),
+    aliases TEXT[] NOT NULL DEFAULT ARRAY[]::TEXT[]
+);
+
+-- NOVA group ที่คำนวณจาก ingredient (NULL = ยังไม่มีข้อมูลส่วนประกอบ) และ ingredient ที่เป็นตัวกำหนด
+ALTER TABLE products
+    ADD COLUMN IF NOT EXISTS nova_group SMALLINT CHECK (nova_group BETWEEN 1 AND 4),
+    ADD COLUMN IF NOT EXISTS nova_markers JSONB NOT NULL DEFAULT '[]'::JSONB;
+
+CREATE INDEX IF NOT EXISTS idx_products_nova_group ON products (nova_group);
+
+INSERT INTO additives (e_number, name, function, nova_group, aliases) VALUES
+    ('E621', 'Monosodium glutamate', 'flavour_enhancer', 4, ARRAY['monosodium glutamate', 'msg', 'ผงชูรส', 'โมโนโซเดียมกลูตาเมต']),
+    ('E627', 'Disodium guanylate', 'flavour_enhancer', 4, ARRAY['disodium guanylate', 'ไดโซเดียมกัวไนเลต']),
+    ('E631', 'Disodium inosinate', 'flavour_enhancer', 4, ARRAY['disodium inosinate', 'ไดโซเดียมไอโนซิเนต']),
+    ('E635', 'Disodium 5''-ribonucleotides', 'flavour_enhancer', 4, ARRAY['ribonucleotide', 'ไรโบนิวคลีโอไทด์']),
+    ('E950', 'Acesulfame K', 'sweetener', 4, ARRAY['acesulfame', 'อะซีซัลเฟม']),
+    ('E951', 'Aspartame', 'sweetener', 4, ARRAY['aspartame', 'แอสปาร์แตม']),
+    ('E955', 'Sucralose', 'sweetener', 4, ARRAY['sucralose', 'ซูคราโลส']),
+    ('E960', 'Steviol glycosides', 'sweetener', 4, ARRAY['steviol', 'สตีวิออลไกลโคไซด์']),
+    ('E322', 'Lecithins', 'emulsifier', 4, ARRAY['lecithin', 'เลซิติน']),
+    ('E471', 'Mono- and diglycerides of fatty acids', 'emulsifier', 4, ARRAY['mono- and diglycerides', 'monoglyceride', 'โมโนและไดกลีเซอไรด์']),
+    ('E407', 'Carrageenan', 'thickener', 4, ARRAY['carrageenan', 'คาราจีแนน']),
+    ('E415', 'Xanthan gum', 'thickener', 4, ARRAY['xanthan', 'แซนแทนกัม']),
+    ('E1422', 'Acetylated distarch adipate', 'modified_starch', 4, ARRAY['modified starch', 'แป้งดัดแปร']),
+    ('E150d', 'Sulphite ammonia caramel', 'colour', 4, ARRAY['caramel colour', 'caramel color', 'สีคาราเมล']),
+    ('E102', 'Tartrazine', 'colour', 4, ARRAY['tartrazine', 'ทาร์ทราซีน']),
+    ('E211', 'Sodium benzoate', 'preservative', 3, ARRAY['sodium benzoate', 'โซเดียมเบนโซเอต']),
+    ('E202', 'Potassium sorbate', 'preservative', 3, ARRAY['potassium sorbate', 'โพแทสเซียมซอร์เบต']),
+    (NULL, 'Maltodextrin', 'industrial_ingredient', 4, ARRAY['maltodextrin', 'มอลโทเด็กซ์ทริน']),
+    (NULL, 'High fructose corn syrup', 'industrial_ingredient', 4, ARRAY['high fructose corn syrup', 'glucose-fructose syrup', 'ไซรัปข้าวโพด']),
+    (NULL, 'Hydrolysed protein', 'industrial_ingredient', 4, ARRAY['hydrolysed protein', 'hydrolyzed protein', 'โปรตีนไฮโดรไลซ์']),
+    (NULL, 'Flavouring', 'flavouring', 4, ARRAY['flavouring', 'flavoring', 'แต่งกลิ่น', 'กลิ่นสังเคราะห์'])
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,15 @@
+-- ใยอาหาร (g ต่อ serving) และสัดส่วนผัก/ผลไม้/ถั่ว (%) ใช้เป็นคะแนนบวกของ Nutri-Score; NULL = ไม่ทราบ
+ALTER TABLE products
+    ADD COLUMN IF NOT EXISTS fibre REAL CHECK (fibre >= 0),
+    ADD COLUMN IF NOT EXISTS fruit_veg_percent REAL CHECK (fruit_veg_percent BETWEEN 0 AND 100);
+
+-- Nutri-Score ที่คำนวณโดย service ทุกครั้งที่ product ถูกเขียน
+--  - `nutri_score_grade` / `nutri_score_points`: ใช้ filter และ sort ใน product list
+--  - `nutri_score`: คะแนนแยกรายองค์ประกอบ (breakdown) สำหรับแสดงผล
+--  - NULL ทั้งหมดเมื่อคำนวณไม่ได้ (ไม่มี serving size จึงหาค่าต่อ 100 g/ml ไม่ได้)
+ALTER TABLE products
+    ADD COLUMN IF NOT EXISTS nutri_score_grade CHAR(1) CHECK (nutri_score_grade IN ('A', 'B', 'C', 'D', 'E')),
+    ADD COLUMN IF NOT EXISTS nutri_score_points SMALLINT,
+    ADD COLUMN IF NOT EXISTS nutri_score JSONB;
+
+CREATE INDEX IF NOT EXISTS idx_products_nutri_score_grade ON products (nutri_score_grade);
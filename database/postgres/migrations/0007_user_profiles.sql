@@ -0,0 +1,13 @@
+-- ข้อมูลของผู้ใช้ที่ใช้คำนวณ BMR (Mifflin-St Jeor), TDEE และเป้าหมายพลังงาน/สารอาหารต่อวัน
+--  - ค่าเป้าหมายคำนวณโดย service ทุกครั้งที่อ่าน จึงไม่เก็บใน table
+CREATE TABLE IF NOT EXISTS user_profiles (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name VARCHAR(255) NOT NULL,
+    age SMALLINT NOT NULL CHECK (age BETWEEN 18 AND 100),
+    sex VARCHAR(6) NOT NULL CHECK (sex IN ('male', 'female')),
+    height_cm REAL NOT NULL CHECK (height_cm > 0),
+    weight_kg REAL NOT NULL CHECK (weight_kg > 0),
+    activity_level VARCHAR(16) NOT NULL DEFAULT 'moderate'
+        CHECK (activity_level IN ('sedentary', 'light', 'moderate', 'active', 'very_active')),
+    goal VARCHAR(8) NOT NULL DEFAULT 'maintain' CHECK (goal IN ('lose', 'maintain', 'gain'))
+);
@@ -0,0 +1,5 @@
+-- คะแนนความหนาแน่นสารอาหารต่อ serving (แบบ NRF: %Thai RDI ของสารอาหารที่ดี − สารอาหารที่ควรจำกัด)
+--  - คำนวณโดย service ทุกครั้งที่ product ถูกเขียน ใช้จัดอันดับ `nutrient_density_per_baht`
+--  - NULL สำหรับ product เดิมจนกว่าจะเรียก `POST /api/v1/admin/products/recompute-scores`
+ALTER TABLE products
+    ADD COLUMN IF NOT EXISTS nutrient_density REAL;
@@ -0,0 +1,19 @@
+-- เขตเวลา (IANA เช่น `Asia/Bangkok`) ใช้ตัดขอบวันของบันทึกอาหาร และงบค่าอาหารต่อวัน (THB, NULL = ไม่ได้ตั้ง)
+ALTER TABLE user_profiles
+    ADD COLUMN IF NOT EXISTS timezone VARCHAR(64) NOT NULL DEFAULT 'Asia/Bangkok',
+    ADD COLUMN IF NOT EXISTS daily_budget REAL CHECK (daily_budget >= 0);
+
+-- บันทึกสิ่งที่ผู้ใช้กินจริง
+--  - `quantity` เป็นจำนวน serving หรือกรัม (มิลลิลิตรสำหรับเครื่องดื่ม) ตาม `quantity_unit`
+--  - ค่าสารอาหารและราคาอ่านจาก `products` ทุกครั้ง จึงห้ามลบ product ที่ยังมีบันทึกอ้างถึง
+CREATE TABLE IF NOT EXISTS food_log_entries (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    profile_id UUID NOT NULL REFERENCES user_profiles(id) ON DELETE CASCADE,
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE RESTRICT,
+    quantity REAL NOT NULL CHECK (quantity > 0),
+    quantity_unit VARCHAR(10) NOT NULL DEFAULT 'servings' CHECK (quantity_unit IN ('servings', 'grams')),
+    meal_slot VARCHAR(10) NOT NULL CHECK (meal_slot IN ('breakfast', 'lunch', 'dinner', 'snack')),
+    eaten_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_food_log_entries_profile_eaten_at ON food_log_entries (profile_id, eaten_at);
@@ -0,0 +1,20 @@
+-- มื้ออาหารที่ประกอบจาก product หลายชิ้น (เช่น ข้าวกล่อง + เครื่องดื่ม + ขนม)
+--  - ค่าสารอาหารและราคารวมคำนวณจาก `products` ทุกครั้งที่อ่าน จึงไม่เก็บใน table
+CREATE TABLE IF NOT EXISTS meals (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name VARCHAR(255) NOT NULL
+);
+
+--  - `position`: ลำดับของ item ในมื้อ (เริ่มที่ 1)
+--  - `quantity` เป็นจำนวน serving หรือกรัม (มิลลิลิตรสำหรับเครื่องดื่ม) ตาม `quantity_unit`
+--  - ห้ามลบ product ที่ยังเป็นส่วนหนึ่งของมื้อ
+CREATE TABLE IF NOT EXISTS meal_items (
+    meal_id UUID NOT NULL REFERENCES meals(id) ON DELETE CASCADE,
+    position INT NOT NULL CHECK (position > 0),
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE RESTRICT,
+    quantity REAL NOT NULL CHECK (quantity > 0),
+    quantity_unit VARCHAR(10) NOT NULL DEFAULT 'servings' CHECK (quantity_unit IN ('servings', 'grams')),
+    PRIMARY KEY (meal_id, position)
+);
+
+CREATE INDEX IF NOT EXISTS idx_meal_items_product_id ON meal_items (product_id);
@@ -0,0 +1,9 @@
+-- บาร์โค้ด EAN-13/UPC-A ของ product (หนึ่ง product มีได้หลายบาร์โค้ด เช่น แต่ละขนาดบรรจุ)
+--  - เก็บเป็น EAN-13 เสมอ: UPC-A (12 หลัก) เติม 0 ข้างหน้า จึงเป็นรหัสเดียวกับ EAN-13 ที่ขึ้นต้นด้วย 0
+--  - บาร์โค้ดหนึ่งอ้างถึง product ได้เพียงตัวเดียว
+CREATE TABLE IF NOT EXISTS product_barcodes (
+    barcode VARCHAR(13) PRIMARY KEY CHECK (barcode ~ '^[0-9]{13}$'),
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_product_barcodes_product_id ON product_barcodes (product_id);
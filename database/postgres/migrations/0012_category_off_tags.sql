@@ -0,0 +1,3 @@
+-- category tag ของ Open Food Facts (เช่น `en:instant-noodles`) ที่ importer จับคู่เข้ากับ category นี้
+-- นอกเหนือจากการจับคู่ด้วยชื่อ (`en:snacks` ↔ `Snacks`)
+ALTER TABLE categories ADD COLUMN IF NOT EXISTS off_tags TEXT[] NOT NULL DEFAULT ARRAY[]::TEXT[];